@@ -1,10 +1,12 @@
 //! Address analysis routes.
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::routing::get;
 use axum::{Json, Router};
+use serde::Deserialize;
 
 use flare_common::error::AppError;
+use flare_common::types::Chain;
 use flare_engine::analyzer::{AddressAnalyzer, AddressClassification};
 
 use crate::state::AppState;
@@ -13,14 +15,26 @@ pub fn router() -> Router<AppState> {
     Router::new().route("/api/addresses/{address}/analyze", get(analyze_address))
 }
 
-/// GET /api/addresses/:address/analyze — Classify an address and return subscribable events.
+/// Query parameters for address analysis.
+#[derive(Debug, Deserialize)]
+pub struct AnalyzeQuery {
+    /// Chain the address lives on (default: flare)
+    pub chain: Option<String>,
+}
+
+/// GET /api/addresses/:address/analyze?chain= — Classify an address and return subscribable events.
 ///
 /// This endpoint is public (no auth required) to allow discovery before sign-up.
 async fn analyze_address(
     State(state): State<AppState>,
     Path(address): Path<String>,
+    Query(query): Query<AnalyzeQuery>,
 ) -> Result<Json<AddressClassification>, AppError> {
-    // Default to "flare" chain; could be extended with query param
-    let classification = AddressAnalyzer::classify(&address, "flare", &state.pool).await?;
+    let chain: Chain = match query.chain.as_deref() {
+        Some(chain) => chain.parse().map_err(AppError::Validation)?,
+        None => Chain::Flare,
+    };
+    let classification =
+        AddressAnalyzer::classify(&address, &chain.to_string(), &state.pool).await?;
     Ok(Json(classification))
 }
@@ -0,0 +1,167 @@
+//! FAsset agent vault health routes.
+//!
+//! Served from the `agent_vault_snapshots` the engine's CR calculator writes.
+
+use axum::extract::{Path, Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use flare_common::error::AppError;
+use flare_common::types::{AgentVaultSnapshot, Chain};
+
+use crate::state::AppState;
+
+/// Default history window when `from` is not given.
+const DEFAULT_HISTORY_WINDOW_HOURS: i64 = 24;
+
+/// Default bucket width of the history series, in seconds.
+const DEFAULT_HISTORY_INTERVAL_SECS: i64 = 3600;
+
+/// Most buckets a history request may span.
+const MAX_HISTORY_BUCKETS: i64 = 10_000;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/agents/{address}/health", get(agent_health))
+        .route(
+            "/api/agents/{address}/health/history",
+            get(agent_health_history),
+        )
+}
+
+/// Query parameters for the current agent health.
+#[derive(Debug, Deserialize)]
+pub struct HealthQuery {
+    /// Chain of the agent (default: flare)
+    pub chain: Option<String>,
+}
+
+/// Query parameters for the agent health history.
+#[derive(Debug, Deserialize)]
+pub struct HealthHistoryQuery {
+    /// Chain of the agent (default: flare)
+    pub chain: Option<String>,
+    /// Start of the series (default: 24 hours before `to`)
+    pub from: Option<DateTime<Utc>>,
+    /// End of the series (default: now)
+    pub to: Option<DateTime<Utc>>,
+    /// Bucket width in seconds (default: 3600)
+    pub interval: Option<i64>,
+}
+
+/// One bucket of the downsampled health series.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct HealthPoint {
+    /// Start of the bucket
+    pub time: DateTime<Utc>,
+    /// CR of the last snapshot in the bucket
+    pub cr: Option<f64>,
+    /// Lowest CR in the bucket
+    pub min_cr: Option<f64>,
+    pub vault_cr: Option<f64>,
+    pub pool_cr: Option<f64>,
+    pub vault_collateral_usd: Option<f64>,
+    pub pool_collateral_usd: Option<f64>,
+    pub minted_value_usd: Option<f64>,
+    pub minted_lots: i64,
+    /// Snapshots in the bucket
+    pub samples: i64,
+}
+
+fn parse_chain(chain: Option<&str>) -> Result<Chain, AppError> {
+    match chain {
+        Some(chain) => chain.parse().map_err(AppError::Validation),
+        None => Ok(Chain::Flare),
+    }
+}
+
+/// GET /api/agents/:address/health?chain= — Latest health snapshot of an agent vault.
+///
+/// Public, like address analysis: it only shows on-chain state.
+async fn agent_health(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Query(query): Query<HealthQuery>,
+) -> Result<Json<AgentVaultSnapshot>, AppError> {
+    let chain = parse_chain(query.chain.as_deref())?;
+
+    let snapshot: Option<AgentVaultSnapshot> = sqlx::query_as(
+        r#"
+        SELECT chain, agent, fasset, cr, vault_cr, pool_cr, vault_collateral, pool_collateral,
+               vault_collateral_usd, pool_collateral_usd, minted_value_usd,
+               minted_lots, reserved_lots, redeeming_lots, block_number, recorded_at
+        FROM agent_vault_snapshots
+        WHERE chain = $1 AND agent = $2
+        ORDER BY recorded_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(chain.to_string())
+    .bind(address.to_lowercase())
+    .fetch_optional(&state.pool)
+    .await?;
+
+    snapshot.map(Json).ok_or_else(|| {
+        AppError::NotFound(format!(
+            "No health snapshots for agent {} on {}",
+            address, chain
+        ))
+    })
+}
+
+/// GET /api/agents/:address/health/history?chain=&from=&to=&interval= — Health
+/// series of an agent vault, one point per `interval` seconds with snapshots.
+async fn agent_health_history(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Query(query): Query<HealthHistoryQuery>,
+) -> Result<Json<Vec<HealthPoint>>, AppError> {
+    let chain = parse_chain(query.chain.as_deref())?;
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query
+        .from
+        .unwrap_or(to - Duration::hours(DEFAULT_HISTORY_WINDOW_HOURS));
+    let interval = query.interval.unwrap_or(DEFAULT_HISTORY_INTERVAL_SECS);
+
+    if interval <= 0 {
+        return Err(AppError::Validation(
+            "interval must be a positive number of seconds".to_string(),
+        ));
+    }
+    if from >= to {
+        return Err(AppError::Validation("from must be before to".to_string()));
+    }
+    if (to - from).num_seconds() / interval > MAX_HISTORY_BUCKETS {
+        return Err(AppError::Validation(format!(
+            "Range spans more than {} intervals; use a larger interval",
+            MAX_HISTORY_BUCKETS
+        )));
+    }
+
+    let points: Vec<HealthPoint> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON (time)
+               time, cr, MIN(cr) OVER w AS min_cr, vault_cr, pool_cr,
+               vault_collateral_usd, pool_collateral_usd, minted_value_usd, minted_lots,
+               COUNT(*) OVER w AS samples
+        FROM (
+            SELECT *, to_timestamp(floor(extract(epoch FROM recorded_at) / $5) * $5) AS time
+            FROM agent_vault_snapshots
+            WHERE chain = $1 AND agent = $2 AND recorded_at >= $3 AND recorded_at < $4
+        ) s
+        WINDOW w AS (PARTITION BY time)
+        ORDER BY time, recorded_at DESC
+        "#,
+    )
+    .bind(chain.to_string())
+    .bind(address.to_lowercase())
+    .bind(from)
+    .bind(to)
+    .bind(interval as f64)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(points))
+}
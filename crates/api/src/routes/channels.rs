@@ -0,0 +1,272 @@
+//! Notification channel routes — channel creation and ownership verification.
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::Html;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use flare_common::error::AppError;
+use flare_common::types::{ChannelType, NotificationChannel};
+use flare_engine::channel::{ChannelService, CreateChannelParams};
+use flare_notifier::email::verification_email;
+use flare_notifier::webhook::webhook_config;
+
+use crate::middleware::auth::AuthUser;
+use crate::routes::html_page;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/channels", post(create_channel))
+        .route("/api/channels", get(list_channels))
+        .route("/api/channels/{id}/verification", post(issue_verification))
+        .route(
+            "/api/channels/{id}/verification/confirm",
+            post(confirm_verification),
+        )
+        .route(
+            "/api/channels/email/confirm",
+            get(confirm_email_page).post(confirm_email),
+        )
+}
+
+/// Response for an issued verification.
+#[derive(Debug, Serialize)]
+pub struct VerificationResponse {
+    pub channel_id: Uuid,
+    pub channel_type: ChannelType,
+    /// Code to present through the channel (e.g., `/start <code>` to the Telegram bot).
+    /// Omitted for email and Discord channels, where the code is only sent to the
+    /// destination being verified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Address the confirmation email was sent to (email channels only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sent_to: Option<String>,
+    /// True when verification already completed (webhook challenge echoed)
+    pub verified: bool,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Body of `POST /api/channels/:id/verification/confirm`.
+#[derive(Debug, Deserialize)]
+pub struct ConfirmVerificationRequest {
+    pub code: String,
+}
+
+/// Query string for the email confirmation link.
+#[derive(Debug, Deserialize)]
+pub struct ConfirmEmailQuery {
+    pub code: String,
+}
+
+/// POST /api/channels — Create a new (unverified) notification channel.
+async fn create_channel(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(params): Json<CreateChannelParams>,
+) -> Result<Json<NotificationChannel>, AppError> {
+    let channel = ChannelService::create(
+        &state.pool,
+        auth.user_id,
+        &params,
+        state.config.webhook_allow_private_targets,
+    )
+    .await?;
+    Ok(Json(channel))
+}
+
+/// GET /api/channels — List all notification channels for the authenticated user.
+async fn list_channels(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<Vec<NotificationChannel>>, AppError> {
+    let channels = ChannelService::list_by_user(&state.pool, auth.user_id).await?;
+    Ok(Json(channels))
+}
+
+/// POST /api/channels/:id/verification — Issue a one-time verification code.
+///
+/// For Telegram channels, the user sends `/start <code>` to the bot to complete
+/// verification; the bot records the chat ID the code arrived from. For email
+/// channels, a confirmation link is emailed to the address instead, so only the
+/// inbox owner can complete verification. Discord channels get the code posted to
+/// their webhook or DM, and the owner submits it to
+/// `POST /api/channels/:id/verification/confirm`. Webhook channels are verified
+/// inline: the code is POSTed to the endpoint as a signed challenge, which must echo
+/// it back.
+async fn issue_verification(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<VerificationResponse>, AppError> {
+    let channel = ChannelService::get(&state.pool, id).await?;
+    let mailer = match channel.channel_type {
+        ChannelType::Email => Some(
+            state
+                .mailer
+                .clone()
+                .ok_or_else(|| AppError::Config("Email delivery is not configured".to_string()))?,
+        ),
+        _ => None,
+    };
+
+    let verification = ChannelService::issue_verification(&state.pool, id, auth.user_id).await?;
+
+    if channel.channel_type == ChannelType::Webhook {
+        let (url, secret) =
+            webhook_config(&channel.config).map_err(|e| AppError::Validation(e.to_string()))?;
+        state
+            .webhooks
+            .challenge(url, secret, &verification.code)
+            .await
+            .map_err(|e| {
+                AppError::Validation(format!("Webhook endpoint failed verification: {}", e))
+            })?;
+        ChannelService::confirm_verification(
+            &state.pool,
+            ChannelType::Webhook,
+            &verification.code,
+            serde_json::json!({}),
+        )
+        .await?;
+
+        return Ok(Json(VerificationResponse {
+            channel_id: verification.channel_id,
+            channel_type: verification.channel_type,
+            code: None,
+            sent_to: None,
+            verified: true,
+            expires_at: verification.expires_at,
+        }));
+    }
+
+    if channel.channel_type == ChannelType::Discord {
+        state
+            .discord
+            .send_verification(&channel.config, &verification.code)
+            .await
+            .map_err(|e| {
+                AppError::Validation(format!("Could not post the code to Discord: {}", e))
+            })?;
+
+        return Ok(Json(VerificationResponse {
+            channel_id: verification.channel_id,
+            channel_type: verification.channel_type,
+            code: None,
+            sent_to: None,
+            verified: false,
+            expires_at: verification.expires_at,
+        }));
+    }
+
+    let Some(mailer) = mailer else {
+        return Ok(Json(VerificationResponse {
+            channel_id: verification.channel_id,
+            channel_type: verification.channel_type,
+            code: Some(verification.code),
+            sent_to: None,
+            verified: false,
+            expires_at: verification.expires_at,
+        }));
+    };
+
+    let to = channel.config["email"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    let confirm_url = format!(
+        "{}/api/channels/email/confirm?code={}",
+        state.config.public_api_url.trim_end_matches('/'),
+        verification.code
+    );
+    mailer
+        .send(&verification_email(&to, &confirm_url))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to send confirmation email: {}", e)))?;
+
+    Ok(Json(VerificationResponse {
+        channel_id: verification.channel_id,
+        channel_type: verification.channel_type,
+        code: None,
+        sent_to: Some(to),
+        verified: false,
+        expires_at: verification.expires_at,
+    }))
+}
+
+/// POST /api/channels/:id/verification/confirm — Redeem a code posted to a Discord
+/// channel, proving the owner can read it.
+async fn confirm_verification(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(body): Json<ConfirmVerificationRequest>,
+) -> Result<Json<NotificationChannel>, AppError> {
+    ChannelService::confirm_delivered_code(&state.pool, id, auth.user_id, &body.code)
+        .await?
+        .map(Json)
+        .ok_or_else(|| AppError::Validation("Invalid or expired verification code".to_string()))
+}
+
+/// GET /api/channels/email/confirm?code= — Confirmation page for the emailed link.
+///
+/// Confirmation itself is a POST so that mail scanners prefetching the link
+/// cannot verify an address on the recipient's behalf.
+async fn confirm_email_page(Query(query): Query<ConfirmEmailQuery>) -> Html<String> {
+    html_page(
+        "Confirm email address",
+        &format!(
+            r#"<p>Confirm that FlareEmissary may send alerts to this address.</p>
+<form method="post" action="?code={}"><button type="submit">Confirm address</button></form>"#,
+            urlencode(&query.code)
+        ),
+    )
+}
+
+/// POST /api/channels/email/confirm?code= — Redeem an email verification code.
+async fn confirm_email(
+    State(state): State<AppState>,
+    Query(query): Query<ConfirmEmailQuery>,
+) -> Result<(StatusCode, Html<String>), AppError> {
+    let verified = ChannelService::confirm_verification(
+        &state.pool,
+        ChannelType::Email,
+        &query.code,
+        serde_json::json!({}),
+    )
+    .await?;
+
+    Ok(match verified {
+        Some(_) => (
+            StatusCode::OK,
+            html_page(
+                "Email confirmed",
+                "<p>✅ Your address is confirmed. FlareEmissary alerts will be delivered here.</p>",
+            ),
+        ),
+        None => (
+            StatusCode::BAD_REQUEST,
+            html_page(
+                "Link expired",
+                "<p>❌ This confirmation link is invalid or has expired. Request a new one from FlareEmissary.</p>",
+            ),
+        ),
+    })
+}
+
+/// Percent-encode everything but unreserved characters.
+fn urlencode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,50 @@
+//! Watched contract routes.
+
+use axum::extract::{Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+
+use flare_common::error::AppError;
+use flare_common::types::{Chain, WatchedContract};
+
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/api/contracts", get(list_watched_contracts))
+}
+
+/// Query parameters for listing watched contracts.
+#[derive(Debug, Deserialize)]
+pub struct WatchedContractsQuery {
+    /// Only contracts on this chain
+    pub chain: Option<String>,
+}
+
+/// GET /api/contracts?chain= — Registry contracts the indexer is currently watching.
+///
+/// Public, like address analysis: it shows which contracts alerts can come from.
+async fn list_watched_contracts(
+    State(state): State<AppState>,
+    Query(query): Query<WatchedContractsQuery>,
+) -> Result<Json<Vec<WatchedContract>>, AppError> {
+    let chain: Option<Chain> = query
+        .chain
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .map_err(AppError::Validation)?;
+
+    let contracts: Vec<WatchedContract> = sqlx::query_as(
+        r#"
+        SELECT chain, contract_name, address, from_block, resolved_at
+        FROM contract_address_history
+        WHERE to_block IS NULL AND ($1::text IS NULL OR chain = $1)
+        ORDER BY chain, contract_name
+        "#,
+    )
+    .bind(chain.map(|c| c.to_string()))
+    .fetch_all(&state.pool)
+    .await?;
+    Ok(Json(contracts))
+}
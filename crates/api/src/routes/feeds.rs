@@ -0,0 +1,31 @@
+//! FTSO feed subscription routes.
+//!
+//! Feed-scoped subscriptions are listed, updated and deleted through the
+//! regular subscription routes.
+
+use axum::extract::State;
+use axum::routing::post;
+use axum::{Json, Router};
+
+use flare_common::error::AppError;
+use flare_common::types::Subscription;
+use flare_engine::subscription::{CreateFeedSubscriptionParams, SubscriptionService};
+
+use crate::middleware::auth::AuthUser;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/api/feeds/subscriptions", post(create_feed_subscription))
+}
+
+/// POST /api/feeds/subscriptions — Subscribe to price thresholds, price moves or
+/// staleness of an FTSO feed.
+async fn create_feed_subscription(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Json(params): Json<CreateFeedSubscriptionParams>,
+) -> Result<Json<Subscription>, AppError> {
+    let subscription =
+        SubscriptionService::create_for_feed(&state.pool, auth.user_id, &params).await?;
+    Ok(Json(subscription))
+}
@@ -1,9 +1,16 @@
 pub mod addresses;
+pub mod agents;
 pub mod auth;
+pub mod channels;
+pub mod contracts;
+pub mod feeds;
 pub mod health;
+pub mod notifications;
 pub mod subscriptions;
+pub mod unsubscribe;
 
 use axum::Router;
+use axum::response::Html;
 
 use crate::state::AppState;
 
@@ -13,6 +20,24 @@ pub fn create_router(state: AppState) -> Router {
         .merge(health::router())
         .merge(auth::router())
         .merge(subscriptions::router())
+        .merge(channels::router())
+        .merge(notifications::router())
+        .merge(unsubscribe::router())
         .merge(addresses::router())
+        .merge(contracts::router())
+        .merge(agents::router())
+        .merge(feeds::router())
         .with_state(state)
 }
+
+/// Minimal standalone HTML page for routes opened from email links.
+pub(crate) fn html_page(title: &str, body: &str) -> Html<String> {
+    Html(format!(
+        r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>{title} · FlareEmissary</title></head>
+<body style="font-family:sans-serif;max-width:480px;margin:48px auto;padding:0 16px">
+<h2>{title}</h2>
+{body}
+</body></html>"#
+    ))
+}
@@ -0,0 +1,83 @@
+//! Notification routes — dead-letter inspection and re-drive.
+
+use axum::extract::{Path, Query, State};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use flare_common::error::AppError;
+use flare_common::queue;
+use flare_common::types::Notification;
+use flare_engine::notification::NotificationService;
+
+use crate::middleware::auth::AuthUser;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/api/notifications/dead-letters", get(list_dead_letters))
+        .route(
+            "/api/notifications/dead-letters/redrive",
+            post(redrive_all_dead_letters),
+        )
+        .route("/api/notifications/{id}/redrive", post(redrive_dead_letter))
+}
+
+/// Query parameters for listing dead letters.
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterQuery {
+    pub limit: Option<i64>,
+}
+
+/// GET /api/notifications/dead-letters — List the user's dead-lettered notifications.
+async fn list_dead_letters(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Query(query): Query<DeadLetterQuery>,
+) -> Result<Json<Vec<Notification>>, AppError> {
+    let notifications = NotificationService::list_dead_letters(
+        &state.pool,
+        auth.user_id,
+        query.limit.unwrap_or(100),
+    )
+    .await?;
+    Ok(Json(notifications))
+}
+
+/// POST /api/notifications/:id/redrive — Reset a dead letter and queue it for delivery.
+async fn redrive_dead_letter(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Notification>, AppError> {
+    let notification = NotificationService::redrive(&state.pool, id, auth.user_id).await?;
+    enqueue(&state, &[notification.id]).await;
+    Ok(Json(notification))
+}
+
+/// POST /api/notifications/dead-letters/redrive — Re-drive all of the user's dead letters.
+async fn redrive_all_dead_letters(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let ids = NotificationService::redrive_all(&state.pool, auth.user_id).await?;
+    enqueue(&state, &ids).await;
+    Ok(Json(serde_json::json!({ "redriven": ids.len() })))
+}
+
+/// Push re-driven notifications onto the delivery queue.
+///
+/// A failed push is not fatal: the notifier's stale sweep re-enqueues pending rows.
+async fn enqueue(state: &AppState, ids: &[Uuid]) {
+    let mut redis = state.redis.clone();
+    for id in ids {
+        if let Err(e) = queue::enqueue_notification(&mut redis, *id).await {
+            tracing::warn!(
+                notification_id = %id,
+                error = %e,
+                "Failed to enqueue re-driven notification; stale sweep will retry"
+            );
+        }
+    }
+}
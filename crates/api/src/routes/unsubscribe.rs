@@ -0,0 +1,60 @@
+//! One-click unsubscribe routes for links embedded in alert emails.
+//!
+//! The token is signed by the notifier (`SubscriptionService::unsubscribe_token`),
+//! so no login is needed. GET shows a confirmation page; POST (also used by mail
+//! clients for RFC 8058 one-click unsubscribe) deactivates the subscription.
+
+use axum::Router;
+use axum::extract::{Query, State};
+use axum::response::Html;
+use axum::routing::get;
+use serde::Deserialize;
+
+use flare_common::error::AppError;
+use flare_engine::subscription::SubscriptionService;
+
+use crate::routes::html_page;
+use crate::state::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/api/unsubscribe", get(unsubscribe_page).post(unsubscribe))
+}
+
+/// Query string carrying the signed unsubscribe token.
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeQuery {
+    pub token: String,
+}
+
+/// GET /api/unsubscribe?token= — Confirmation page.
+async fn unsubscribe_page(
+    State(state): State<AppState>,
+    Query(query): Query<UnsubscribeQuery>,
+) -> Result<Html<String>, AppError> {
+    // Reject bad links up front rather than after the user clicks through
+    SubscriptionService::verify_unsubscribe_token(&query.token, &state.config.unsubscribe_key())
+        .ok_or_else(|| AppError::Auth("Invalid unsubscribe link".to_string()))?;
+
+    Ok(html_page(
+        "Unsubscribe",
+        &format!(
+            r#"<p>Stop receiving alerts for this FlareEmissary subscription?</p>
+<form method="post" action="?token={}"><button type="submit">Unsubscribe</button></form>"#,
+            query.token
+        ),
+    ))
+}
+
+/// POST /api/unsubscribe?token= — Deactivate the subscription.
+async fn unsubscribe(
+    State(state): State<AppState>,
+    Query(query): Query<UnsubscribeQuery>,
+) -> Result<Html<String>, AppError> {
+    SubscriptionService::unsubscribe(&state.pool, &query.token, &state.config.unsubscribe_key())
+        .await?;
+
+    Ok(html_page(
+        "Unsubscribed",
+        "<p>You have been unsubscribed and will no longer receive these alerts.</p>",
+    ))
+}
@@ -1,6 +1,11 @@
 //! Shared application state for the Axum API server.
 
+use std::sync::Arc;
+
 use flare_common::config::AppConfig;
+use flare_notifier::discord::DiscordSender;
+use flare_notifier::email::ResendClient;
+use flare_notifier::webhook::WebhookSender;
 use redis::aio::ConnectionManager;
 use sqlx::PgPool;
 
@@ -10,14 +15,37 @@ pub struct AppState {
     pub pool: PgPool,
     pub redis: ConnectionManager,
     pub config: AppConfig,
+    /// Email client for confirm-your-address emails; `None` when Resend isn't configured
+    pub mailer: Option<Arc<ResendClient>>,
+    /// Sends the challenge request that verifies a webhook endpoint
+    pub webhooks: Arc<WebhookSender>,
+    /// Posts verification codes to Discord channels
+    pub discord: Arc<DiscordSender>,
 }
 
 impl AppState {
     pub fn new(pool: PgPool, redis: ConnectionManager, config: AppConfig) -> Self {
+        let mailer = match (&config.resend_api_key, &config.email_from) {
+            (Some(api_key), Some(from)) => Some(Arc::new(ResendClient::new(
+                &config.resend_api_base_url,
+                api_key,
+                from,
+            ))),
+            _ => None,
+        };
+        let webhooks = Arc::new(WebhookSender::new(config.webhook_allow_private_targets));
+        let discord = Arc::new(DiscordSender::new(
+            &config.discord_api_base_url,
+            config.discord_bot_token.clone(),
+        ));
+
         Self {
             pool,
             redis,
             config,
+            mailer,
+            webhooks,
+            discord,
         }
     }
 }
@@ -8,6 +8,8 @@
 //!   cargo test -p flare-api --test integration -- --ignored --nocapture
 //! ```
 
+use std::sync::{Arc, Mutex};
+
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
 use sqlx::PgPool;
@@ -17,6 +19,12 @@ use uuid::Uuid;
 use flare_api::routes::create_router;
 use flare_api::state::AppState;
 use flare_common::config::AppConfig;
+use flare_common::types::DeliveryStatus;
+use flare_engine::subscription::SubscriptionService;
+use flare_notifier::discord::DiscordSender;
+use flare_notifier::email::ResendClient;
+use flare_notifier::sender::SenderRegistry;
+use flare_notifier::worker::NotificationWorker;
 
 // ============================================================
 // Helpers
@@ -59,18 +67,40 @@ async fn setup(pool: &PgPool) {
 /// Create a test AppConfig with a specific JWT secret.
 fn test_config() -> AppConfig {
     AppConfig {
-        flare_rpc_url: "http://unused".to_string(),
-        flare_rpc_fallback_url: None,
+        indexer_chains: Vec::new(),
         database_url: "unused".to_string(),
         redis_url: "redis://localhost:6379".to_string(),
         indexer_poll_interval_ms: 1500,
         indexer_reorg_window: 10,
+        indexer_backfill_chunk_size: 1000,
+        indexer_catchup_threshold: 50,
+        indexer_catchup_window: 500,
+        indexer_registry_refresh_blocks: 1000,
+        indexer_generic_subscriptions: false,
+        indexer_generic_refresh_secs: 30,
+        indexer_rpc_health_interval_secs: 10,
+        indexer_rpc_max_lag_blocks: 5,
+        indexer_metrics_addr: None,
+        engine_poll_interval_ms: 1000,
+        engine_batch_size: 500,
+        engine_retraction_notices: true,
+        engine_snapshot_interval_secs: 300,
+        engine_vault_refresh_blocks: 200,
         jwt_secret: "test-jwt-secret-for-integration-tests".to_string(),
         jwt_expiry_hours: 24,
+        unsubscribe_secret: None,
+        notifier_workers: 1,
+        notifier_max_attempts: 5,
+        notifier_retry_base_secs: 30,
         telegram_bot_token: None,
+        telegram_api_base_url: "http://unused".to_string(),
         discord_bot_token: None,
+        discord_api_base_url: "http://unused".to_string(),
         resend_api_key: None,
+        resend_api_base_url: "http://unused".to_string(),
         email_from: None,
+        public_api_url: "http://localhost:3000".to_string(),
+        webhook_allow_private_targets: true,
         db_max_connections: 5,
     }
 }
@@ -288,10 +318,11 @@ async fn test_subscription_requires_auth(pool: PgPool) {
 #[ignore]
 async fn test_address_analyze_endpoint(pool: PgPool) {
     setup(&pool).await;
-    let state = build_test_state(pool).await;
+    let state = build_test_state(pool.clone()).await;
     let app = create_router(state);
 
     let response = app
+        .clone()
         .oneshot(
             Request::builder()
                 .uri("/api/addresses/0xnew_contract/analyze")
@@ -309,6 +340,287 @@ async fn test_address_analyze_endpoint(pool: PgPool) {
     assert_eq!(json["address"], "0xnew_contract");
     assert_eq!(json["label"], "Smart Contract");
     assert!(!json["subscribable_events"].as_array().unwrap().is_empty());
+
+    // The same address on a testnet is tracked separately
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/addresses/0xnew_contract/analyze?chain=coston2")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let chains: Vec<(String,)> = sqlx::query_as(
+        "SELECT chain FROM monitored_addresses WHERE address = '0xnew_contract' ORDER BY chain",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        chains,
+        vec![("coston2".to_string(),), ("flare".to_string(),)]
+    );
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/addresses/0xnew_contract/analyze?chain=ethereum")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_watched_contracts_endpoint(pool: PgPool) {
+    setup(&pool).await;
+    sqlx::query(
+        r#"
+        INSERT INTO contract_address_history (chain, contract_name, address, from_block, to_block)
+        VALUES ('flare', 'FtsoManager', '0xold', 100, 199),
+               ('flare', 'FtsoManager', '0xnew', 200, NULL),
+               ('flare', 'AssetManager', '0xasset', 100, NULL),
+               ('coston2', 'FtsoManager', '0xtestnet', 50, NULL)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    let app = create_router(build_test_state(pool).await);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/contracts?chain=flare")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let contracts: Vec<(&str, &str, i64)> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| {
+            (
+                c["contract_name"].as_str().unwrap(),
+                c["address"].as_str().unwrap(),
+                c["from_block"].as_i64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        contracts,
+        vec![
+            ("AssetManager", "0xasset", 100),
+            ("FtsoManager", "0xnew", 200)
+        ]
+    );
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/contracts")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 3);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/contracts?chain=ethereum")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_agent_health_endpoints(pool: PgPool) {
+    setup(&pool).await;
+    let agent = "0x00000000000000000000000000000000000a11ce";
+    let start = chrono::DateTime::parse_from_rfc3339("2025-05-01T10:00:00Z")
+        .unwrap()
+        .to_utc();
+    for (minutes, cr) in [(10, 2.0), (50, 1.7), (70, 1.9)] {
+        sqlx::query(
+            r#"
+            INSERT INTO agent_vault_snapshots (
+                chain, agent, fasset, cr, vault_cr, vault_collateral, pool_collateral,
+                vault_collateral_usd, minted_value_usd, minted_lots, reserved_lots,
+                redeeming_lots, block_number, recorded_at
+            )
+            VALUES ('flare', $1, 'FXRP', $2, $2, 100, 0, 100, 100 / $2, 10, 0, 0, $3, $4)
+            "#,
+        )
+        .bind(agent)
+        .bind(cr)
+        .bind(1000 + minutes)
+        .bind(start + chrono::Duration::minutes(minutes))
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+    let app = create_router(build_test_state(pool).await);
+
+    let get = |uri: String| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap_or_default(),
+            )
+        }
+    };
+
+    // Addresses are matched case-insensitively
+    let (status, json) = get(format!("/api/agents/{}/health", agent.to_uppercase())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["cr"], 1.9);
+    assert_eq!(json["fasset"], "FXRP");
+    assert_eq!(json["block_number"], 1070);
+
+    let (status, json) = get(format!(
+        "/api/agents/{}/health/history?from=2025-05-01T10:00:00Z&to=2025-05-01T12:00:00Z&interval=3600",
+        agent
+    ))
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let points = json.as_array().unwrap();
+    assert_eq!(points.len(), 2);
+    assert_eq!(points[0]["cr"], 1.7);
+    assert_eq!(points[0]["min_cr"], 1.7);
+    assert_eq!(points[0]["samples"], 2);
+    assert_eq!(points[1]["cr"], 1.9);
+    assert_eq!(points[1]["samples"], 1);
+
+    let (status, _) = get(format!("/api/agents/{}/health/history?interval=0", agent)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = get("/api/agents/0xunknown/health".to_string()).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_feed_subscription_endpoint(pool: PgPool) {
+    setup(&pool).await;
+    let (user_id, token) = create_user_with_token(&pool).await;
+
+    let chan_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO notification_channels (id, user_id, channel_type, config) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(chan_id)
+    .bind(user_id)
+    .bind("telegram")
+    .bind(serde_json::json!({"chat_id": "12345"}))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let state = build_test_state(pool.clone()).await;
+    let post = |body: serde_json::Value| {
+        let app = create_router(state.clone());
+        let token = token.clone();
+        async move {
+            let response = app
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/api/feeds/subscriptions")
+                        .header("authorization", format!("Bearer {}", token))
+                        .header("content-type", "application/json")
+                        .body(Body::from(body.to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap_or_default(),
+            )
+        }
+    };
+
+    let (status, created) = post(serde_json::json!({
+        "feed": "flr/usd",
+        "channel_id": chan_id,
+        "event_type": "price_move",
+        "threshold_config": { "deviation_pct": 5.0, "window_secs": 900 }
+    }))
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(created["event_type"], "PriceMove");
+
+    // The feed is tracked by its normalized name
+    let (address, address_type): (String, String) =
+        sqlx::query_as("SELECT address, address_type FROM monitored_addresses WHERE id = $1")
+            .bind(Uuid::parse_str(created["address_id"].as_str().unwrap()).unwrap())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(address, "FLR/USD");
+    assert_eq!(address_type, "ftso_feed");
+
+    let (status, _) = post(serde_json::json!({
+        "feed": "FLR/USD",
+        "channel_id": chan_id,
+        "event_type": "price_move"
+    }))
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = post(serde_json::json!({
+        "feed": "FLRUSD",
+        "channel_id": chan_id,
+        "event_type": "feed_stale"
+    }))
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = post(serde_json::json!({
+        "feed": "FLR/USD",
+        "channel_id": chan_id,
+        "event_type": "generic_event"
+    }))
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
 }
 
 #[sqlx::test]
@@ -331,3 +643,687 @@ async fn test_invalid_jwt_rejected(pool: PgPool) {
 
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 }
+
+#[sqlx::test]
+#[ignore]
+async fn test_channel_create_and_issue_verification(pool: PgPool) {
+    setup(&pool).await;
+    let (_, token) = create_user_with_token(&pool).await;
+    let (_, other_token) = create_user_with_token(&pool).await;
+    let state = build_test_state(pool).await;
+
+    // 1. Create a Telegram channel — starts unverified
+    let app = create_router(state.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/channels")
+                .header("authorization", format!("Bearer {}", token))
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"channel_type":"telegram"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let channel: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(channel["verified"], false);
+    let channel_id = channel["id"].as_str().unwrap();
+
+    // 2. Issue a verification code
+    let app = create_router(state.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/channels/{}/verification", channel_id))
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let verification: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(verification["code"].as_str().unwrap().len(), 8);
+
+    // 3. Another user cannot issue codes for this channel
+    let app = create_router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/channels/{}/verification", channel_id))
+                .header("authorization", format!("Bearer {}", other_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+/// Serve a fake Resend API that records each email's plain-text body.
+async fn stub_resend() -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+    let sent: std::sync::Arc<std::sync::Mutex<Vec<String>>> = Default::default();
+    let recorded = sent.clone();
+    let router = axum::Router::new().route(
+        "/emails",
+        axum::routing::post(
+            move |axum::Json(body): axum::Json<serde_json::Value>| async move {
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push(body["text"].as_str().unwrap().to_string());
+                axum::Json(serde_json::json!({ "id": "email-1" }))
+            },
+        ),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    (format!("http://{}", addr), sent)
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_email_channel_confirmed_via_emailed_link(pool: PgPool) {
+    setup(&pool).await;
+    let (_, token) = create_user_with_token(&pool).await;
+
+    let (resend_url, sent) = stub_resend().await;
+    let mut state = build_test_state(pool.clone()).await;
+    state.mailer = Some(std::sync::Arc::new(ResendClient::new(
+        resend_url,
+        "re_test",
+        "alerts@example.com",
+    )));
+
+    // 1. Create the email channel
+    let app = create_router(state.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/api/channels")
+                .header("authorization", format!("Bearer {}", token))
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"channel_type":"email","config":{"email":"Alice@Example.com"}}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let channel: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(channel["config"]["email"], "alice@example.com");
+    let channel_id = channel["id"].as_str().unwrap().to_string();
+
+    // 2. Request verification — the code goes to the inbox, not the API caller
+    let app = create_router(state.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/channels/{}/verification", channel_id))
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let verification: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(verification.get("code").is_none());
+    assert_eq!(verification["sent_to"], "alice@example.com");
+
+    let text = sent.lock().unwrap()[0].clone();
+    let confirm_path = text
+        .lines()
+        .find_map(|line| line.strip_prefix("http://localhost:3000"))
+        .expect("confirmation link in email")
+        .to_string();
+
+    // 3. An immediate resend is throttled
+    let app = create_router(state.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/channels/{}/verification", channel_id))
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(sent.lock().unwrap().len(), 1);
+
+    // 4. Opening the link does not verify by itself; submitting the form does
+    let app = create_router(state.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(&confirm_path)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let app = create_router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(&confirm_path)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (verified,): (bool,) =
+        sqlx::query_as("SELECT verified FROM notification_channels WHERE id = $1")
+            .bind(Uuid::parse_str(&channel_id).unwrap())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(verified);
+}
+
+/// Serve a webhook receiver that echoes verification challenges when `echo` is set.
+async fn stub_webhook_receiver(echo: bool) -> String {
+    let router = axum::Router::new().route(
+        "/hook",
+        axum::routing::post(
+            move |axum::Json(body): axum::Json<serde_json::Value>| async move {
+                let challenge = if echo {
+                    body["challenge"].clone()
+                } else {
+                    serde_json::Value::Null
+                };
+                axum::Json(serde_json::json!({ "challenge": challenge }))
+            },
+        ),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    format!("http://{}/hook", addr)
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_webhook_channel_verified_by_challenge(pool: PgPool) {
+    setup(&pool).await;
+    let (_, token) = create_user_with_token(&pool).await;
+    let state = build_test_state(pool.clone()).await;
+
+    for (echo, expected_status) in [(false, StatusCode::BAD_REQUEST), (true, StatusCode::OK)] {
+        let url = stub_webhook_receiver(echo).await;
+
+        let app = create_router(state.clone());
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/channels")
+                    .header("authorization", format!("Bearer {}", token))
+                    .header("content-type", "application/json")
+                    .body(Body::from(format!(
+                        r#"{{"channel_type":"webhook","config":{{"url":"{}"}}}}"#,
+                        url
+                    )))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let channel: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(
+            channel["config"]["secret"]
+                .as_str()
+                .unwrap()
+                .starts_with("whsec_")
+        );
+        let channel_id = channel["id"].as_str().unwrap().to_string();
+
+        let app = create_router(state.clone());
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/api/channels/{}/verification", channel_id))
+                    .header("authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), expected_status);
+
+        let (verified,): (bool,) =
+            sqlx::query_as("SELECT verified FROM notification_channels WHERE id = $1")
+                .bind(Uuid::parse_str(&channel_id).unwrap())
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(verified, echo);
+    }
+}
+
+/// Serve a fake Discord API that accepts webhook posts and records their bodies.
+async fn stub_discord_api() -> (String, Arc<Mutex<Vec<serde_json::Value>>>) {
+    let posted: Arc<Mutex<Vec<serde_json::Value>>> = Arc::default();
+    let recorded = posted.clone();
+    let router = axum::Router::new().route(
+        "/webhooks/{id}/{token}",
+        axum::routing::post(
+            move |axum::Json(body): axum::Json<serde_json::Value>| async move {
+                recorded.lock().unwrap().push(body);
+                axum::Json(serde_json::json!({ "id": "1" }))
+            },
+        ),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    (format!("http://{}", addr), posted)
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_discord_channel_verified_and_delivered(pool: PgPool) {
+    setup(&pool).await;
+    let (user_id, token) = create_user_with_token(&pool).await;
+    let (discord_api, posted) = stub_discord_api().await;
+
+    let mut config = test_config();
+    config.discord_api_base_url = discord_api.clone();
+    let redis = redis::Client::open(config.redis_url.as_str())
+        .unwrap()
+        .get_connection_manager()
+        .await
+        .unwrap();
+    let state = AppState::new(pool.clone(), redis, config);
+
+    let post_json = |uri: String, body: String| {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("authorization", format!("Bearer {}", token))
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    // Both destinations at once is ambiguous
+    let response = create_router(state.clone())
+        .oneshot(post_json(
+            "/api/channels".to_string(),
+            r#"{"channel_type":"discord","config":{"webhook_url":"https://discord.com/api/webhooks/123/tok","user_id":"42"}}"#.to_string(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = create_router(state.clone())
+        .oneshot(post_json(
+            "/api/channels".to_string(),
+            r#"{"channel_type":"discord","config":{"webhook_url":"https://discord.com/api/webhooks/123/tok"}}"#.to_string(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let channel: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let channel_id = Uuid::parse_str(channel["id"].as_str().unwrap()).unwrap();
+
+    // The code is posted to Discord, not returned
+    let response = create_router(state.clone())
+        .oneshot(post_json(
+            format!("/api/channels/{}/verification", channel_id),
+            String::new(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let verification: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(verification.get("code").is_none());
+    assert_eq!(verification["verified"], false);
+
+    let content = posted.lock().unwrap()[0]["content"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let code = content.split("**").nth(1).unwrap().to_string();
+
+    let confirm = |code: &str| {
+        post_json(
+            format!("/api/channels/{}/verification/confirm", channel_id),
+            format!(r#"{{"code":"{}"}}"#, code),
+        )
+    };
+    let response = create_router(state.clone())
+        .oneshot(confirm("00000000"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = create_router(state.clone())
+        .oneshot(confirm(&code))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let (verified,): (bool,) =
+        sqlx::query_as("SELECT verified FROM notification_channels WHERE id = $1")
+            .bind(channel_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!(verified);
+
+    // Alerts now reach the channel
+    let addr_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO monitored_addresses (id, address, chain) VALUES ($1, '0xagent', 'flare')",
+    )
+    .bind(addr_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let sub_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO subscriptions (id, user_id, address_id, channel_id, event_type) VALUES ($1, $2, $3, $4, 'liquidation_started')",
+    )
+    .bind(sub_id)
+    .bind(user_id)
+    .bind(addr_id)
+    .bind(channel_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let (event_id,): (i64,) = sqlx::query_as(
+        r#"
+        INSERT INTO indexed_events (tx_hash, log_index, block_number, block_timestamp, chain, address, event_type)
+        VALUES ('0xdiscord', 0, 1000, NOW(), 'flare', '0xagent', 'liquidation_started')
+        RETURNING id
+        "#,
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let alert_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO alerts (id, subscription_id, event_id, severity, message) VALUES ($1, $2, $3, 'critical', 'test')",
+    )
+    .bind(alert_id)
+    .bind(sub_id)
+    .bind(event_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+    let notification_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO notifications (id, alert_id, channel_id) VALUES ($1, $2, $3)")
+        .bind(notification_id)
+        .bind(alert_id)
+        .bind(channel_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let mut senders = SenderRegistry::new();
+    senders.register(Box::new(DiscordSender::new(discord_api, None)));
+    let worker = NotificationWorker::new(0, pool.clone(), Arc::new(senders));
+    assert_eq!(
+        worker.deliver(notification_id).await.unwrap(),
+        Some(DeliveryStatus::Sent)
+    );
+    let posted = posted.lock().unwrap();
+    assert_eq!(posted.len(), 2);
+    assert!(posted[1]["embeds"][0]["title"].is_string());
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_unsubscribe_link_deactivates_subscription(pool: PgPool) {
+    setup(&pool).await;
+    let (user_id, _) = create_user_with_token(&pool).await;
+
+    let addr_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO monitored_addresses (id, address, chain) VALUES ($1, $2, 'flare')")
+        .bind(addr_id)
+        .bind("0xunsubscribe_test")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let chan_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO notification_channels (id, user_id, channel_type, config, verified) VALUES ($1, $2, 'email', $3, true)",
+    )
+    .bind(chan_id)
+    .bind(user_id)
+    .bind(serde_json::json!({"email": "alice@example.com"}))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let sub_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO subscriptions (id, user_id, address_id, channel_id, event_type) VALUES ($1, $2, $3, $4, 'liquidation_started')",
+    )
+    .bind(sub_id)
+    .bind(user_id)
+    .bind(addr_id)
+    .bind(chan_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let state = build_test_state(pool.clone()).await;
+
+    // A token signed with anything but the unsubscribe key (here the raw JWT secret) is rejected
+    let forged =
+        SubscriptionService::unsubscribe_token(sub_id, test_config().jwt_secret.as_bytes());
+    let app = create_router(state.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/unsubscribe?token={}", forged))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // The genuine token (signed with the API's secret) deactivates the subscription
+    let token = SubscriptionService::unsubscribe_token(sub_id, &test_config().unsubscribe_key());
+    let app = create_router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/unsubscribe?token={}", token))
+                .header("content-type", "application/x-www-form-urlencoded")
+                .body(Body::from("List-Unsubscribe=One-Click"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (active,): (bool,) = sqlx::query_as("SELECT active FROM subscriptions WHERE id = $1")
+        .bind(sub_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert!(!active);
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_dead_letters_listed_and_redriven(pool: PgPool) {
+    setup(&pool).await;
+    let (user_id, token) = create_user_with_token(&pool).await;
+    let (_, other_token) = create_user_with_token(&pool).await;
+
+    let addr_id = Uuid::new_v4();
+    sqlx::query("INSERT INTO monitored_addresses (id, address, chain) VALUES ($1, $2, 'flare')")
+        .bind(addr_id)
+        .bind("0xdead_letter_test")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let chan_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO notification_channels (id, user_id, channel_type, config, verified) VALUES ($1, $2, 'telegram', $3, true)",
+    )
+    .bind(chan_id)
+    .bind(user_id)
+    .bind(serde_json::json!({"chat_id": "12345"}))
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let sub_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO subscriptions (id, user_id, address_id, channel_id, event_type) VALUES ($1, $2, $3, $4, 'liquidation_started')",
+    )
+    .bind(sub_id)
+    .bind(user_id)
+    .bind(addr_id)
+    .bind(chan_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let (event_id,): (i64,) = sqlx::query_as(
+        r#"
+        INSERT INTO indexed_events (tx_hash, log_index, block_number, block_timestamp, chain, address, event_type)
+        VALUES ('0xdeadletter', 0, 1000, NOW(), 'flare', '0xdead_letter_test', 'liquidation_started')
+        RETURNING id
+        "#,
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let alert_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO alerts (id, subscription_id, event_id, severity, message) VALUES ($1, $2, $3, 'critical', 'test')",
+    )
+    .bind(alert_id)
+    .bind(sub_id)
+    .bind(event_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let notification_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO notifications (id, alert_id, channel_id, status, attempts, error_detail) VALUES ($1, $2, $3, 'dead_letter', 5, 'chat not found')",
+    )
+    .bind(notification_id)
+    .bind(alert_id)
+    .bind(chan_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let state = build_test_state(pool.clone()).await;
+
+    // 1. The owner sees the dead letter; another user does not
+    for (bearer, expected) in [(&token, 1), (&other_token, 0)] {
+        let app = create_router(state.clone());
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/notifications/dead-letters")
+                    .header("authorization", format!("Bearer {}", bearer))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let list: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(list.len(), expected);
+    }
+
+    // 2. Another user cannot re-drive it
+    let app = create_router(state.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/notifications/{}/redrive", notification_id))
+                .header("authorization", format!("Bearer {}", other_token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // 3. The owner re-drives it back to pending with a fresh attempt budget
+    let app = create_router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/notifications/{}/redrive", notification_id))
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (status, attempts): (String, i32) =
+        sqlx::query_as("SELECT status, attempts FROM notifications WHERE id = $1")
+            .bind(notification_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(status, "pending");
+    assert_eq!(attempts, 0);
+}
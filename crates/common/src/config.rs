@@ -1,13 +1,13 @@
 use serde::Deserialize;
 
+use crate::signing;
+use crate::types::Chain;
+
 /// Global application configuration loaded from environment variables.
 #[derive(Debug, Clone, Deserialize)]
 pub struct AppConfig {
-    /// Primary Flare RPC URL (NaaS provider recommended)
-    pub flare_rpc_url: String,
-
-    /// Fallback Flare RPC URL (public endpoint)
-    pub flare_rpc_fallback_url: Option<String>,
+    /// Chains the indexer follows concurrently, from `INDEXER_CHAINS` (default: flare)
+    pub indexer_chains: Vec<ChainConfig>,
 
     /// PostgreSQL connection string
     pub database_url: String,
@@ -15,30 +15,101 @@ pub struct AppConfig {
     /// Redis connection string
     pub redis_url: String,
 
-    /// Block polling interval in milliseconds (default: 1500 for < 1.8s blocks)
+    /// Default block polling interval in milliseconds (default: 1500 for < 1.8s blocks)
     pub indexer_poll_interval_ms: u64,
 
-    /// Number of recent block hashes to track for reorg detection
+    /// Default number of recent block hashes to track for reorg detection
     pub indexer_reorg_window: u64,
 
+    /// Blocks per `eth_getLogs` request in backfill mode (default: 1000)
+    pub indexer_backfill_chunk_size: u64,
+
+    /// Lag behind the chain tip, in blocks, above which the poller fetches logs in
+    /// ranges instead of block by block (default: 50)
+    pub indexer_catchup_threshold: u64,
+
+    /// Maximum blocks per ranged `eth_getLogs` while catching up (default: 500)
+    pub indexer_catchup_window: u64,
+
+    /// Blocks between FlareContractRegistry re-resolutions; 0 re-resolves only on
+    /// registry update events (default: 1000)
+    pub indexer_registry_refresh_blocks: u64,
+
+    /// Also index addresses with an active `generic_event` subscription (default: false)
+    pub indexer_generic_subscriptions: bool,
+
+    /// Seconds between re-reads of `generic_event` subscriptions (default: 30)
+    pub indexer_generic_refresh_secs: u64,
+
+    /// Seconds between RPC endpoint health checks (default: 10)
+    pub indexer_rpc_health_interval_secs: u64,
+
+    /// Blocks an RPC endpoint may trail the best known height before failover (default: 5)
+    pub indexer_rpc_max_lag_blocks: u64,
+
+    /// Listen address for the indexer's Prometheus `/metrics` endpoint; disabled when unset
+    pub indexer_metrics_addr: Option<String>,
+
+    /// Interval in milliseconds between engine polls of `indexed_events` (default: 1000)
+    pub engine_poll_interval_ms: u64,
+
+    /// Maximum number of indexed events the engine processes per poll (default: 500)
+    pub engine_batch_size: u32,
+
+    /// Send a follow-up notification when a delivered alert is retracted after a reorg (default: true)
+    pub engine_retraction_notices: bool,
+
+    /// Minimum block time in seconds between two health snapshots of an FAsset agent (default: 300)
+    pub engine_snapshot_interval_secs: u64,
+
+    /// Blocks between two reads of an FAsset agent vault on price ticks (default: 200)
+    pub engine_vault_refresh_blocks: u64,
+
     /// JWT secret for API authentication
     pub jwt_secret: String,
 
     /// JWT token expiry in hours
     pub jwt_expiry_hours: u64,
 
+    /// Secret for unsubscribe link signatures; derived from `jwt_secret` when unset
+    pub unsubscribe_secret: Option<String>,
+
+    /// Number of concurrent notification delivery workers (default: 4)
+    pub notifier_workers: usize,
+
+    /// Delivery attempts before a notification is dead-lettered (default: 5)
+    pub notifier_max_attempts: u32,
+
+    /// Base delay in seconds before the first delivery retry, doubled per attempt (default: 30)
+    pub notifier_retry_base_secs: u64,
+
     /// Telegram bot token
     pub telegram_bot_token: Option<String>,
 
-    /// Discord bot token
+    /// Telegram Bot API base URL (default: https://api.telegram.org)
+    pub telegram_api_base_url: String,
+
+    /// Discord bot token (enables DM delivery)
     pub discord_bot_token: Option<String>,
 
+    /// Discord API base URL (default: https://discord.com/api/v10)
+    pub discord_api_base_url: String,
+
     /// Resend API key for email delivery
     pub resend_api_key: Option<String>,
 
+    /// Resend API base URL (default: https://api.resend.com)
+    pub resend_api_base_url: String,
+
     /// Email sender address
     pub email_from: Option<String>,
 
+    /// Public base URL of the API, used in email links (default: http://localhost:3000)
+    pub public_api_url: String,
+
+    /// Allow webhook channels to target loopback/private addresses (default: false)
+    pub webhook_allow_private_targets: bool,
+
     /// Maximum number of PostgreSQL connections in the pool (default: 20)
     pub db_max_connections: u32,
 }
@@ -48,36 +119,278 @@ impl AppConfig {
     pub fn from_env() -> anyhow::Result<Self> {
         dotenvy::dotenv().ok();
 
+        let indexer_poll_interval_ms = std::env::var("INDEXER_POLL_INTERVAL_MS")
+            .unwrap_or_else(|_| "1500".to_string())
+            .parse()
+            .map_err(|_| anyhow::anyhow!("INDEXER_POLL_INTERVAL_MS must be a valid u64"))?;
+        let indexer_reorg_window = std::env::var("INDEXER_REORG_WINDOW")
+            .unwrap_or_else(|_| "10".to_string())
+            .parse()
+            .map_err(|_| anyhow::anyhow!("INDEXER_REORG_WINDOW must be a valid u64"))?;
+        let indexer_chains =
+            parse_chains(&std::env::var("INDEXER_CHAINS").unwrap_or_else(|_| "flare".to_string()))?
+                .into_iter()
+                .map(|chain| {
+                    ChainConfig::from_vars(
+                        chain,
+                        |name| std::env::var(name).ok(),
+                        indexer_poll_interval_ms,
+                        indexer_reorg_window,
+                    )
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
         Ok(Self {
-            flare_rpc_url: std::env::var("FLARE_RPC_URL")
-                .unwrap_or_else(|_| "https://flare-api.flare.network/ext/C/rpc".to_string()),
-            flare_rpc_fallback_url: std::env::var("FLARE_RPC_FALLBACK_URL").ok(),
+            indexer_chains,
             database_url: std::env::var("DATABASE_URL")
                 .map_err(|_| anyhow::anyhow!("DATABASE_URL environment variable is required"))?,
             redis_url: std::env::var("REDIS_URL")
                 .unwrap_or_else(|_| "redis://localhost:6379".to_string()),
-            indexer_poll_interval_ms: std::env::var("INDEXER_POLL_INTERVAL_MS")
-                .unwrap_or_else(|_| "1500".to_string())
+            indexer_poll_interval_ms,
+            indexer_reorg_window,
+            indexer_backfill_chunk_size: std::env::var("INDEXER_BACKFILL_CHUNK_SIZE")
+                .unwrap_or_else(|_| "1000".to_string())
                 .parse()
-                .map_err(|_| anyhow::anyhow!("INDEXER_POLL_INTERVAL_MS must be a valid u64"))?,
-            indexer_reorg_window: std::env::var("INDEXER_REORG_WINDOW")
+                .map_err(|_| anyhow::anyhow!("INDEXER_BACKFILL_CHUNK_SIZE must be a valid u64"))?,
+            indexer_catchup_threshold: std::env::var("INDEXER_CATCHUP_THRESHOLD")
+                .unwrap_or_else(|_| "50".to_string())
+                .parse()
+                .map_err(|_| anyhow::anyhow!("INDEXER_CATCHUP_THRESHOLD must be a valid u64"))?,
+            indexer_catchup_window: std::env::var("INDEXER_CATCHUP_WINDOW")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .map_err(|_| anyhow::anyhow!("INDEXER_CATCHUP_WINDOW must be a valid u64"))?,
+            indexer_registry_refresh_blocks: std::env::var("INDEXER_REGISTRY_REFRESH_BLOCKS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .map_err(|_| {
+                    anyhow::anyhow!("INDEXER_REGISTRY_REFRESH_BLOCKS must be a valid u64")
+                })?,
+            indexer_generic_subscriptions: std::env::var("INDEXER_GENERIC_SUBSCRIPTIONS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| {
+                    anyhow::anyhow!("INDEXER_GENERIC_SUBSCRIPTIONS must be true or false")
+                })?,
+            indexer_generic_refresh_secs: std::env::var("INDEXER_GENERIC_REFRESH_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| anyhow::anyhow!("INDEXER_GENERIC_REFRESH_SECS must be a valid u64"))?,
+            indexer_rpc_health_interval_secs: std::env::var("INDEXER_RPC_HEALTH_INTERVAL_SECS")
                 .unwrap_or_else(|_| "10".to_string())
                 .parse()
-                .map_err(|_| anyhow::anyhow!("INDEXER_REORG_WINDOW must be a valid u64"))?,
+                .map_err(|_| {
+                    anyhow::anyhow!("INDEXER_RPC_HEALTH_INTERVAL_SECS must be a valid u64")
+                })?,
+            indexer_rpc_max_lag_blocks: std::env::var("INDEXER_RPC_MAX_LAG_BLOCKS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .map_err(|_| anyhow::anyhow!("INDEXER_RPC_MAX_LAG_BLOCKS must be a valid u64"))?,
+            indexer_metrics_addr: std::env::var("INDEXER_METRICS_ADDR").ok(),
+            engine_poll_interval_ms: std::env::var("ENGINE_POLL_INTERVAL_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .map_err(|_| anyhow::anyhow!("ENGINE_POLL_INTERVAL_MS must be a valid u64"))?,
+            engine_batch_size: std::env::var("ENGINE_BATCH_SIZE")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .map_err(|_| anyhow::anyhow!("ENGINE_BATCH_SIZE must be a valid u32"))?,
+            engine_retraction_notices: std::env::var("ENGINE_RETRACTION_NOTICES")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|_| anyhow::anyhow!("ENGINE_RETRACTION_NOTICES must be true or false"))?,
+            engine_snapshot_interval_secs: std::env::var("ENGINE_SNAPSHOT_INTERVAL_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .map_err(|_| {
+                    anyhow::anyhow!("ENGINE_SNAPSHOT_INTERVAL_SECS must be a valid u64")
+                })?,
+            engine_vault_refresh_blocks: std::env::var("ENGINE_VAULT_REFRESH_BLOCKS")
+                .unwrap_or_else(|_| "200".to_string())
+                .parse()
+                .map_err(|_| anyhow::anyhow!("ENGINE_VAULT_REFRESH_BLOCKS must be a valid u64"))?,
             jwt_secret: std::env::var("JWT_SECRET")
                 .map_err(|_| anyhow::anyhow!("JWT_SECRET environment variable is required"))?,
             jwt_expiry_hours: std::env::var("JWT_EXPIRY_HOURS")
                 .unwrap_or_else(|_| "24".to_string())
                 .parse()
                 .map_err(|_| anyhow::anyhow!("JWT_EXPIRY_HOURS must be a valid u64"))?,
+            unsubscribe_secret: std::env::var("UNSUBSCRIBE_SECRET")
+                .ok()
+                .filter(|s| !s.is_empty()),
+            notifier_workers: std::env::var("NOTIFIER_WORKERS")
+                .unwrap_or_else(|_| "4".to_string())
+                .parse()
+                .map_err(|_| anyhow::anyhow!("NOTIFIER_WORKERS must be a valid usize"))?,
+            notifier_max_attempts: std::env::var("NOTIFIER_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .map_err(|_| anyhow::anyhow!("NOTIFIER_MAX_ATTEMPTS must be a valid u32"))?,
+            notifier_retry_base_secs: std::env::var("NOTIFIER_RETRY_BASE_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| anyhow::anyhow!("NOTIFIER_RETRY_BASE_SECS must be a valid u64"))?,
             telegram_bot_token: std::env::var("TELEGRAM_BOT_TOKEN").ok(),
+            telegram_api_base_url: std::env::var("TELEGRAM_API_BASE_URL")
+                .unwrap_or_else(|_| "https://api.telegram.org".to_string()),
             discord_bot_token: std::env::var("DISCORD_BOT_TOKEN").ok(),
+            discord_api_base_url: std::env::var("DISCORD_API_BASE_URL")
+                .unwrap_or_else(|_| "https://discord.com/api/v10".to_string()),
             resend_api_key: std::env::var("RESEND_API_KEY").ok(),
+            resend_api_base_url: std::env::var("RESEND_API_BASE_URL")
+                .unwrap_or_else(|_| "https://api.resend.com".to_string()),
             email_from: std::env::var("EMAIL_FROM").ok(),
+            public_api_url: std::env::var("PUBLIC_API_URL")
+                .unwrap_or_else(|_| "http://localhost:3000".to_string()),
+            webhook_allow_private_targets: std::env::var("WEBHOOK_ALLOW_PRIVATE_TARGETS")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| {
+                    anyhow::anyhow!("WEBHOOK_ALLOW_PRIVATE_TARGETS must be true or false")
+                })?,
             db_max_connections: std::env::var("DB_MAX_CONNECTIONS")
                 .unwrap_or_else(|_| "20".to_string())
                 .parse()
                 .map_err(|_| anyhow::anyhow!("DB_MAX_CONNECTIONS must be a valid u32"))?,
         })
     }
+
+    /// Key that signs unsubscribe links: `UNSUBSCRIBE_SECRET` when set, otherwise a
+    /// key derived from `JWT_SECRET` so the JWT signing key is never used directly.
+    pub fn unsubscribe_key(&self) -> [u8; 32] {
+        match &self.unsubscribe_secret {
+            Some(secret) => signing::derive_key(secret.as_bytes(), "unsubscribe"),
+            None => signing::derive_key(self.jwt_secret.as_bytes(), "unsubscribe"),
+        }
+    }
+}
+
+/// Indexer settings for one chain.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChainConfig {
+    pub chain: Chain,
+
+    /// Primary RPC URL (NaaS provider recommended)
+    pub rpc_url: String,
+
+    /// Fallback RPC URLs, highest priority first
+    pub rpc_fallback_urls: Vec<String>,
+
+    /// FlareContractRegistry address; the network's well-known address when unset
+    pub registry_address: Option<String>,
+
+    /// Block polling interval in milliseconds
+    pub poll_interval_ms: u64,
+
+    /// Number of recent block hashes to track for reorg detection
+    pub reorg_window: u64,
+}
+
+impl ChainConfig {
+    /// Read a chain's settings from `<CHAIN>_RPC_URL`, `<CHAIN>_RPC_FALLBACK_URL`
+    /// (comma-separated), `<CHAIN>_REGISTRY_ADDRESS`, `<CHAIN>_POLL_INTERVAL_MS` and
+    /// `<CHAIN>_REORG_WINDOW`, e.g. `COSTON2_RPC_URL`.
+    ///
+    /// The RPC URL defaults to the chain's public endpoint; the poll interval and
+    /// reorg window default to the global `INDEXER_*` values.
+    pub fn from_vars(
+        chain: Chain,
+        var: impl Fn(&str) -> Option<String>,
+        default_poll_interval_ms: u64,
+        default_reorg_window: u64,
+    ) -> anyhow::Result<Self> {
+        let prefix = chain.to_string().to_uppercase();
+        let name = |suffix: &str| format!("{}_{}", prefix, suffix);
+        let number = |suffix: &str, default: u64| -> anyhow::Result<u64> {
+            match var(&name(suffix)) {
+                Some(value) => value
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("{} must be a valid u64", name(suffix))),
+                None => Ok(default),
+            }
+        };
+
+        Ok(Self {
+            chain,
+            rpc_url: var(&name("RPC_URL")).unwrap_or_else(|| chain.public_rpc_url().to_string()),
+            rpc_fallback_urls: var(&name("RPC_FALLBACK_URL"))
+                .map(|urls| {
+                    urls.split(',')
+                        .map(str::trim)
+                        .filter(|url| !url.is_empty())
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            registry_address: var(&name("REGISTRY_ADDRESS")),
+            poll_interval_ms: number("POLL_INTERVAL_MS", default_poll_interval_ms)?,
+            reorg_window: number("REORG_WINDOW", default_reorg_window)?,
+        })
+    }
+}
+
+/// Parse a comma-separated chain list such as `flare,coston2`, ignoring repeats.
+pub fn parse_chains(list: &str) -> anyhow::Result<Vec<Chain>> {
+    let mut chains = Vec::new();
+    for name in list.split(',').filter(|name| !name.trim().is_empty()) {
+        let chain: Chain = name
+            .parse()
+            .map_err(|e| anyhow::anyhow!("INDEXER_CHAINS: {}", e))?;
+        if !chains.contains(&chain) {
+            chains.push(chain);
+        }
+    }
+    if chains.is_empty() {
+        anyhow::bail!("INDEXER_CHAINS must name at least one chain");
+    }
+    Ok(chains)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parse_chains() {
+        assert_eq!(
+            parse_chains("flare, Coston2,flare").unwrap(),
+            vec![Chain::Flare, Chain::Coston2]
+        );
+        assert!(parse_chains("flare,ethereum").is_err());
+        assert!(parse_chains(" , ").is_err());
+    }
+
+    #[test]
+    fn test_chain_config_reads_prefixed_vars_with_defaults() {
+        let vars: HashMap<&str, &str> = HashMap::from([
+            ("COSTON2_RPC_URL", "https://coston2.example/rpc"),
+            (
+                "COSTON2_RPC_FALLBACK_URL",
+                "https://a.example, https://b.example",
+            ),
+            ("COSTON2_REORG_WINDOW", "32"),
+        ]);
+        let var = |name: &str| vars.get(name).map(|v| v.to_string());
+
+        let coston2 = ChainConfig::from_vars(Chain::Coston2, var, 1500, 10).unwrap();
+        assert_eq!(coston2.rpc_url, "https://coston2.example/rpc");
+        assert_eq!(
+            coston2.rpc_fallback_urls,
+            vec!["https://a.example", "https://b.example"]
+        );
+        assert_eq!(coston2.registry_address, None);
+        assert_eq!(coston2.poll_interval_ms, 1500);
+        assert_eq!(coston2.reorg_window, 32);
+
+        let songbird = ChainConfig::from_vars(Chain::Songbird, var, 1500, 10).unwrap();
+        assert_eq!(songbird.rpc_url, Chain::Songbird.public_rpc_url());
+        assert!(songbird.rpc_fallback_urls.is_empty());
+
+        let bad = HashMap::from([("FLARE_POLL_INTERVAL_MS", "fast")]);
+        assert!(
+            ChainConfig::from_vars(Chain::Flare, |n| bad.get(n).map(|v| v.to_string()), 1, 1)
+                .is_err()
+        );
+    }
 }
@@ -1,5 +1,8 @@
 pub mod config;
 pub mod db;
 pub mod error;
+pub mod net;
+pub mod queue;
 pub mod redis_pool;
+pub mod signing;
 pub mod types;
@@ -0,0 +1,41 @@
+//! Redis notification queue shared by the engine (producer) and notifier (consumer).
+//!
+//! The queue only carries notification IDs — the `notifications` table remains the
+//! source of truth, so a lost or duplicated queue entry never loses or doubles a delivery.
+
+use redis::AsyncCommands;
+use redis::aio::ConnectionManager;
+use uuid::Uuid;
+
+/// Redis list holding IDs of notifications awaiting delivery.
+pub const NOTIFICATION_QUEUE_KEY: &str = "notifications:queue";
+
+/// Push a notification ID onto the delivery queue.
+pub async fn enqueue_notification(
+    redis: &mut ConnectionManager,
+    notification_id: Uuid,
+) -> anyhow::Result<()> {
+    redis
+        .lpush::<_, _, ()>(NOTIFICATION_QUEUE_KEY, notification_id.to_string())
+        .await?;
+    Ok(())
+}
+
+/// Block for up to `timeout_secs` waiting for the next notification ID.
+///
+/// Returns `None` on timeout. Entries that are not valid UUIDs are dropped.
+pub async fn dequeue_notification(
+    redis: &mut ConnectionManager,
+    timeout_secs: f64,
+) -> anyhow::Result<Option<Uuid>> {
+    let popped: Option<(String, String)> =
+        redis.brpop(NOTIFICATION_QUEUE_KEY, timeout_secs).await?;
+
+    Ok(popped.and_then(|(_, id)| match Uuid::parse_str(&id) {
+        Ok(id) => Some(id),
+        Err(_) => {
+            tracing::warn!(entry = %id, "Dropping malformed notification queue entry");
+            None
+        }
+    }))
+}
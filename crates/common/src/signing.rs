@@ -0,0 +1,77 @@
+//! HMAC-SHA256 signing helpers for tokens that leave the system (e.g., unsubscribe links).
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Sign `message` with `secret`, returning the lowercase hex HMAC-SHA256.
+pub fn sign(secret: &[u8], message: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Derive a 32-byte key for one purpose from a shared secret (HKDF-SHA256, RFC 5869,
+/// with no salt and `label` as the info string).
+///
+/// Keys derived under different labels are independent, so a signature made for one
+/// purpose can never be replayed as another.
+pub fn derive_key(secret: &[u8], label: &str) -> [u8; 32] {
+    let mut extract =
+        HmacSha256::new_from_slice(&[0u8; 32]).expect("HMAC accepts keys of any length");
+    extract.update(secret);
+    let prk = extract.finalize().into_bytes();
+
+    let mut expand = HmacSha256::new_from_slice(&prk).expect("HMAC accepts keys of any length");
+    expand.update(label.as_bytes());
+    expand.update(&[1]);
+    expand.finalize().into_bytes().into()
+}
+
+/// Check a hex signature produced by `sign`, in constant time.
+pub fn verify(secret: &[u8], message: &[u8], signature_hex: &str) -> bool {
+    let Ok(signature) = hex::decode(signature_hex) else {
+        return false;
+    };
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.verify_slice(&signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_matches_rfc4231_vector() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_derive_key_matches_rfc5869_vector() {
+        // RFC 5869 test case 3 (no salt, no info), first 32 bytes of OKM
+        let key = derive_key(&[0x0b; 22], "");
+        assert_eq!(
+            hex::encode(key),
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d"
+        );
+        assert_ne!(
+            derive_key(b"secret", "unsubscribe"),
+            derive_key(b"secret", "other")
+        );
+    }
+
+    #[test]
+    fn test_verify_round_trip() {
+        let sig = sign(b"secret", b"payload");
+        assert!(verify(b"secret", b"payload", &sig));
+        assert!(!verify(b"secret", b"tampered", &sig));
+        assert!(!verify(b"other", b"payload", &sig));
+        assert!(!verify(b"secret", b"payload", "not-hex"));
+    }
+}
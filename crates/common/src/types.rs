@@ -3,11 +3,15 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Supported blockchain networks.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "text", rename_all = "lowercase")]
 pub enum Chain {
     Flare,
     Songbird,
+    /// Songbird testnet
+    Coston,
+    /// Flare testnet
+    Coston2,
 }
 
 impl std::fmt::Display for Chain {
@@ -15,6 +19,47 @@ impl std::fmt::Display for Chain {
         match self {
             Chain::Flare => write!(f, "flare"),
             Chain::Songbird => write!(f, "songbird"),
+            Chain::Coston => write!(f, "coston"),
+            Chain::Coston2 => write!(f, "coston2"),
+        }
+    }
+}
+
+impl std::str::FromStr for Chain {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "flare" => Ok(Chain::Flare),
+            "songbird" => Ok(Chain::Songbird),
+            "coston" => Ok(Chain::Coston),
+            "coston2" => Ok(Chain::Coston2),
+            other => Err(format!(
+                "unknown chain '{}' (expected flare, songbird, coston or coston2)",
+                other
+            )),
+        }
+    }
+}
+
+impl Chain {
+    /// Base URL of the chain's block explorer.
+    pub fn explorer_url(&self) -> &'static str {
+        match self {
+            Chain::Flare => "https://flare-explorer.flare.network",
+            Chain::Songbird => "https://songbird-explorer.flare.network",
+            Chain::Coston => "https://coston-explorer.flare.network",
+            Chain::Coston2 => "https://coston2-explorer.flare.network",
+        }
+    }
+
+    /// Public RPC endpoint, used when no RPC URL is configured for the chain.
+    pub fn public_rpc_url(&self) -> &'static str {
+        match self {
+            Chain::Flare => "https://flare-api.flare.network/ext/C/rpc",
+            Chain::Songbird => "https://songbird-api.flare.network/ext/C/rpc",
+            Chain::Coston => "https://coston-api.flare.network/ext/C/rpc",
+            Chain::Coston2 => "https://coston2-api.flare.network/ext/C/rpc",
         }
     }
 }
@@ -27,6 +72,8 @@ pub enum AddressType {
     FassetAgent,
     GenericContract,
     Eoa,
+    /// An FTSO feed, e.g. `FLR/USD`, for feed-scoped subscriptions
+    FtsoFeed,
 }
 
 /// Types of events that can be decoded from on-chain logs.
@@ -37,6 +84,23 @@ pub enum EventType {
     PriceEpochFinalized,
     VotePowerChanged,
     RewardEpochStarted,
+    /// Anchor feed values published by `FtsoFeedPublisher`
+    FtsoFeedPublished,
+    /// Block-latency feed values from `FastUpdater`
+    FastUpdateFeeds,
+
+    // Flare Systems Protocol events (FTSO v2 voters)
+    /// A reward epoch's signing policy was initialized on `Relay`
+    SigningPolicyInitialized,
+    /// A protocol's voting round result was relayed
+    ProtocolMessageRelayed,
+    /// A voter registered for a reward epoch
+    VoterRegistered,
+    /// A voter was removed from a reward epoch's registration
+    VoterRemoved,
+    SigningPolicySigned,
+    RewardsSigned,
+    UptimeVoteSigned,
 
     // FDC events
     AttestationRequested,
@@ -49,6 +113,18 @@ pub enum EventType {
     MintingExecuted,
     RedemptionRequested,
     LiquidationStarted,
+    CollateralReserved,
+    RedemptionPerformed,
+
+    // Synthetic collateral ratio events, raised by the engine's CR calculator
+    CrWarning,
+    CrCritical,
+    LiquidationImminent,
+
+    // Synthetic feed events for feed-scoped subscriptions, raised by the engine's feed monitor
+    PriceThreshold,
+    PriceMove,
+    FeedStale,
 
     // Generic
     GenericEvent,
@@ -60,6 +136,15 @@ impl std::fmt::Display for EventType {
             EventType::PriceEpochFinalized => write!(f, "price_epoch_finalized"),
             EventType::VotePowerChanged => write!(f, "vote_power_changed"),
             EventType::RewardEpochStarted => write!(f, "reward_epoch_started"),
+            EventType::FtsoFeedPublished => write!(f, "ftso_feed_published"),
+            EventType::FastUpdateFeeds => write!(f, "fast_update_feeds"),
+            EventType::SigningPolicyInitialized => write!(f, "signing_policy_initialized"),
+            EventType::ProtocolMessageRelayed => write!(f, "protocol_message_relayed"),
+            EventType::VoterRegistered => write!(f, "voter_registered"),
+            EventType::VoterRemoved => write!(f, "voter_removed"),
+            EventType::SigningPolicySigned => write!(f, "signing_policy_signed"),
+            EventType::RewardsSigned => write!(f, "rewards_signed"),
+            EventType::UptimeVoteSigned => write!(f, "uptime_vote_signed"),
             EventType::AttestationRequested => write!(f, "attestation_requested"),
             EventType::AttestationProved => write!(f, "attestation_proved"),
             EventType::RoundFinalized => write!(f, "round_finalized"),
@@ -68,6 +153,14 @@ impl std::fmt::Display for EventType {
             EventType::MintingExecuted => write!(f, "minting_executed"),
             EventType::RedemptionRequested => write!(f, "redemption_requested"),
             EventType::LiquidationStarted => write!(f, "liquidation_started"),
+            EventType::CollateralReserved => write!(f, "collateral_reserved"),
+            EventType::RedemptionPerformed => write!(f, "redemption_performed"),
+            EventType::CrWarning => write!(f, "cr_warning"),
+            EventType::CrCritical => write!(f, "cr_critical"),
+            EventType::LiquidationImminent => write!(f, "liquidation_imminent"),
+            EventType::PriceThreshold => write!(f, "price_threshold"),
+            EventType::PriceMove => write!(f, "price_move"),
+            EventType::FeedStale => write!(f, "feed_stale"),
             EventType::GenericEvent => write!(f, "generic_event"),
         }
     }
@@ -88,16 +181,36 @@ pub enum Severity {
 pub enum DeliveryStatus {
     Pending,
     Sent,
+    /// Last attempt failed; another is scheduled at `next_attempt_at`
     Failed,
+    /// Failed permanently or exhausted its attempts; only re-driven manually
+    #[sqlx(rename = "dead_letter")]
+    DeadLetter,
+    /// Withdrawn before delivery because its alert was retracted
+    Cancelled,
+    /// Waiting for the triggering event to reach the subscription's confirmation depth
+    Held,
 }
 
-/// Notification channel type.
+/// What a notification tells the channel about its alert.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum NotificationKind {
+    /// The alert itself
+    Alert,
+    /// Follow-up saying a previously delivered alert was invalidated by a reorg
+    Retraction,
+}
+
+/// Notification channel type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
 pub enum ChannelType {
     Telegram,
     Discord,
     Email,
+    /// HMAC-signed HTTP POST to a user-supplied URL
+    Webhook,
 }
 
 /// A decoded on-chain event ready for persistence and alert matching.
@@ -113,6 +226,74 @@ pub struct DecodedEvent {
     pub decoded_data: serde_json::Value,
 }
 
+/// A persisted row from `indexed_events`.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct IndexedEvent {
+    pub id: i64,
+    pub tx_hash: String,
+    pub log_index: Option<i64>,
+    pub block_number: i64,
+    pub block_timestamp: DateTime<Utc>,
+    pub chain: Chain,
+    pub address: String,
+    pub event_type: EventType,
+    pub decoded_data: serde_json::Value,
+    pub is_reorged: bool,
+}
+
+impl From<IndexedEvent> for DecodedEvent {
+    fn from(row: IndexedEvent) -> Self {
+        Self {
+            tx_hash: row.tx_hash,
+            log_index: row.log_index.map(|i| i as u64),
+            block_number: row.block_number as u64,
+            block_timestamp: row.block_timestamp,
+            chain: row.chain,
+            address: row.address,
+            event_type: row.event_type,
+            decoded_data: row.decoded_data,
+        }
+    }
+}
+
+/// A registry contract whose logs the indexer currently fetches.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WatchedContract {
+    pub chain: Chain,
+    pub contract_name: String,
+    pub address: String,
+    /// First block indexed with this address
+    pub from_block: i64,
+    pub resolved_at: DateTime<Utc>,
+}
+
+/// Periodic health snapshot of an FAsset agent vault, written by the engine's CR calculator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AgentVaultSnapshot {
+    pub chain: Chain,
+    /// Agent vault address (lowercase)
+    pub agent: String,
+    pub fasset: Option<String>,
+    /// Lower of the vault and pool CR; `None` while nothing is backed or a price is unknown
+    pub cr: Option<f64>,
+    pub vault_cr: Option<f64>,
+    pub pool_cr: Option<f64>,
+    /// Vault collateral in whole tokens
+    pub vault_collateral: f64,
+    /// Pool collateral in whole native tokens
+    pub pool_collateral: f64,
+    pub vault_collateral_usd: Option<f64>,
+    pub pool_collateral_usd: Option<f64>,
+    /// USD value of the minted, reserved and redeeming lots the collateral backs
+    pub minted_value_usd: Option<f64>,
+    pub minted_lots: i64,
+    pub reserved_lots: i64,
+    pub redeeming_lots: i64,
+    pub block_number: i64,
+    /// Timestamp of the block the snapshot was computed at
+    pub recorded_at: DateTime<Utc>,
+}
+
 /// A monitored blockchain address.
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct MonitoredAddress {
@@ -168,6 +349,8 @@ pub struct Alert {
     pub severity: Severity,
     pub message: String,
     pub triggered_at: DateTime<Utc>,
+    /// Set when the triggering event was reorged out of the canonical chain
+    pub retracted_at: Option<DateTime<Utc>>,
 }
 
 /// A notification queued for delivery.
@@ -180,6 +363,11 @@ pub struct Notification {
     pub sent_at: Option<DateTime<Utc>>,
     pub error_detail: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Delivery attempts made so far
+    pub attempts: i32,
+    /// When the next retry is due (`failed` notifications only)
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub kind: NotificationKind,
 }
 
 /// Human-readable notification payload ready for delivery.
@@ -204,12 +392,26 @@ pub struct ThresholdConfig {
     pub min_value: Option<f64>,
     /// Maximum value (alert when value rises above)
     pub max_value: Option<f64>,
-    /// Percentage deviation from a baseline to trigger alert
+    /// Percentage deviation from a baseline to trigger alert. For feed events the
+    /// baseline is the feed's price `window_secs` earlier.
     pub deviation_pct: Option<f64>,
+    /// Rolling window in seconds for `deviation_pct` baselines (default: 3600)
+    pub window_secs: Option<u64>,
+    /// Voting epochs without an update before a `feed_stale` subscription alerts (default: 3)
+    pub stale_epochs: Option<u64>,
     /// Number of consecutive blocks the threshold must be met (default: 1)
     pub hysteresis_blocks: Option<u64>,
     /// Cooldown period in seconds between alerts (default: 300 = 5 min)
     pub cooldown_seconds: Option<u64>,
+    /// Blocks the indexed chain head must be past the event before notifying
+    /// (default: 0 = notify immediately)
+    pub confirmations: Option<u64>,
+    /// Only match FAsset events for this asset, e.g. "FXRP" (case-insensitive)
+    pub fasset: Option<String>,
+    /// Collateral ratio below which a `cr_warning` subscription alerts (default: 1.8)
+    pub cr_warning: Option<f64>,
+    /// Collateral ratio below which a `cr_critical` subscription alerts (default: 1.5)
+    pub cr_critical: Option<f64>,
 }
 
 impl std::fmt::Display for Severity {
@@ -228,6 +430,18 @@ impl std::fmt::Display for DeliveryStatus {
             DeliveryStatus::Pending => write!(f, "pending"),
             DeliveryStatus::Sent => write!(f, "sent"),
             DeliveryStatus::Failed => write!(f, "failed"),
+            DeliveryStatus::DeadLetter => write!(f, "dead_letter"),
+            DeliveryStatus::Cancelled => write!(f, "cancelled"),
+            DeliveryStatus::Held => write!(f, "held"),
+        }
+    }
+}
+
+impl std::fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationKind::Alert => write!(f, "alert"),
+            NotificationKind::Retraction => write!(f, "retraction"),
         }
     }
 }
@@ -239,6 +453,35 @@ impl std::fmt::Display for AddressType {
             AddressType::FassetAgent => write!(f, "fasset_agent"),
             AddressType::GenericContract => write!(f, "generic_contract"),
             AddressType::Eoa => write!(f, "eoa"),
+            AddressType::FtsoFeed => write!(f, "ftso_feed"),
+        }
+    }
+}
+
+impl std::fmt::Display for ChannelType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChannelType::Telegram => write!(f, "telegram"),
+            ChannelType::Discord => write!(f, "discord"),
+            ChannelType::Email => write!(f, "email"),
+            ChannelType::Webhook => write!(f, "webhook"),
+        }
+    }
+}
+
+impl std::str::FromStr for ChannelType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "telegram" => Ok(ChannelType::Telegram),
+            "discord" => Ok(ChannelType::Discord),
+            "email" => Ok(ChannelType::Email),
+            "webhook" => Ok(ChannelType::Webhook),
+            other => Err(format!(
+                "Invalid channel_type '{}'. Valid types: telegram, discord, email, webhook",
+                other
+            )),
         }
     }
 }
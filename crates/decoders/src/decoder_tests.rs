@@ -4,13 +4,15 @@
 //! on-chain EVM log layout and verify that every decoder correctly extracts
 //! event types and decoded fields.
 
-use alloy::primitives::{Address, B256, Bytes, Log, LogData, U256, keccak256};
+use alloy::primitives::{Address, B256, Bytes, I256, Log, LogData, U256, keccak256};
+use alloy::sol_types::SolValue;
 use chrono::Utc;
 
 use flare_common::types::{Chain, EventType};
 
 use crate::fasset::FassetDecoder;
 use crate::fdc::FdcDecoder;
+use crate::fsp::FspDecoder;
 use crate::ftso::FtsoDecoder;
 use crate::generic::GenericDecoder;
 use crate::{DecoderRegistry, EventDecoder};
@@ -119,6 +121,63 @@ fn test_ftso_reward_epoch_started() {
     assert_eq!(event.decoded_data["epoch_id"], epoch_id);
 }
 
+#[test]
+fn test_ftso_feed_published() {
+    let decoder = FtsoDecoder::new();
+    let topic0 = keccak256("FtsoFeedPublished(uint32,bytes21,int32,uint16,int8)");
+    let mut id = [0u8; 32];
+    id[..21].copy_from_slice(&crate::ftso::feed_id(1, "FLR/USD").unwrap());
+    let data = (1_834_567i32, 6_500u16, 7i8).abi_encode_params();
+
+    let log = build_log(
+        vec![topic0, B256::from(encode_u256(881_000)), B256::from(id)],
+        data,
+        CONTRACT,
+    );
+
+    let event = decoder
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::FtsoFeedPublished);
+    assert_eq!(event.decoded_data["voting_round_id"], 881_000);
+    assert_eq!(
+        event.decoded_data["feed_id"],
+        "0x01464c522f55534400000000000000000000000000"
+    );
+    assert_eq!(event.decoded_data["feed_name"], "FLR/USD");
+    assert_eq!(event.decoded_data["value"], 1_834_567);
+    assert_eq!(event.decoded_data["decimals"], 7);
+    assert_eq!(event.decoded_data["turnout_bips"], 6_500);
+}
+
+#[test]
+fn test_ftso_fast_update_feeds() {
+    let decoder = FtsoDecoder::new();
+    let topic0 = keccak256("FastUpdateFeeds(uint256,uint256[],int8[])");
+    let data = (
+        vec![U256::from(1_834_567u64), U256::from(6_512_345_678u64)],
+        vec![7i8, 2i8],
+    )
+        .abi_encode_params();
+
+    let log = build_log(
+        vec![topic0, B256::from(encode_u256(440_500))],
+        data,
+        CONTRACT,
+    );
+
+    let event = decoder
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::FastUpdateFeeds);
+    assert_eq!(event.decoded_data["voting_epoch_id"], 440_500);
+    assert_eq!(
+        event.decoded_data["values"],
+        serde_json::json!(["1834567", "6512345678"])
+    );
+    assert_eq!(event.decoded_data["decimals"], serde_json::json!([7, 2]));
+}
+
 #[test]
 fn test_ftso_unknown_topic_returns_none() {
     let decoder = FtsoDecoder::new();
@@ -132,6 +191,273 @@ fn test_ftso_unknown_topic_returns_none() {
     );
 }
 
+// ═══════════════════════════════════════════════════════════════════
+//  Flare Systems Protocol Decoder
+// ═══════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_fsp_signing_policy_initialized() {
+    let decoder = FspDecoder::new();
+    let topic0 = keccak256(
+        "SigningPolicyInitialized(uint24,uint32,uint16,uint256,address[],uint16[],bytes,uint64)",
+    );
+    let voters = vec![Address::repeat_byte(0xA1), Address::repeat_byte(0xB2)];
+    let data = (
+        1_234_560u32,
+        32_768u16,
+        U256::from(777u64),
+        voters,
+        vec![40_000u16, 25_535u16],
+        Bytes::from(vec![0xde, 0xad]),
+        1_700_000_000u64,
+    )
+        .abi_encode_params();
+
+    let log = build_log(vec![topic0, B256::from(encode_u256(250))], data, CONTRACT);
+
+    let event = decoder
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::SigningPolicyInitialized);
+    let data = &event.decoded_data;
+    assert_eq!(data["reward_epoch_id"], 250);
+    assert_eq!(data["start_voting_round_id"], 1_234_560);
+    assert_eq!(data["threshold"], 32_768);
+    assert_eq!(data["seed"], "777");
+    assert_eq!(
+        data["voters"],
+        serde_json::json!([
+            format!("0x{}", "a1".repeat(20)),
+            format!("0x{}", "b2".repeat(20))
+        ])
+    );
+    assert_eq!(data["weights"], serde_json::json!([40_000, 25_535]));
+    assert_eq!(data["total_weight"], 65_535);
+    assert_eq!(data["timestamp"], 1_700_000_000u64);
+}
+
+#[test]
+fn test_fsp_protocol_message_relayed() {
+    let decoder = FspDecoder::new();
+    let topic0 = keccak256("ProtocolMessageRelayed(uint8,uint32,bool,bytes32)");
+    let merkle_root = B256::repeat_byte(0x5A);
+    let data = (true, merkle_root).abi_encode_params();
+
+    let log = build_log(
+        vec![
+            topic0,
+            B256::from(encode_u256(100)),
+            B256::from(encode_u256(881_000)),
+        ],
+        data,
+        CONTRACT,
+    );
+
+    let event = decoder
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::ProtocolMessageRelayed);
+    assert_eq!(event.decoded_data["protocol_id"], 100);
+    assert_eq!(event.decoded_data["voting_round_id"], 881_000);
+    assert_eq!(event.decoded_data["is_secure_random"], true);
+    assert_eq!(
+        event.decoded_data["merkle_root"],
+        format!("0x{}", "5a".repeat(32))
+    );
+}
+
+#[test]
+fn test_fsp_voter_registered() {
+    let decoder = FspDecoder::new();
+    let topic0 = keccak256(
+        "VoterRegistered(address,uint24,address,address,address,bytes32,bytes32,uint256)",
+    );
+    let voter = Address::repeat_byte(0xA1);
+    let signing = Address::repeat_byte(0xC3);
+    let data = (
+        Address::repeat_byte(0xD4),
+        Address::repeat_byte(0xE5),
+        B256::repeat_byte(0x01),
+        B256::repeat_byte(0x02),
+        U256::from(5_000_000u64),
+    )
+        .abi_encode_params();
+
+    let log = build_log(
+        vec![
+            topic0,
+            address_to_topic(voter),
+            B256::from(encode_u256(251)),
+            address_to_topic(signing),
+        ],
+        data,
+        CONTRACT,
+    );
+
+    let event = decoder
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::VoterRegistered);
+    let data = &event.decoded_data;
+    assert_eq!(data["voter"], format!("0x{}", "a1".repeat(20)));
+    assert_eq!(data["reward_epoch_id"], 251);
+    assert_eq!(
+        data["signing_policy_address"],
+        format!("0x{}", "c3".repeat(20))
+    );
+    assert_eq!(data["submit_address"], format!("0x{}", "d4".repeat(20)));
+    assert_eq!(
+        data["submit_signatures_address"],
+        format!("0x{}", "e5".repeat(20))
+    );
+    assert_eq!(
+        data["public_key"],
+        format!("0x{}{}", "01".repeat(32), "02".repeat(32))
+    );
+    assert_eq!(data["registration_weight"], "5000000");
+}
+
+#[test]
+fn test_fsp_voter_removed() {
+    let decoder = FspDecoder::new();
+    let topic0 = keccak256("VoterRemoved(address,uint256)");
+    let voter = Address::repeat_byte(0xA1);
+
+    let log = build_log(
+        vec![
+            topic0,
+            address_to_topic(voter),
+            B256::from(encode_u256(251)),
+        ],
+        vec![],
+        CONTRACT,
+    );
+
+    let event = decoder
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::VoterRemoved);
+    assert_eq!(
+        event.decoded_data["voter"],
+        format!("0x{}", "a1".repeat(20))
+    );
+    assert_eq!(event.decoded_data["reward_epoch_id"], 251);
+}
+
+#[test]
+fn test_fsp_reward_epoch_started() {
+    let decoder = FspDecoder::new();
+    let topic0 = keccak256("RewardEpochStarted(uint24,uint32,uint64)");
+    let data = (1_234_560u32, 1_700_000_000u64).abi_encode_params();
+
+    let log = build_log(vec![topic0, B256::from(encode_u256(251))], data, CONTRACT);
+
+    let event = decoder
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::RewardEpochStarted);
+    assert_eq!(event.decoded_data["reward_epoch_id"], 251);
+    assert_eq!(event.decoded_data["start_voting_round_id"], 1_234_560);
+}
+
+#[test]
+fn test_fsp_signatures() {
+    let decoder = FspDecoder::new();
+    let voter = Address::repeat_byte(0xA1);
+    let topics = |signature: &str| {
+        vec![
+            keccak256(signature),
+            B256::from(encode_u256(250)),
+            address_to_topic(Address::repeat_byte(0xC3)),
+            address_to_topic(voter),
+        ]
+    };
+
+    let log = build_log(
+        topics("SigningPolicySigned(uint24,address,address,uint64,bool)"),
+        (1_700_000_000u64, false).abi_encode_params(),
+        CONTRACT,
+    );
+    let event = decoder
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::SigningPolicySigned);
+    assert_eq!(
+        event.decoded_data["voter"],
+        format!("0x{}", "a1".repeat(20))
+    );
+    assert_eq!(
+        event.decoded_data["signing_policy_address"],
+        format!("0x{}", "c3".repeat(20))
+    );
+    assert_eq!(event.decoded_data["threshold_reached"], false);
+
+    let log = build_log(
+        topics("UptimeVoteSigned(uint24,address,address,bytes32,uint64,bool)"),
+        (B256::repeat_byte(0x07), 1_700_000_000u64, true).abi_encode_params(),
+        CONTRACT,
+    );
+    let event = decoder
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::UptimeVoteSigned);
+    assert_eq!(
+        event.decoded_data["uptime_vote_hash"],
+        format!("0x{}", "07".repeat(32))
+    );
+    assert_eq!(event.decoded_data["threshold_reached"], true);
+
+    let log = build_log(
+        topics("RewardsSigned(uint24,address,address,bytes32,(uint256,uint256)[],uint64,bool)"),
+        (
+            B256::repeat_byte(0x08),
+            vec![
+                (U256::from(0u64), U256::from(12u64)),
+                (U256::from(1u64), U256::from(3u64)),
+            ],
+            1_700_000_000u64,
+            false,
+        )
+            .abi_encode_params(),
+        CONTRACT,
+    );
+    let event = decoder
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::RewardsSigned);
+    assert_eq!(event.decoded_data["reward_epoch_id"], 250);
+    assert_eq!(
+        event.decoded_data["weight_based_claims"],
+        serde_json::json!([
+            { "reward_manager_id": "0", "claims": "12" },
+            { "reward_manager_id": "1", "claims": "3" }
+        ])
+    );
+}
+
+#[test]
+fn test_fsp_truncated_data_returns_none() {
+    let decoder = FspDecoder::new();
+    let topic0 = keccak256("SigningPolicySigned(uint24,address,address,uint64,bool)");
+    let voter = Address::repeat_byte(0xA1);
+
+    // Missing the data and the voter topic
+    let log = build_log(
+        vec![
+            topic0,
+            B256::from(encode_u256(250)),
+            address_to_topic(voter),
+        ],
+        vec![],
+        CONTRACT,
+    );
+    assert!(
+        decoder
+            .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+            .is_none()
+    );
+}
+
 // ═══════════════════════════════════════════════════════════════════
 //  FDC Decoder
 // ═══════════════════════════════════════════════════════════════════
@@ -264,16 +590,81 @@ fn test_fasset_collateral_withdrawn() {
     assert_eq!(event.decoded_data["amount"].as_str().unwrap(), "500000");
 }
 
+/// Topic of an indexed `uint256` (or smaller uint) event parameter.
+fn uint_to_topic(val: u64) -> B256 {
+    B256::from(encode_u256(val))
+}
+
+#[test]
+fn test_fasset_collateral_reserved() {
+    let decoder = FassetDecoder::new();
+    let topic0 = keccak256(
+        "CollateralReserved(address,address,uint256,uint256,uint256,uint256,uint256,uint256,string,bytes32,address,uint256)",
+    );
+    let agent = Address::repeat_byte(0x66);
+    let minter = Address::repeat_byte(0x67);
+    let executor = Address::repeat_byte(0x68);
+    let reference = B256::repeat_byte(0x46);
+
+    // 2 lots of 10 XRP, with a 0.5% fee
+    let data = (
+        U256::from(20_000_000u64),
+        U256::from(100_000u64),
+        U256::from(93_000_000u64),
+        U256::from(93_000_225u64),
+        U256::from(1_745_000_000u64),
+        "rPEPPER7kfTD9w2To4CQk6UCfuHM9c6GDY".to_string(),
+        reference,
+        executor,
+        U256::ZERO,
+    )
+        .abi_encode_params();
+    let log = build_log(
+        vec![
+            topic0,
+            address_to_topic(agent),
+            address_to_topic(minter),
+            uint_to_topic(1_234_567),
+        ],
+        data,
+        CONTRACT,
+    );
+
+    let event = decoder
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::CollateralReserved);
+    let data = &event.decoded_data;
+    assert_eq!(data["agent"], format!("{:#x}", agent));
+    assert_eq!(data["minter"], format!("{:#x}", minter));
+    assert_eq!(data["collateral_reservation_id"], "1234567");
+    assert_eq!(data["value_uba"], "20000000");
+    assert_eq!(data["fee_uba"], "100000");
+    assert_eq!(data["last_underlying_block"], "93000225");
+    assert_eq!(data["last_underlying_timestamp"], "1745000000");
+    assert_eq!(
+        data["payment_address"],
+        "rPEPPER7kfTD9w2To4CQk6UCfuHM9c6GDY"
+    );
+    assert_eq!(data["payment_reference"], format!("{:#x}", reference));
+    assert_eq!(data["executor"], format!("{:#x}", executor));
+}
+
 #[test]
 fn test_fasset_minting_executed() {
     let decoder = FassetDecoder::new();
-    let topic0 = keccak256("MintingExecuted(address,address,uint256)");
-    let minter = Address::repeat_byte(0x11);
+    let topic0 = keccak256("MintingExecuted(address,uint256,uint256,uint256,uint256)");
     let agent = Address::repeat_byte(0x22);
 
+    let data = (
+        U256::from(20_000_000u64),
+        U256::from(60_000u64),
+        U256::from(40_000u64),
+    )
+        .abi_encode_params();
     let log = build_log(
-        vec![topic0, address_to_topic(minter), address_to_topic(agent)],
-        encode_u256(10).to_vec(), // lots
+        vec![topic0, address_to_topic(agent), uint_to_topic(1_234_567)],
+        data,
         CONTRACT,
     );
 
@@ -281,31 +672,44 @@ fn test_fasset_minting_executed() {
         .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
         .unwrap();
     assert_eq!(event.event_type, EventType::MintingExecuted);
-    assert!(
-        event.decoded_data["minter"]
-            .as_str()
-            .unwrap()
-            .contains("111111")
-    );
-    assert!(
-        event.decoded_data["agent"]
-            .as_str()
-            .unwrap()
-            .contains("222222")
-    );
-    assert_eq!(event.decoded_data["lots"].as_str().unwrap(), "10");
+    let data = &event.decoded_data;
+    assert_eq!(data["agent"], format!("{:#x}", agent));
+    assert_eq!(data["collateral_reservation_id"], "1234567");
+    assert_eq!(data["minted_amount_uba"], "20000000");
+    assert_eq!(data["agent_fee_uba"], "60000");
+    assert_eq!(data["pool_fee_uba"], "40000");
 }
 
 #[test]
 fn test_fasset_redemption_requested() {
     let decoder = FassetDecoder::new();
-    let topic0 = keccak256("RedemptionRequested(address,address,uint256)");
-    let redeemer = Address::repeat_byte(0x33);
+    let topic0 = keccak256(
+        "RedemptionRequested(address,address,uint256,string,uint256,uint256,uint256,uint256,uint256,bytes32,address,uint256)",
+    );
     let agent = Address::repeat_byte(0x44);
-
+    let redeemer = Address::repeat_byte(0x33);
+    let reference = B256::repeat_byte(0x52);
+
+    let data = (
+        "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh".to_string(),
+        U256::from(30_000_000u64),
+        U256::from(60_000u64),
+        U256::from(93_000_000u64),
+        U256::from(93_000_225u64),
+        U256::from(1_745_000_000u64),
+        reference,
+        Address::ZERO,
+        U256::ZERO,
+    )
+        .abi_encode_params();
     let log = build_log(
-        vec![topic0, address_to_topic(redeemer), address_to_topic(agent)],
-        encode_u256(5).to_vec(),
+        vec![
+            topic0,
+            address_to_topic(agent),
+            address_to_topic(redeemer),
+            uint_to_topic(7_654_321),
+        ],
+        data,
         CONTRACT,
     );
 
@@ -313,19 +717,83 @@ fn test_fasset_redemption_requested() {
         .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
         .unwrap();
     assert_eq!(event.event_type, EventType::RedemptionRequested);
+    let data = &event.decoded_data;
+    assert_eq!(data["agent"], format!("{:#x}", agent));
+    assert_eq!(data["redeemer"], format!("{:#x}", redeemer));
+    assert_eq!(data["request_id"], "7654321");
+    assert_eq!(
+        data["payment_address"],
+        "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh"
+    );
+    assert_eq!(data["value_uba"], "30000000");
+    assert_eq!(data["fee_uba"], "60000");
+    assert_eq!(data["payment_reference"], format!("{:#x}", reference));
+}
+
+#[test]
+fn test_fasset_redemption_performed() {
+    let decoder = FassetDecoder::new();
+    let topic0 = keccak256("RedemptionPerformed(address,address,uint64,bytes32,uint256,int256)");
+    let agent = Address::repeat_byte(0x77);
+    let redeemer = Address::repeat_byte(0x33);
+    let transaction_hash = B256::repeat_byte(0x99);
+
+    let data = (
+        transaction_hash,
+        U256::from(30_000_000u64),
+        I256::try_from(29_940_012i64).unwrap(),
+    )
+        .abi_encode_params();
+    let log = build_log(
+        vec![
+            topic0,
+            address_to_topic(agent),
+            address_to_topic(redeemer),
+            uint_to_topic(7_654_321),
+        ],
+        data,
+        CONTRACT,
+    );
+
+    let event = decoder
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::RedemptionPerformed);
+    let data = &event.decoded_data;
+    assert_eq!(data["agent"], format!("{:#x}", agent));
+    assert_eq!(data["redeemer"], format!("{:#x}", redeemer));
+    assert_eq!(data["request_id"], "7654321");
+    assert_eq!(data["transaction_hash"], format!("{:#x}", transaction_hash));
+    assert_eq!(data["redemption_amount_uba"], "30000000");
+    assert_eq!(data["spent_underlying_uba"], "29940012");
+}
+
+#[test]
+fn test_fasset_minting_executed_needs_agent_and_amounts() {
+    let decoder = FassetDecoder::new();
+    let topic0 = keccak256("MintingExecuted(address,uint256,uint256,uint256,uint256)");
+    let agent = Address::repeat_byte(0x22);
+
+    // Missing the reservation ID topic
+    let data = (U256::from(1u64), U256::ZERO, U256::ZERO).abi_encode_params();
+    let log = build_log(vec![topic0, address_to_topic(agent)], data, CONTRACT);
     assert!(
-        event.decoded_data["redeemer"]
-            .as_str()
-            .unwrap()
-            .contains("333333")
+        decoder
+            .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+            .is_none()
+    );
+
+    // Truncated data
+    let log = build_log(
+        vec![topic0, address_to_topic(agent), uint_to_topic(1)],
+        encode_u256(1).to_vec(),
+        CONTRACT,
     );
     assert!(
-        event.decoded_data["agent"]
-            .as_str()
-            .unwrap()
-            .contains("444444")
+        decoder
+            .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+            .is_none()
     );
-    assert_eq!(event.decoded_data["lots"].as_str().unwrap(), "5");
 }
 
 #[test]
@@ -455,6 +923,27 @@ fn test_registry_routes_ftso_event() {
     assert_eq!(event.event_type, EventType::PriceEpochFinalized);
 }
 
+#[test]
+fn test_registry_routes_fsp_event() {
+    let registry = DecoderRegistry::new();
+    let topic0 = keccak256("VoterRemoved(address,uint256)");
+
+    let log = build_log(
+        vec![
+            topic0,
+            address_to_topic(Address::repeat_byte(0xA1)),
+            B256::from(encode_u256(251)),
+        ],
+        vec![],
+        CONTRACT,
+    );
+
+    let event = registry
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::VoterRemoved);
+}
+
 #[test]
 fn test_registry_routes_fdc_event() {
     let registry = DecoderRegistry::new();
@@ -507,18 +996,53 @@ fn test_registry_with_generic_catches_unknown() {
     assert_eq!(event.event_type, EventType::GenericEvent);
 }
 
+#[test]
+fn test_registry_generic_addresses_scope_generic_decoding() {
+    let mut registry = DecoderRegistry::new();
+    let watched = Address::repeat_byte(0xaa);
+    registry.set_generic_addresses([watched]);
+    let unknown = keccak256("NobodyKnowsThisEvent(bytes32)");
+
+    let log = build_log(vec![unknown], vec![], watched);
+    let event = registry
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_eq!(event.event_type, EventType::GenericEvent);
+    assert_eq!(event.address, format!("{:#x}", watched));
+
+    let log = build_log(vec![unknown], vec![], CONTRACT);
+    assert!(
+        registry
+            .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+            .is_none()
+    );
+}
+
+#[test]
+fn test_registry_generic_addresses_keep_protocol_decoding() {
+    let mut registry = DecoderRegistry::new();
+    registry.set_generic_addresses([CONTRACT]);
+    let topic0 = keccak256("RewardEpochStarted(uint256,uint256)");
+
+    let log = build_log(vec![topic0, B256::from(encode_u256(1))], vec![], CONTRACT);
+    let event = registry
+        .decode(&log, BLOCK_NUMBER, now(), Chain::Flare)
+        .unwrap();
+    assert_ne!(event.event_type, EventType::GenericEvent);
+}
+
 #[test]
 fn test_registry_all_signatures_count() {
     let registry = DecoderRegistry::new();
-    // FTSO: 3, FDC: 3, FAsset: 5 = 11 total (Generic has 0)
-    assert_eq!(registry.all_signatures().len(), 11);
+    // FTSO: 5, FSP: 8, FDC: 3, FAsset: 7 = 23 total (Generic has 0)
+    assert_eq!(registry.all_signatures().len(), 23);
 }
 
 #[test]
 fn test_registry_with_generic_signatures_count() {
     let registry = DecoderRegistry::with_generic();
     // Generic returns 0 signatures, so same as without
-    assert_eq!(registry.all_signatures().len(), 11);
+    assert_eq!(registry.all_signatures().len(), 23);
 }
 
 #[test]
@@ -1,4 +1,5 @@
-use alloy::primitives::{B256, Log, keccak256};
+use alloy::primitives::{Address, B256, I256, Log, U256, keccak256};
+use alloy::sol_types::SolValue;
 use chrono::{DateTime, Utc};
 use flare_common::types::{Chain, DecodedEvent, EventType};
 use serde_json::json;
@@ -10,15 +11,35 @@ use crate::EventDecoder;
 /// Handles key FAsset Agent/Vault lifecycle events:
 /// - `CollateralDeposited(address agent, uint256 amount)`
 /// - `CollateralWithdrawn(address agent, uint256 amount)`
-/// - `MintingExecuted(address minter, address agent, uint256 lots)`
-/// - `RedemptionRequested(address redeemer, address agent, uint256 lots)`
-/// - `LiquidationStarted(address agent, uint256 timestamp)`
+/// - `LiquidationStarted(address indexed agentVault, uint256 timestamp)`
+///
+/// and the minting and redemption events of `IAssetManagerEvents`, with amounts in
+/// the underlying asset's base units (UBA):
+/// - `CollateralReserved(address indexed agentVault, address indexed minter,
+///   uint256 indexed collateralReservationId, uint256 valueUBA, uint256 feeUBA,
+///   uint256 firstUnderlyingBlock, uint256 lastUnderlyingBlock,
+///   uint256 lastUnderlyingTimestamp, string paymentAddress, bytes32 paymentReference,
+///   address executor, uint256 executorFeeNatWei)`
+/// - `MintingExecuted(address indexed agentVault, uint256 indexed collateralReservationId,
+///   uint256 mintedAmountUBA, uint256 agentFeeUBA, uint256 poolFeeUBA)`
+/// - `RedemptionRequested(address indexed agentVault, address indexed redeemer,
+///   uint256 indexed requestId, string paymentAddress, uint256 valueUBA, uint256 feeUBA,
+///   uint256 firstUnderlyingBlock, uint256 lastUnderlyingBlock,
+///   uint256 lastUnderlyingTimestamp, bytes32 paymentReference, address executor,
+///   uint256 executorFeeNatWei)`
+/// - `RedemptionPerformed(address indexed agentVault, address indexed redeemer,
+///   uint64 indexed requestId, bytes32 transactionHash, uint256 redemptionAmountUBA,
+///   int256 spentUnderlyingUBA)`
+///
+/// Each event's `agent` is the agent vault address.
 pub struct FassetDecoder {
     collateral_deposited: B256,
     collateral_withdrawn: B256,
     minting_executed: B256,
     redemption_requested: B256,
     liquidation_started: B256,
+    collateral_reserved: B256,
+    redemption_performed: B256,
 }
 
 impl FassetDecoder {
@@ -26,9 +47,17 @@ impl FassetDecoder {
         Self {
             collateral_deposited: keccak256("CollateralDeposited(address,uint256)"),
             collateral_withdrawn: keccak256("CollateralWithdrawn(address,uint256)"),
-            minting_executed: keccak256("MintingExecuted(address,address,uint256)"),
-            redemption_requested: keccak256("RedemptionRequested(address,address,uint256)"),
+            minting_executed: keccak256("MintingExecuted(address,uint256,uint256,uint256,uint256)"),
+            redemption_requested: keccak256(
+                "RedemptionRequested(address,address,uint256,string,uint256,uint256,uint256,uint256,uint256,bytes32,address,uint256)",
+            ),
             liquidation_started: keccak256("LiquidationStarted(address,uint256)"),
+            collateral_reserved: keccak256(
+                "CollateralReserved(address,address,uint256,uint256,uint256,uint256,uint256,uint256,string,bytes32,address,uint256)",
+            ),
+            redemption_performed: keccak256(
+                "RedemptionPerformed(address,address,uint64,bytes32,uint256,int256)",
+            ),
         }
     }
 
@@ -36,6 +65,10 @@ impl FassetDecoder {
         format!("0x{}", alloy::hex::encode(&topic.as_slice()[12..32]))
     }
 
+    fn decode_u256_from_topic(topic: &B256) -> String {
+        U256::from_be_bytes(topic.0).to_string()
+    }
+
     fn decode_u256_from_data(data: &[u8], offset: usize) -> Option<String> {
         if data.len() >= offset + 32 {
             let bytes: [u8; 32] = data[offset..offset + 32].try_into().ok()?;
@@ -60,6 +93,8 @@ impl EventDecoder for FassetDecoder {
             self.minting_executed,
             self.redemption_requested,
             self.liquidation_started,
+            self.collateral_reserved,
+            self.redemption_performed,
         ]
     }
 
@@ -74,80 +109,137 @@ impl EventDecoder for FassetDecoder {
         let address = format!("{:#x}", log.address);
         let data = log.data.data.as_ref();
 
-        if *topic0 == self.collateral_deposited {
-            let agent = log.topics().get(1).map(Self::decode_address_from_topic);
-            let amount = Self::decode_u256_from_data(data, 0);
-
-            Some(DecodedEvent {
-                tx_hash: String::new(),
-                log_index: None,
-                block_number,
-                block_timestamp,
-                chain,
-                address,
-                event_type: EventType::CollateralDeposited,
-                decoded_data: json!({ "agent": agent, "amount": amount }),
-            })
-        } else if *topic0 == self.collateral_withdrawn {
-            let agent = log.topics().get(1).map(Self::decode_address_from_topic);
-            let amount = Self::decode_u256_from_data(data, 0);
-
-            Some(DecodedEvent {
-                tx_hash: String::new(),
-                log_index: None,
-                block_number,
-                block_timestamp,
-                chain,
-                address,
-                event_type: EventType::CollateralWithdrawn,
-                decoded_data: json!({ "agent": agent, "amount": amount }),
-            })
-        } else if *topic0 == self.minting_executed {
-            let minter = log.topics().get(1).map(Self::decode_address_from_topic);
-            let agent = log.topics().get(2).map(Self::decode_address_from_topic);
-            let lots = Self::decode_u256_from_data(data, 0);
-
-            Some(DecodedEvent {
-                tx_hash: String::new(),
-                log_index: None,
-                block_number,
-                block_timestamp,
-                chain,
-                address,
-                event_type: EventType::MintingExecuted,
-                decoded_data: json!({ "minter": minter, "agent": agent, "lots": lots }),
-            })
-        } else if *topic0 == self.redemption_requested {
-            let redeemer = log.topics().get(1).map(Self::decode_address_from_topic);
-            let agent = log.topics().get(2).map(Self::decode_address_from_topic);
-            let lots = Self::decode_u256_from_data(data, 0);
-
-            Some(DecodedEvent {
-                tx_hash: String::new(),
-                log_index: None,
-                block_number,
-                block_timestamp,
-                chain,
-                address,
-                event_type: EventType::RedemptionRequested,
-                decoded_data: json!({ "redeemer": redeemer, "agent": agent, "lots": lots }),
-            })
-        } else if *topic0 == self.liquidation_started {
-            let agent = log.topics().get(1).map(Self::decode_address_from_topic);
-
-            Some(DecodedEvent {
-                tx_hash: String::new(),
-                log_index: None,
-                block_number,
-                block_timestamp,
-                chain,
-                address,
-                event_type: EventType::LiquidationStarted,
-                decoded_data: json!({ "agent": agent }),
-            })
-        } else {
-            None
-        }
+        let (event_type, decoded_data) =
+            if *topic0 == self.collateral_deposited || *topic0 == self.collateral_withdrawn {
+                let agent = log.topics().get(1).map(Self::decode_address_from_topic);
+                let amount = Self::decode_u256_from_data(data, 0);
+                let event_type = if *topic0 == self.collateral_deposited {
+                    EventType::CollateralDeposited
+                } else {
+                    EventType::CollateralWithdrawn
+                };
+
+                (event_type, json!({ "agent": agent, "amount": amount }))
+            } else if *topic0 == self.liquidation_started {
+                let agent = log.topics().get(1).map(Self::decode_address_from_topic);
+
+                (EventType::LiquidationStarted, json!({ "agent": agent }))
+            } else if *topic0 == self.collateral_reserved {
+                let agent = log.topics().get(1).map(Self::decode_address_from_topic)?;
+                let minter = log.topics().get(2).map(Self::decode_address_from_topic)?;
+                let reservation_id = log.topics().get(3).map(Self::decode_u256_from_topic)?;
+                let (
+                value,
+                fee,
+                _,
+                last_block,
+                last_timestamp,
+                payment_address,
+                reference,
+                executor,
+                _,
+            ) = <(U256, U256, U256, U256, U256, String, B256, Address, U256)>::abi_decode_params(
+                data,
+            )
+            .ok()?;
+
+                (
+                    EventType::CollateralReserved,
+                    json!({
+                        "agent": agent,
+                        "minter": minter,
+                        "collateral_reservation_id": reservation_id,
+                        "value_uba": value.to_string(),
+                        "fee_uba": fee.to_string(),
+                        "last_underlying_block": last_block.to_string(),
+                        "last_underlying_timestamp": last_timestamp.to_string(),
+                        "payment_address": payment_address,
+                        "payment_reference": format!("{:#x}", reference),
+                        "executor": format!("{:#x}", executor),
+                    }),
+                )
+            } else if *topic0 == self.minting_executed {
+                let agent = log.topics().get(1).map(Self::decode_address_from_topic)?;
+                let reservation_id = log.topics().get(2).map(Self::decode_u256_from_topic)?;
+                let (minted, agent_fee, pool_fee) =
+                    <(U256, U256, U256)>::abi_decode_params(data).ok()?;
+
+                (
+                    EventType::MintingExecuted,
+                    json!({
+                        "agent": agent,
+                        "collateral_reservation_id": reservation_id,
+                        "minted_amount_uba": minted.to_string(),
+                        "agent_fee_uba": agent_fee.to_string(),
+                        "pool_fee_uba": pool_fee.to_string(),
+                    }),
+                )
+            } else if *topic0 == self.redemption_requested {
+                let agent = log.topics().get(1).map(Self::decode_address_from_topic)?;
+                let redeemer = log.topics().get(2).map(Self::decode_address_from_topic)?;
+                let request_id = log.topics().get(3).map(Self::decode_u256_from_topic)?;
+                let (
+                payment_address,
+                value,
+                fee,
+                _,
+                last_block,
+                last_timestamp,
+                reference,
+                executor,
+                _,
+            ) = <(String, U256, U256, U256, U256, U256, B256, Address, U256)>::abi_decode_params(
+                data,
+            )
+            .ok()?;
+
+                (
+                    EventType::RedemptionRequested,
+                    json!({
+                        "agent": agent,
+                        "redeemer": redeemer,
+                        "request_id": request_id,
+                        "payment_address": payment_address,
+                        "value_uba": value.to_string(),
+                        "fee_uba": fee.to_string(),
+                        "last_underlying_block": last_block.to_string(),
+                        "last_underlying_timestamp": last_timestamp.to_string(),
+                        "payment_reference": format!("{:#x}", reference),
+                        "executor": format!("{:#x}", executor),
+                    }),
+                )
+            } else if *topic0 == self.redemption_performed {
+                let agent = log.topics().get(1).map(Self::decode_address_from_topic)?;
+                let redeemer = log.topics().get(2).map(Self::decode_address_from_topic)?;
+                let request_id = log.topics().get(3).map(Self::decode_u256_from_topic)?;
+                let (transaction_hash, redeemed, spent) =
+                    <(B256, U256, I256)>::abi_decode_params(data).ok()?;
+
+                (
+                    EventType::RedemptionPerformed,
+                    json!({
+                        "agent": agent,
+                        "redeemer": redeemer,
+                        "request_id": request_id,
+                        "transaction_hash": format!("{:#x}", transaction_hash),
+                        "redemption_amount_uba": redeemed.to_string(),
+                        "spent_underlying_uba": spent.to_string(),
+                    }),
+                )
+            } else {
+                return None;
+            };
+
+        Some(DecodedEvent {
+            tx_hash: String::new(),
+            log_index: None,
+            block_number,
+            block_timestamp,
+            chain,
+            address,
+            event_type,
+            decoded_data,
+        })
     }
 
     fn name(&self) -> &'static str {
@@ -162,6 +254,6 @@ mod tests {
     #[test]
     fn test_event_signatures() {
         let decoder = FassetDecoder::new();
-        assert_eq!(decoder.event_signatures().len(), 5);
+        assert_eq!(decoder.event_signatures().len(), 7);
     }
 }
@@ -0,0 +1,279 @@
+use alloy::primitives::{Address, B256, Bytes, Log, U256, keccak256};
+use alloy::sol_types::SolValue;
+use chrono::{DateTime, Utc};
+use flare_common::types::{Chain, DecodedEvent, EventType};
+use serde_json::json;
+
+use crate::EventDecoder;
+
+/// Flare Systems Protocol (FSP) event decoder.
+///
+/// Handles the FTSO v2 protocol events providers' voters take part in, from
+/// `Relay`, `VoterRegistry` and `FlareSystemsManager`:
+/// - `SigningPolicyInitialized(uint24 indexed rewardEpochId, uint32 startVotingRoundId,
+///   uint16 threshold, uint256 seed, address[] voters, uint16[] weights,
+///   bytes signingPolicyBytes, uint64 timestamp)`
+/// - `ProtocolMessageRelayed(uint8 indexed protocolId, uint32 indexed votingRoundId,
+///   bool isSecureRandom, bytes32 merkleRoot)`
+/// - `VoterRegistered(address indexed voter, uint24 indexed rewardEpochId,
+///   address indexed signingPolicyAddress, address submitAddress,
+///   address submitSignaturesAddress, bytes32 publicKeyPart1, bytes32 publicKeyPart2,
+///   uint256 registrationWeight)`
+/// - `VoterRemoved(address indexed voter, uint256 indexed rewardEpochId)`
+/// - `RewardEpochStarted(uint24 indexed rewardEpochId, uint32 startVotingRoundId,
+///   uint64 timestamp)`
+/// - `SigningPolicySigned(uint24 indexed rewardEpochId, address indexed signingPolicyAddress,
+///   address indexed voter, uint64 timestamp, bool thresholdReached)`
+/// - `UptimeVoteSigned(uint24 indexed rewardEpochId, address indexed signingPolicyAddress,
+///   address indexed voter, bytes32 uptimeVoteHash, uint64 timestamp, bool thresholdReached)`
+/// - `RewardsSigned(uint24 indexed rewardEpochId, address indexed signingPolicyAddress,
+///   address indexed voter, bytes32 rewardsHash,
+///   (uint256 rewardManagerId, uint256 noOfWeightBasedClaims)[] noOfWeightBasedClaims,
+///   uint64 timestamp, bool thresholdReached)`
+///
+/// Commits, reveals and signatures are sent to `Submission` as calldata and
+/// emit no events of their own.
+pub struct FspDecoder {
+    signing_policy_initialized: B256,
+    protocol_message_relayed: B256,
+    voter_registered: B256,
+    voter_removed: B256,
+    reward_epoch_started: B256,
+    signing_policy_signed: B256,
+    uptime_vote_signed: B256,
+    rewards_signed: B256,
+}
+
+impl FspDecoder {
+    pub fn new() -> Self {
+        Self {
+            signing_policy_initialized: keccak256(
+                "SigningPolicyInitialized(uint24,uint32,uint16,uint256,address[],uint16[],bytes,uint64)",
+            ),
+            protocol_message_relayed: keccak256(
+                "ProtocolMessageRelayed(uint8,uint32,bool,bytes32)",
+            ),
+            voter_registered: keccak256(
+                "VoterRegistered(address,uint24,address,address,address,bytes32,bytes32,uint256)",
+            ),
+            voter_removed: keccak256("VoterRemoved(address,uint256)"),
+            reward_epoch_started: keccak256("RewardEpochStarted(uint24,uint32,uint64)"),
+            signing_policy_signed: keccak256(
+                "SigningPolicySigned(uint24,address,address,uint64,bool)",
+            ),
+            uptime_vote_signed: keccak256(
+                "UptimeVoteSigned(uint24,address,address,bytes32,uint64,bool)",
+            ),
+            rewards_signed: keccak256(
+                "RewardsSigned(uint24,address,address,bytes32,(uint256,uint256)[],uint64,bool)",
+            ),
+        }
+    }
+}
+
+impl Default for FspDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn u64_from_topic(topic: &B256) -> u64 {
+    u64::from_be_bytes(topic.as_slice()[24..32].try_into().unwrap_or_default())
+}
+
+fn address_from_topic(topic: &B256) -> String {
+    format!("0x{}", alloy::hex::encode(&topic.as_slice()[12..32]))
+}
+
+fn address_hex(address: &Address) -> String {
+    format!("{:#x}", address)
+}
+
+impl EventDecoder for FspDecoder {
+    fn event_signatures(&self) -> Vec<B256> {
+        vec![
+            self.signing_policy_initialized,
+            self.protocol_message_relayed,
+            self.voter_registered,
+            self.voter_removed,
+            self.reward_epoch_started,
+            self.signing_policy_signed,
+            self.uptime_vote_signed,
+            self.rewards_signed,
+        ]
+    }
+
+    fn decode(
+        &self,
+        log: &Log,
+        block_number: u64,
+        block_timestamp: DateTime<Utc>,
+        chain: Chain,
+    ) -> Option<DecodedEvent> {
+        let topic0 = log.topics().first()?;
+        let data = log.data.data.as_ref();
+
+        let (event_type, decoded_data) = if *topic0 == self.signing_policy_initialized {
+            let reward_epoch_id = log.topics().get(1).map(u64_from_topic)?;
+            let (start_voting_round_id, threshold, seed, voters, weights, _policy_bytes, timestamp) =
+                <(u32, u16, U256, Vec<Address>, Vec<u16>, Bytes, u64)>::abi_decode_params(data)
+                    .ok()?;
+            if voters.len() != weights.len() {
+                return None;
+            }
+            let total_weight: u64 = weights.iter().map(|w| *w as u64).sum();
+
+            (
+                EventType::SigningPolicyInitialized,
+                json!({
+                    "reward_epoch_id": reward_epoch_id,
+                    "start_voting_round_id": start_voting_round_id,
+                    "threshold": threshold,
+                    "seed": seed.to_string(),
+                    "voters": voters.iter().map(address_hex).collect::<Vec<_>>(),
+                    "weights": weights,
+                    "total_weight": total_weight,
+                    "timestamp": timestamp,
+                }),
+            )
+        } else if *topic0 == self.protocol_message_relayed {
+            let protocol_id = log.topics().get(1).map(u64_from_topic)?;
+            let voting_round_id = log.topics().get(2).map(u64_from_topic)?;
+            let (is_secure_random, merkle_root) = <(bool, B256)>::abi_decode_params(data).ok()?;
+
+            (
+                EventType::ProtocolMessageRelayed,
+                json!({
+                    "protocol_id": protocol_id,
+                    "voting_round_id": voting_round_id,
+                    "is_secure_random": is_secure_random,
+                    "merkle_root": format!("{:#x}", merkle_root),
+                }),
+            )
+        } else if *topic0 == self.voter_registered {
+            let voter = log.topics().get(1).map(address_from_topic)?;
+            let reward_epoch_id = log.topics().get(2).map(u64_from_topic)?;
+            let signing_policy_address = log.topics().get(3).map(address_from_topic)?;
+            let (submit_address, submit_signatures_address, key_part1, key_part2, weight) =
+                <(Address, Address, B256, B256, U256)>::abi_decode_params(data).ok()?;
+
+            (
+                EventType::VoterRegistered,
+                json!({
+                    "voter": voter,
+                    "reward_epoch_id": reward_epoch_id,
+                    "signing_policy_address": signing_policy_address,
+                    "submit_address": address_hex(&submit_address),
+                    "submit_signatures_address": address_hex(&submit_signatures_address),
+                    "public_key": format!(
+                        "0x{}{}",
+                        alloy::hex::encode(key_part1),
+                        alloy::hex::encode(key_part2)
+                    ),
+                    "registration_weight": weight.to_string(),
+                }),
+            )
+        } else if *topic0 == self.voter_removed {
+            let voter = log.topics().get(1).map(address_from_topic)?;
+            let reward_epoch_id = log.topics().get(2).map(u64_from_topic)?;
+
+            (
+                EventType::VoterRemoved,
+                json!({ "voter": voter, "reward_epoch_id": reward_epoch_id }),
+            )
+        } else if *topic0 == self.reward_epoch_started {
+            let reward_epoch_id = log.topics().get(1).map(u64_from_topic)?;
+            let (start_voting_round_id, timestamp) = <(u32, u64)>::abi_decode_params(data).ok()?;
+
+            (
+                EventType::RewardEpochStarted,
+                json!({
+                    "reward_epoch_id": reward_epoch_id,
+                    "start_voting_round_id": start_voting_round_id,
+                    "timestamp": timestamp,
+                }),
+            )
+        } else if *topic0 == self.signing_policy_signed
+            || *topic0 == self.uptime_vote_signed
+            || *topic0 == self.rewards_signed
+        {
+            // Signatures of the next signing policy, uptime vote and reward distribution
+            // share their indexed fields
+            let reward_epoch_id = log.topics().get(1).map(u64_from_topic)?;
+            let signing_policy_address = log.topics().get(2).map(address_from_topic)?;
+            let voter = log.topics().get(3).map(address_from_topic)?;
+            let mut decoded = json!({
+                "reward_epoch_id": reward_epoch_id,
+                "signing_policy_address": signing_policy_address,
+                "voter": voter,
+            });
+
+            let event_type = if *topic0 == self.signing_policy_signed {
+                let (timestamp, threshold_reached) = <(u64, bool)>::abi_decode_params(data).ok()?;
+                decoded["timestamp"] = json!(timestamp);
+                decoded["threshold_reached"] = json!(threshold_reached);
+                EventType::SigningPolicySigned
+            } else if *topic0 == self.uptime_vote_signed {
+                let (hash, timestamp, threshold_reached) =
+                    <(B256, u64, bool)>::abi_decode_params(data).ok()?;
+                decoded["uptime_vote_hash"] = json!(format!("{:#x}", hash));
+                decoded["timestamp"] = json!(timestamp);
+                decoded["threshold_reached"] = json!(threshold_reached);
+                EventType::UptimeVoteSigned
+            } else {
+                let (hash, claims, timestamp, threshold_reached) =
+                    <(B256, Vec<(U256, U256)>, u64, bool)>::abi_decode_params(data).ok()?;
+                decoded["rewards_hash"] = json!(format!("{:#x}", hash));
+                decoded["weight_based_claims"] = json!(
+                    claims
+                        .iter()
+                        .map(|(manager, claims)| json!({
+                            "reward_manager_id": manager.to_string(),
+                            "claims": claims.to_string(),
+                        }))
+                        .collect::<Vec<_>>()
+                );
+                decoded["timestamp"] = json!(timestamp);
+                decoded["threshold_reached"] = json!(threshold_reached);
+                EventType::RewardsSigned
+            };
+            (event_type, decoded)
+        } else {
+            return None;
+        };
+
+        Some(DecodedEvent {
+            tx_hash: String::new(),
+            log_index: None,
+            block_number,
+            block_timestamp,
+            chain,
+            address: format!("{:#x}", log.address),
+            event_type,
+            decoded_data,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "Flare Systems Protocol"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_signatures() {
+        let decoder = FspDecoder::new();
+        let signatures = decoder.event_signatures();
+        assert_eq!(signatures.len(), 8);
+        // Unlike the FTSO v1 `RewardEpochStarted(uint256,uint256)`
+        assert!(!signatures.contains(&keccak256("RewardEpochStarted(uint256,uint256)")));
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(FspDecoder::new().name(), "Flare Systems Protocol");
+    }
+}
@@ -1,4 +1,5 @@
-use alloy::primitives::{B256, Log, keccak256};
+use alloy::primitives::{B256, Log, U256, keccak256};
+use alloy::sol_types::SolValue;
 use chrono::{DateTime, Utc};
 use flare_common::types::{Chain, DecodedEvent, EventType};
 use serde_json::json;
@@ -11,10 +12,19 @@ use crate::EventDecoder;
 /// - `PriceEpochFinalized(uint256 epochId, uint256 timestamp)`
 /// - `VotePowerChanged(address provider, uint256 newVotePower)`
 /// - `RewardEpochStarted(uint256 epochId, uint256 timestamp)`
+/// - `FtsoFeedPublished(uint32 indexed votingRoundId, bytes21 indexed id, int32 value,
+///   uint16 turnoutBIPS, int8 decimals)` — anchor feed values, from `FtsoFeedPublisher`
+/// - `FastUpdateFeeds(uint256 indexed votingEpochId, uint256[] feeds, int8[] decimals)`
+///   — block-latency feed values, from `FastUpdater`
+///
+/// Fast update values are listed by feed index; the feed IDs for those indices come
+/// from `FastUpdatesConfiguration.getFeedIds()`.
 pub struct FtsoDecoder {
     price_epoch_finalized: B256,
     vote_power_changed: B256,
     reward_epoch_started: B256,
+    ftso_feed_published: B256,
+    fast_update_feeds: B256,
 }
 
 impl FtsoDecoder {
@@ -23,10 +33,41 @@ impl FtsoDecoder {
             price_epoch_finalized: keccak256("PriceEpochFinalized(uint256,uint256)"),
             vote_power_changed: keccak256("VotePowerChanged(address,uint256)"),
             reward_epoch_started: keccak256("RewardEpochStarted(uint256,uint256)"),
+            ftso_feed_published: keccak256("FtsoFeedPublished(uint32,bytes21,int32,uint16,int8)"),
+            fast_update_feeds: keccak256("FastUpdateFeeds(uint256,uint256[],int8[])"),
         }
     }
 }
 
+/// Hex form of a 21-byte FTSO feed ID, e.g. `0x01464c522f555344…` for FLR/USD.
+pub fn feed_id_hex(id: &[u8]) -> String {
+    format!("0x{}", alloy::hex::encode(id))
+}
+
+/// Human-readable name of a 21-byte FTSO feed ID: the bytes after the category
+/// byte with trailing zero padding removed, e.g. `FLR/USD`.
+pub fn feed_name(id: &[u8]) -> String {
+    let name = id.get(1..).unwrap_or_default();
+    let end = name.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+    String::from_utf8_lossy(&name[..end]).into_owned()
+}
+
+/// 21-byte FTSO feed ID for `name` in `category` (1 = crypto), the inverse of `feed_name`.
+pub fn feed_id(category: u8, name: &str) -> Option<[u8; 21]> {
+    let bytes = name.as_bytes();
+    if bytes.len() > 20 {
+        return None;
+    }
+    let mut id = [0u8; 21];
+    id[0] = category;
+    id[1..=bytes.len()].copy_from_slice(bytes);
+    Some(id)
+}
+
+fn u64_from_topic(topic: &B256) -> u64 {
+    u64::from_be_bytes(topic.as_slice()[24..32].try_into().unwrap_or_default())
+}
+
 impl Default for FtsoDecoder {
     fn default() -> Self {
         Self::new()
@@ -39,6 +80,8 @@ impl EventDecoder for FtsoDecoder {
             self.price_epoch_finalized,
             self.vote_power_changed,
             self.reward_epoch_started,
+            self.ftso_feed_published,
+            self.fast_update_feeds,
         ]
     }
 
@@ -115,6 +158,52 @@ impl EventDecoder for FtsoDecoder {
                     "epoch_id": epoch_id,
                 }),
             })
+        } else if *topic0 == self.ftso_feed_published {
+            let voting_round_id = log.topics().get(1).map(u64_from_topic)?;
+            // Indexed bytes21 are left-aligned in the topic
+            let id = &log.topics().get(2)?.as_slice()[..21];
+            let (value, turnout_bips, decimals) =
+                <(i32, u16, i8)>::abi_decode_params(log.data.data.as_ref()).ok()?;
+
+            Some(DecodedEvent {
+                tx_hash: String::new(),
+                log_index: None,
+                block_number,
+                block_timestamp,
+                chain,
+                address,
+                event_type: EventType::FtsoFeedPublished,
+                decoded_data: json!({
+                    "voting_round_id": voting_round_id,
+                    "feed_id": feed_id_hex(id),
+                    "feed_name": feed_name(id),
+                    "value": value,
+                    "decimals": decimals,
+                    "turnout_bips": turnout_bips,
+                }),
+            })
+        } else if *topic0 == self.fast_update_feeds {
+            let voting_epoch_id = log.topics().get(1).map(u64_from_topic)?;
+            let (values, decimals) =
+                <(Vec<U256>, Vec<i8>)>::abi_decode_params(log.data.data.as_ref()).ok()?;
+            if values.len() != decimals.len() {
+                return None;
+            }
+
+            Some(DecodedEvent {
+                tx_hash: String::new(),
+                log_index: None,
+                block_number,
+                block_timestamp,
+                chain,
+                address,
+                event_type: EventType::FastUpdateFeeds,
+                decoded_data: json!({
+                    "voting_epoch_id": voting_epoch_id,
+                    "values": values.iter().map(U256::to_string).collect::<Vec<_>>(),
+                    "decimals": decimals,
+                }),
+            })
         } else {
             None
         }
@@ -132,7 +221,18 @@ mod tests {
     #[test]
     fn test_event_signatures_not_empty() {
         let decoder = FtsoDecoder::new();
-        assert_eq!(decoder.event_signatures().len(), 3);
+        assert_eq!(decoder.event_signatures().len(), 5);
+    }
+
+    #[test]
+    fn test_feed_name_round_trip() {
+        let id = feed_id(1, "FLR/USD").unwrap();
+        assert_eq!(
+            feed_id_hex(&id),
+            "0x01464c522f55534400000000000000000000000000"
+        );
+        assert_eq!(feed_name(&id), "FLR/USD");
+        assert!(feed_id(1, "A_NAME_THAT_IS_TOO_LONG").is_none());
     }
 
     #[test]
@@ -1,9 +1,12 @@
 pub mod fasset;
 pub mod fdc;
+pub mod fsp;
 pub mod ftso;
 pub mod generic;
 
-use alloy::primitives::Log;
+use std::collections::HashSet;
+
+use alloy::primitives::{Address, Log};
 use chrono::{DateTime, Utc};
 use flare_common::types::{Chain, DecodedEvent};
 
@@ -29,21 +32,25 @@ pub trait EventDecoder: Send + Sync {
 /// Registry of all available decoders, used by the indexer's event router.
 pub struct DecoderRegistry {
     decoders: Vec<Box<dyn EventDecoder>>,
+    /// Addresses whose unmatched logs are captured by the generic decoder
+    generic_addresses: HashSet<Address>,
 }
 
 impl DecoderRegistry {
     /// Create a new registry with all protocol decoders.
     ///
     /// Note: `GenericDecoder` is NOT included by default to avoid flooding the
-    /// database with every unmatched event on the chain. Use `with_generic()`
-    /// to add it for specific monitored addresses.
+    /// database with every unmatched event on the chain. Use
+    /// `set_generic_addresses()` to apply it to specific monitored addresses.
     pub fn new() -> Self {
         Self {
             decoders: vec![
                 Box::new(ftso::FtsoDecoder::new()),
+                Box::new(fsp::FspDecoder::new()),
                 Box::new(fdc::FdcDecoder::new()),
                 Box::new(fasset::FassetDecoder::new()),
             ],
+            generic_addresses: HashSet::new(),
         }
     }
 
@@ -53,13 +60,26 @@ impl DecoderRegistry {
         Self {
             decoders: vec![
                 Box::new(ftso::FtsoDecoder::new()),
+                Box::new(fsp::FspDecoder::new()),
                 Box::new(fdc::FdcDecoder::new()),
                 Box::new(fasset::FassetDecoder::new()),
                 Box::new(generic::GenericDecoder::new()),
             ],
+            generic_addresses: HashSet::new(),
         }
     }
 
+    /// Capture unmatched logs from these addresses as generic events, replacing
+    /// any previously set. Logs from other addresses still need a protocol decoder.
+    pub fn set_generic_addresses(&mut self, addresses: impl IntoIterator<Item = Address>) {
+        self.generic_addresses = addresses.into_iter().collect();
+    }
+
+    /// Addresses whose unmatched logs are captured as generic events.
+    pub fn generic_addresses(&self) -> &HashSet<Address> {
+        &self.generic_addresses
+    }
+
     /// Try to decode a log using all registered decoders.
     /// Returns the first successful decode, or `None`.
     pub fn decode(
@@ -79,6 +99,9 @@ impl DecoderRegistry {
                 return Some(event);
             }
         }
+        if self.generic_addresses.contains(&log.address) {
+            return generic::GenericDecoder.decode(log, block_number, block_timestamp, chain);
+        }
         None
     }
 
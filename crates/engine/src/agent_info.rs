@@ -0,0 +1,143 @@
+//! On-chain agent vault state, read with `AssetManager.getAgentInfo`.
+//!
+//! The CR calculator tracks vaults from FAsset events, which only carry changes.
+//! An agent that was already active before its first processed event would start
+//! from an empty vault, so each vault is first seeded from the AssetManager's view
+//! of it at a known block (see `CrCalculator`).
+//!
+//! Reading at an older block needs a node that still has that block's state.
+
+use std::collections::HashMap;
+
+use alloy::primitives::{Address, U256};
+use alloy::providers::{DynProvider, Provider, ProviderBuilder};
+use alloy::rpc::types::TransactionRequest;
+use alloy::sol;
+use alloy::sol_types::SolCall;
+
+use flare_common::types::Chain;
+
+sol! {
+    /// Returns the agent's `AgentInfo.Info` struct. It has more fields than the ABI
+    /// decoder's tuples support, so the words needed are read by position.
+    function getAgentInfo(address _agentVault) external view;
+}
+
+/// Positions of the fields read from the head of `AgentInfo.Info`. Later FAssets
+/// releases only append fields, so these hold across them.
+const TOTAL_VAULT_COLLATERAL_WEI: usize = 13;
+const TOTAL_POOL_COLLATERAL_NAT_WEI: usize = 17;
+const MINTED_UBA: usize = 24;
+const RESERVED_UBA: usize = 25;
+const REDEEMING_UBA: usize = 26;
+
+/// Collateral and backed amounts of an agent vault, as its AssetManager reports them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AgentState {
+    /// Vault collateral in token base units
+    pub vault_collateral: f64,
+    /// Pool collateral in native token base units (wei)
+    pub pool_collateral: f64,
+    /// Amounts in the underlying asset's base units (UBA)
+    pub minted_uba: f64,
+    pub reserved_uba: f64,
+    pub redeeming_uba: f64,
+}
+
+impl AgentState {
+    /// Decode the return data of `getAgentInfo`: the offset of the struct, then its head.
+    pub fn decode(data: &[u8]) -> anyhow::Result<Self> {
+        let word = |at: usize| -> anyhow::Result<U256> {
+            data.get(at..at + 32)
+                .map(U256::from_be_slice)
+                .ok_or_else(|| anyhow::anyhow!("getAgentInfo returned {} bytes", data.len()))
+        };
+        let head: usize = word(0)?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("getAgentInfo returned an invalid offset"))?;
+        let field = |index: usize| -> anyhow::Result<f64> {
+            Ok(word(head.saturating_add(index * 32))?
+                .to_string()
+                .parse()
+                .unwrap_or(f64::MAX))
+        };
+
+        Ok(Self {
+            vault_collateral: field(TOTAL_VAULT_COLLATERAL_WEI)?,
+            pool_collateral: field(TOTAL_POOL_COLLATERAL_NAT_WEI)?,
+            minted_uba: field(MINTED_UBA)?,
+            reserved_uba: field(RESERVED_UBA)?,
+            redeeming_uba: field(REDEEMING_UBA)?,
+        })
+    }
+}
+
+/// Reads agent vault state from each chain's RPC endpoint.
+#[derive(Clone, Default)]
+pub struct AgentInfoReader {
+    providers: HashMap<Chain, DynProvider>,
+}
+
+impl AgentInfoReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read agents on `chain` through `rpc_url`.
+    pub fn with_chain(mut self, chain: Chain, rpc_url: &str) -> anyhow::Result<Self> {
+        let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+        self.providers.insert(chain, provider.erased());
+        Ok(self)
+    }
+
+    /// State of `agent` at the end of `block`, from the AssetManager at `asset_manager`.
+    pub async fn agent_state(
+        &self,
+        chain: Chain,
+        asset_manager: Address,
+        agent: Address,
+        block: u64,
+    ) -> anyhow::Result<AgentState> {
+        let provider = self
+            .providers
+            .get(&chain)
+            .ok_or_else(|| anyhow::anyhow!("no RPC endpoint configured for {}", chain))?;
+        let call = getAgentInfoCall { _agentVault: agent };
+        let data = provider
+            .call(
+                TransactionRequest::default()
+                    .to(asset_manager)
+                    .input(call.abi_encode().into()),
+            )
+            .block(block.into())
+            .await?;
+        AgentState::decode(&data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_agent_state() {
+        // Offset of the struct, then head words with field i set to i + 1
+        let mut data = U256::from(32).to_be_bytes::<32>().to_vec();
+        for i in 0..40u64 {
+            data.extend_from_slice(&U256::from(i + 1).to_be_bytes::<32>());
+        }
+        assert_eq!(
+            AgentState::decode(&data).unwrap(),
+            AgentState {
+                vault_collateral: 14.0,
+                pool_collateral: 18.0,
+                minted_uba: 25.0,
+                reserved_uba: 26.0,
+                redeeming_uba: 27.0,
+            }
+        );
+
+        data.truncate(32 * 20);
+        assert!(AgentState::decode(&data).is_err());
+    }
+}
@@ -92,6 +92,13 @@ impl AddressAnalyzer {
                 EventType::PriceEpochFinalized,
                 EventType::VotePowerChanged,
                 EventType::RewardEpochStarted,
+                // Matched on the provider's voter address
+                EventType::SigningPolicyInitialized,
+                EventType::VoterRegistered,
+                EventType::VoterRemoved,
+                EventType::SigningPolicySigned,
+                EventType::RewardsSigned,
+                EventType::UptimeVoteSigned,
             ],
             AddressType::FassetAgent => vec![
                 EventType::CollateralDeposited,
@@ -99,9 +106,19 @@ impl AddressAnalyzer {
                 EventType::MintingExecuted,
                 EventType::RedemptionRequested,
                 EventType::LiquidationStarted,
+                EventType::CollateralReserved,
+                EventType::RedemptionPerformed,
+                EventType::CrWarning,
+                EventType::CrCritical,
+                EventType::LiquidationImminent,
             ],
             AddressType::GenericContract => vec![EventType::GenericEvent],
             AddressType::Eoa => vec![],
+            AddressType::FtsoFeed => vec![
+                EventType::PriceThreshold,
+                EventType::PriceMove,
+                EventType::FeedStale,
+            ],
         }
     }
 
@@ -112,6 +129,7 @@ impl AddressAnalyzer {
             AddressType::FassetAgent => "FAsset Agent Vault".to_string(),
             AddressType::GenericContract => "Smart Contract".to_string(),
             AddressType::Eoa => "Externally Owned Account".to_string(),
+            AddressType::FtsoFeed => "FTSO Feed".to_string(),
         }
     }
 }
@@ -123,18 +141,22 @@ mod tests {
     #[test]
     fn test_ftso_provider_events() {
         let events = AddressAnalyzer::events_for_type(&AddressType::FtsoProvider);
-        assert_eq!(events.len(), 3);
+        assert_eq!(events.len(), 9);
         assert!(events.contains(&EventType::PriceEpochFinalized));
         assert!(events.contains(&EventType::VotePowerChanged));
         assert!(events.contains(&EventType::RewardEpochStarted));
+        assert!(events.contains(&EventType::VoterRemoved));
+        assert!(events.contains(&EventType::SigningPolicyInitialized));
     }
 
     #[test]
     fn test_fasset_agent_events() {
         let events = AddressAnalyzer::events_for_type(&AddressType::FassetAgent);
-        assert_eq!(events.len(), 5);
+        assert_eq!(events.len(), 10);
         assert!(events.contains(&EventType::CollateralDeposited));
         assert!(events.contains(&EventType::LiquidationStarted));
+        assert!(events.contains(&EventType::CrWarning));
+        assert!(events.contains(&EventType::LiquidationImminent));
     }
 
     #[test]
@@ -144,6 +166,19 @@ mod tests {
         assert!(events.contains(&EventType::GenericEvent));
     }
 
+    #[test]
+    fn test_ftso_feed_events() {
+        let events = AddressAnalyzer::events_for_type(&AddressType::FtsoFeed);
+        assert_eq!(
+            events,
+            vec![
+                EventType::PriceThreshold,
+                EventType::PriceMove,
+                EventType::FeedStale
+            ]
+        );
+    }
+
     #[test]
     fn test_eoa_no_events() {
         let events = AddressAnalyzer::events_for_type(&AddressType::Eoa);
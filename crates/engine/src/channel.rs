@@ -0,0 +1,628 @@
+//! Notification channel service — channel CRUD and ownership verification.
+//!
+//! Channels are created unverified. The API issues a one-time verification code,
+//! the user presents it back through the channel itself (e.g., by messaging the
+//! Telegram bot), and only then is the channel marked `verified`. Discord codes go
+//! the other way: they are posted into the Discord channel or DM, and the owner
+//! proves they can read it by submitting the code back to the API. The notifier
+//! refuses to deliver to unverified channels.
+
+use std::net::IpAddr;
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use flare_common::error::AppError;
+use flare_common::net::is_internal_ip;
+use flare_common::types::{ChannelType, NotificationChannel};
+
+/// How long a verification code stays valid.
+const VERIFICATION_TTL_MINUTES: i64 = 15;
+
+/// Minimum length of a user-supplied webhook signing secret.
+const MIN_WEBHOOK_SECRET_LEN: usize = 16;
+
+/// Hosts accepted in Discord webhook URLs.
+const DISCORD_WEBHOOK_HOSTS: [&str; 2] = ["discord.com", "discordapp.com"];
+
+/// Minimum time between verification codes for one channel, so the
+/// confirm-your-address email cannot be used to flood an inbox.
+const VERIFICATION_COOLDOWN_SECS: i64 = 60;
+
+/// Service layer for notification channel operations.
+pub struct ChannelService;
+
+/// Parameters for creating a new notification channel.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct CreateChannelParams {
+    pub channel_type: String,
+    pub config: Option<serde_json::Value>,
+}
+
+/// A freshly issued one-time verification code.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct VerificationCode {
+    pub channel_id: Uuid,
+    pub channel_type: ChannelType,
+    pub code: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ChannelService {
+    /// Create a new (unverified) notification channel for a user.
+    ///
+    /// Unless `allow_private_targets` is set, webhook URLs naming a loopback or
+    /// private IP are refused; hostnames are checked by the notifier on every send.
+    pub async fn create(
+        pool: &PgPool,
+        user_id: Uuid,
+        params: &CreateChannelParams,
+        allow_private_targets: bool,
+    ) -> Result<NotificationChannel, AppError> {
+        let channel_type: ChannelType =
+            params.channel_type.parse().map_err(AppError::Validation)?;
+        let config = Self::validate_config(
+            channel_type,
+            params.config.clone().unwrap_or(serde_json::json!({})),
+            allow_private_targets,
+        )?;
+
+        let channel: NotificationChannel = sqlx::query_as(
+            r#"
+            INSERT INTO notification_channels (id, user_id, channel_type, config, verified)
+            VALUES ($1, $2, $3, $4, false)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(channel_type.to_string())
+        .bind(&config)
+        .fetch_one(pool)
+        .await?;
+
+        tracing::info!(
+            channel_id = %channel.id,
+            user_id = %user_id,
+            channel_type = %channel_type,
+            "Notification channel created"
+        );
+
+        Ok(channel)
+    }
+
+    /// List all notification channels for a user.
+    pub async fn list_by_user(
+        pool: &PgPool,
+        user_id: Uuid,
+    ) -> Result<Vec<NotificationChannel>, AppError> {
+        let channels: Vec<NotificationChannel> = sqlx::query_as(
+            "SELECT * FROM notification_channels WHERE user_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(channels)
+    }
+
+    /// Get a single channel by ID.
+    pub async fn get(pool: &PgPool, channel_id: Uuid) -> Result<NotificationChannel, AppError> {
+        let channel: NotificationChannel =
+            sqlx::query_as("SELECT * FROM notification_channels WHERE id = $1")
+                .bind(channel_id)
+                .fetch_optional(pool)
+                .await?
+                .ok_or_else(|| AppError::NotFound(format!("Channel {} not found", channel_id)))?;
+
+        Ok(channel)
+    }
+
+    /// Issue a new one-time verification code for a channel owned by `user_id`.
+    ///
+    /// Any previously issued codes for the channel are invalidated. Codes can be
+    /// reissued at most once per `VERIFICATION_COOLDOWN_SECS`.
+    pub async fn issue_verification(
+        pool: &PgPool,
+        channel_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<VerificationCode, AppError> {
+        let channel = Self::get(pool, channel_id).await?;
+        if channel.user_id != user_id {
+            return Err(AppError::Auth(
+                "Not authorized to verify this channel".to_string(),
+            ));
+        }
+        if channel.verified {
+            return Err(AppError::Validation(format!(
+                "Channel {} is already verified",
+                channel_id
+            )));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        // Lock the channel so concurrent requests can't both pass the cooldown check
+        sqlx::query("SELECT id FROM notification_channels WHERE id = $1 FOR UPDATE")
+            .bind(channel_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let last_issued: Option<(DateTime<Utc>,)> = sqlx::query_as(
+            "SELECT created_at FROM channel_verifications WHERE channel_id = $1 ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(channel_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some((last_issued,)) = last_issued {
+            let wait = VERIFICATION_COOLDOWN_SECS - (Utc::now() - last_issued).num_seconds();
+            if wait > 0 {
+                return Err(AppError::RateLimited(format!(
+                    "Verification recently issued, retry in {} seconds",
+                    wait
+                )));
+            }
+        }
+
+        // Email codes travel in an unauthenticated link and webhook challenges over
+        // the network, so they must be unguessable; Telegram codes are typed by hand
+        // and only redeemable from a chat.
+        let code = match channel.channel_type {
+            ChannelType::Email | ChannelType::Webhook => {
+                Uuid::new_v4().simple().to_string().to_uppercase()
+            }
+            _ => Self::generate_code(),
+        };
+        let expires_at = Utc::now() + Duration::minutes(VERIFICATION_TTL_MINUTES);
+
+        sqlx::query("DELETE FROM channel_verifications WHERE channel_id = $1")
+            .bind(channel_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "INSERT INTO channel_verifications (code, channel_id, expires_at) VALUES ($1, $2, $3)",
+        )
+        .bind(&code)
+        .bind(channel_id)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        tracing::info!(channel_id = %channel_id, "Channel verification code issued");
+
+        Ok(VerificationCode {
+            channel_id,
+            channel_type: channel.channel_type,
+            code,
+            expires_at,
+        })
+    }
+
+    /// Redeem a verification code presented through a channel of `channel_type`.
+    ///
+    /// On success the code is consumed, `config_patch` is merged into the channel's
+    /// config (e.g., the Telegram `chat_id` the code arrived from) and the channel is
+    /// marked verified. Returns `None` for unknown, expired or mismatched codes.
+    pub async fn confirm_verification(
+        pool: &PgPool,
+        channel_type: ChannelType,
+        code: &str,
+        config_patch: serde_json::Value,
+    ) -> Result<Option<NotificationChannel>, AppError> {
+        Self::redeem(pool, channel_type, None, code, config_patch).await
+    }
+
+    /// Redeem a code that was delivered through a channel owned by `user_id` and
+    /// submitted back by its owner.
+    ///
+    /// Only Discord channels are confirmed this way: their code is posted to the
+    /// Discord destination and never returned by the API. Returns `None` for unknown,
+    /// expired or mismatched codes.
+    pub async fn confirm_delivered_code(
+        pool: &PgPool,
+        channel_id: Uuid,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<Option<NotificationChannel>, AppError> {
+        let channel = Self::get(pool, channel_id).await?;
+        if channel.user_id != user_id {
+            return Err(AppError::Auth(
+                "Not authorized to verify this channel".to_string(),
+            ));
+        }
+        if channel.channel_type != ChannelType::Discord {
+            return Err(AppError::Validation(format!(
+                "{} channels are not verified by submitting a code",
+                channel.channel_type
+            )));
+        }
+
+        Self::redeem(
+            pool,
+            channel.channel_type,
+            Some(channel_id),
+            code,
+            serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Consume a verification code, optionally only one issued for `channel_id`, and
+    /// mark its channel verified.
+    async fn redeem(
+        pool: &PgPool,
+        channel_type: ChannelType,
+        channel_id: Option<Uuid>,
+        code: &str,
+        config_patch: serde_json::Value,
+    ) -> Result<Option<NotificationChannel>, AppError> {
+        let mut tx = pool.begin().await?;
+
+        let channel_id: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            DELETE FROM channel_verifications v
+            USING notification_channels c
+            WHERE v.code = $1
+              AND v.channel_id = c.id
+              AND c.channel_type = $2
+              AND ($3::uuid IS NULL OR v.channel_id = $3)
+              AND v.expires_at > NOW()
+            RETURNING v.channel_id
+            "#,
+        )
+        .bind(code.trim().to_uppercase())
+        .bind(channel_type.to_string())
+        .bind(channel_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some((channel_id,)) = channel_id else {
+            return Ok(None);
+        };
+
+        let channel: NotificationChannel = sqlx::query_as(
+            r#"
+            UPDATE notification_channels
+            SET verified = true, config = config || $1
+            WHERE id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(&config_patch)
+        .bind(channel_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(
+            channel_id = %channel_id,
+            channel_type = %channel_type,
+            "Notification channel verified"
+        );
+
+        Ok(Some(channel))
+    }
+
+    /// Check channel-specific config at creation time.
+    ///
+    /// Email addresses are normalised and must look deliverable. Webhook channels need
+    /// an http(s) URL and get a generated signing secret if none is supplied. Discord
+    /// channels need exactly one of a Discord webhook URL or a user ID to DM. Telegram
+    /// channels receive their chat ID during verification.
+    fn validate_config(
+        channel_type: ChannelType,
+        mut config: serde_json::Value,
+        allow_private_targets: bool,
+    ) -> Result<serde_json::Value, AppError> {
+        if !config.is_object() {
+            return Err(AppError::Validation(
+                "config must be a JSON object".to_string(),
+            ));
+        }
+
+        match channel_type {
+            ChannelType::Email => {
+                let email = config
+                    .get("email")
+                    .and_then(|v| v.as_str())
+                    .map(|e| e.trim().to_lowercase())
+                    .ok_or_else(|| {
+                        AppError::Validation("Email channels require config.email".to_string())
+                    })?;
+                if !Self::is_plausible_email(&email) {
+                    return Err(AppError::Validation(format!(
+                        "Invalid email address '{}'",
+                        email
+                    )));
+                }
+                config["email"] = serde_json::Value::String(email);
+            }
+            ChannelType::Webhook => {
+                let url = config.get("url").and_then(|v| v.as_str()).ok_or_else(|| {
+                    AppError::Validation("Webhook channels require config.url".to_string())
+                })?;
+                let host = url
+                    .strip_prefix("https://")
+                    .or_else(|| url.strip_prefix("http://"))
+                    .map(Self::url_host)
+                    .filter(|host| !host.is_empty());
+                let Some(host) = host else {
+                    return Err(AppError::Validation(format!(
+                        "Webhook url must be an http(s) URL, got '{}'",
+                        url
+                    )));
+                };
+                let literal_ip = host.trim_matches(['[', ']']).parse::<IpAddr>().ok();
+                if !allow_private_targets && literal_ip.is_some_and(is_internal_ip) {
+                    return Err(AppError::Validation(format!(
+                        "Webhook url must not target a private or loopback address, got '{}'",
+                        url
+                    )));
+                }
+
+                match config.get("secret") {
+                    None => {
+                        config["secret"] = serde_json::Value::String(Self::generate_secret());
+                    }
+                    Some(serde_json::Value::String(secret))
+                        if secret.len() >= MIN_WEBHOOK_SECRET_LEN => {}
+                    Some(_) => {
+                        return Err(AppError::Validation(format!(
+                            "Webhook secret must be a string of at least {} characters",
+                            MIN_WEBHOOK_SECRET_LEN
+                        )));
+                    }
+                }
+            }
+            ChannelType::Discord => {
+                let webhook_url = config.get("webhook_url");
+                let user_id = config.get("user_id");
+                match (webhook_url, user_id) {
+                    (Some(url), None) => {
+                        if !url.as_str().is_some_and(Self::is_discord_webhook_url) {
+                            return Err(AppError::Validation(format!(
+                                "Discord webhook_url must be a https://discord.com/api/webhooks/<id>/<token> URL, got {}",
+                                url
+                            )));
+                        }
+                    }
+                    (None, Some(user_id)) => {
+                        let is_snowflake = user_id.as_str().is_some_and(|id| {
+                            !id.is_empty()
+                                && id.len() <= 20
+                                && id.chars().all(|c| c.is_ascii_digit())
+                        });
+                        if !is_snowflake {
+                            return Err(AppError::Validation(format!(
+                                "Discord user_id must be a numeric user ID, got {}",
+                                user_id
+                            )));
+                        }
+                    }
+                    _ => {
+                        return Err(AppError::Validation(
+                            "Discord channels require exactly one of config.webhook_url or config.user_id"
+                                .to_string(),
+                        ));
+                    }
+                }
+            }
+            ChannelType::Telegram => {}
+        }
+
+        Ok(config)
+    }
+
+    /// Generate a random webhook signing secret.
+    fn generate_secret() -> String {
+        format!(
+            "whsec_{}{}",
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        )
+    }
+
+    /// Host part (without port) of a URL with its scheme stripped.
+    fn url_host(rest: &str) -> &str {
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        let host_port = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+        match host_port.strip_prefix('[') {
+            // IPv6 literal: [::1]:8080
+            Some(v6) => v6.split_once(']').map_or(host_port, |(ip, _)| ip),
+            None => host_port.split(':').next().unwrap_or_default(),
+        }
+    }
+
+    /// Whether `url` is a Discord webhook URL: `https://<discord host>/api/webhooks/<id>/<token>`.
+    fn is_discord_webhook_url(url: &str) -> bool {
+        let Some((host, path)) = url
+            .strip_prefix("https://")
+            .and_then(|rest| rest.split_once('/'))
+        else {
+            return false;
+        };
+        let Some(rest) = path.strip_prefix("api/webhooks/").or_else(|| {
+            // Versioned form: api/v10/webhooks/...
+            path.strip_prefix("api/")
+                .and_then(|p| p.split_once('/'))
+                .filter(|(version, _)| version.starts_with('v'))
+                .and_then(|(_, p)| p.strip_prefix("webhooks/"))
+        }) else {
+            return false;
+        };
+        let rest = rest.split(['?', '#']).next().unwrap_or_default();
+        let mut parts = rest.trim_end_matches('/').split('/');
+        let (Some(id), Some(token), None) = (parts.next(), parts.next(), parts.next()) else {
+            return false;
+        };
+
+        DISCORD_WEBHOOK_HOSTS.contains(&host.to_ascii_lowercase().as_str())
+            && !id.is_empty()
+            && id.chars().all(|c| c.is_ascii_digit())
+            && !token.is_empty()
+            && token
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    /// Cheap syntactic check; deliverability is proven by the confirmation email.
+    fn is_plausible_email(email: &str) -> bool {
+        let Some((local, domain)) = email.split_once('@') else {
+            return false;
+        };
+        email.len() <= 254
+            && !local.is_empty()
+            && domain.contains('.')
+            && !domain.starts_with('.')
+            && !domain.ends_with('.')
+            && !domain.contains('@')
+            && !email.chars().any(|c| c.is_whitespace() || c.is_control())
+    }
+
+    /// Generate a short, human-typeable one-time code.
+    fn generate_code() -> String {
+        Uuid::new_v4().simple().to_string()[..8].to_uppercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_code_format() {
+        let code = ChannelService::generate_code();
+        assert_eq!(code.len(), 8);
+        assert!(
+            code.chars()
+                .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+        );
+    }
+
+    #[test]
+    fn test_validate_email_config() {
+        let config = ChannelService::validate_config(
+            ChannelType::Email,
+            serde_json::json!({ "email": "  Alice@Example.com " }),
+            false,
+        )
+        .unwrap();
+        assert_eq!(config["email"], "alice@example.com");
+
+        for bad in [
+            serde_json::json!({}),
+            serde_json::json!({ "email": "not-an-email" }),
+            serde_json::json!({ "email": "a@b@c.com" }),
+            serde_json::json!({ "email": "a b@c.com" }),
+            serde_json::json!({ "email": "a@localhost" }),
+        ] {
+            assert!(ChannelService::validate_config(ChannelType::Email, bad, false).is_err());
+        }
+
+        assert!(
+            ChannelService::validate_config(ChannelType::Telegram, serde_json::json!({}), false)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_validate_webhook_config() {
+        let config = ChannelService::validate_config(
+            ChannelType::Webhook,
+            serde_json::json!({ "url": "https://hooks.example.com/flare" }),
+            false,
+        )
+        .unwrap();
+        assert!(config["secret"].as_str().unwrap().starts_with("whsec_"));
+
+        let config = ChannelService::validate_config(
+            ChannelType::Webhook,
+            serde_json::json!({ "url": "http://10.0.0.5/x", "secret": "0123456789abcdef" }),
+            true,
+        )
+        .unwrap();
+        assert_eq!(config["secret"], "0123456789abcdef");
+
+        for bad in [
+            serde_json::json!({}),
+            serde_json::json!({ "url": "ftp://example.com" }),
+            serde_json::json!({ "url": "https://" }),
+            serde_json::json!({ "url": "https://example.com", "secret": "short" }),
+        ] {
+            assert!(ChannelService::validate_config(ChannelType::Webhook, bad, false).is_err());
+        }
+    }
+
+    #[test]
+    fn test_webhook_private_ip_literals_refused() {
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://10.0.0.5/x",
+            "https://169.254.169.254/latest",
+            "http://user@192.168.1.1/",
+            "http://[::1]:9000/hook",
+            "http://[fd00::1]/hook",
+        ] {
+            let config = serde_json::json!({ "url": url });
+            assert!(
+                ChannelService::validate_config(ChannelType::Webhook, config.clone(), false)
+                    .is_err(),
+                "{} should be refused",
+                url
+            );
+            assert!(ChannelService::validate_config(ChannelType::Webhook, config, true).is_ok());
+        }
+
+        for url in [
+            "https://8.8.8.8/hook",
+            "https://[2606:4700::1111]/hook",
+            "https://localhost.example.com/",
+        ] {
+            let config = serde_json::json!({ "url": url });
+            assert!(ChannelService::validate_config(ChannelType::Webhook, config, false).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_validate_discord_config() {
+        for good in [
+            serde_json::json!({ "webhook_url": "https://discord.com/api/webhooks/123/abc-DEF_9" }),
+            serde_json::json!({ "webhook_url": "https://discordapp.com/api/v10/webhooks/123/tok?thread_id=1" }),
+            serde_json::json!({ "user_id": "80351110224678912" }),
+        ] {
+            assert!(ChannelService::validate_config(ChannelType::Discord, good, false).is_ok());
+        }
+
+        for bad in [
+            serde_json::json!({}),
+            serde_json::json!({
+                "webhook_url": "https://discord.com/api/webhooks/123/abc",
+                "user_id": "42",
+            }),
+            serde_json::json!({ "webhook_url": "https://evil.example/api/webhooks/123/abc" }),
+            serde_json::json!({ "webhook_url": "https://discord.com.evil.example/api/webhooks/123/abc" }),
+            serde_json::json!({ "webhook_url": "http://discord.com/api/webhooks/123/abc" }),
+            serde_json::json!({ "webhook_url": "https://discord.com/api/webhooks/123" }),
+            serde_json::json!({ "webhook_url": "https://discord.com/api/webhooks/123/tok/../x" }),
+            serde_json::json!({ "user_id": "@alice" }),
+            serde_json::json!({ "user_id": 42 }),
+        ] {
+            assert!(
+                ChannelService::validate_config(ChannelType::Discord, bad.clone(), false).is_err(),
+                "{} should be rejected",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_generate_code_unique() {
+        assert_ne!(
+            ChannelService::generate_code(),
+            ChannelService::generate_code()
+        );
+    }
+}
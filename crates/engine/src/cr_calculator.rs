@@ -1,17 +1,852 @@
-// Milestone 4 — FAsset Collateral Ratio calculator
-//
-// Calculates real-time CR per agent vault:
-// CR = vault_collateral_value / minted_asset_value
-//
-// Uses FTSO price feed for collateral valuation.
-// Emits cr_warning / cr_critical / liquidation_imminent alerts.
+//! FAsset collateral ratio (CR) calculator.
+//!
+//! Tracks every agent vault seen in FAsset events. A vault's collateral and backed
+//! amounts are read from its AssetManager's `getAgentInfo` (see `agent_info`): seeded
+//! at the block of the first event that needs it, then read again at the block of
+//! each of the agent's later FAsset events, and on price ticks once the last read is
+//! a refresh interval of blocks old. Events only trigger reads; their amounts are
+//! never applied to the vault, so pool collateral moved through the agent's
+//! CollateralPool and anything else without an AssetManager event is picked up too.
+//!
+//! Collateral is valued with the latest `ftso_price_ticks` of its feed and compared
+//! with the value of the lots it backs (minted + reserved + redeeming):
+//!
+//! ```text
+//! CR = collateral value / (backed lots × lot size × asset price)
+//! ```
+//!
+//! Vault and pool CR are computed separately and the lower one counts. Both are
+//! recomputed on every FAsset event of an agent and every price tick on its chain,
+//! and a health snapshot is written to `agent_vault_snapshots` at most once per
+//! snapshot interval of block time.
+//!
+//! When the CR of an agent with an active `cr_warning`, `cr_critical` or
+//! `liquidation_imminent` subscription falls below its threshold, a synthetic `cr_warning`,
+//! `cr_critical` or `liquidation_imminent` event is written to `indexed_events` and
+//! goes through the alert pipeline like any other event. It keeps the triggering
+//! transaction hash and block, with a negative log index derived from the trigger,
+//! the agent and the event type so a replayed trigger doesn't write it twice.
+//!
+//! Alerts fire on the downward crossing only. Each synthetic event carries the
+//! previous CR, so a subscription matches only if its own threshold was crossed
+//! (see `crosses_subscription_threshold`). State is in memory: after a restart or
+//! a reorg, vaults are seeded again and their previous CR is the last snapshot's.
+//! Agents are neither snapshotted nor alerted on until seeded, and an agent with
+//! no previous CR only records a baseline.
 
-/// Placeholder for the CR calculator (Milestone 4).
-pub struct CrCalculator;
+use std::collections::{HashMap, HashSet};
+
+use std::time::Duration;
+
+use alloy::primitives::Address;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use flare_common::types::{
+    AgentVaultSnapshot, Chain, DecodedEvent, EventType, Subscription, ThresholdConfig,
+};
+use flare_decoders::ftso::{feed_id, feed_id_hex};
+
+use crate::agent_info::{AgentInfoReader, AgentState};
+use crate::synthetic::synthetic_log_index;
+
+/// `cr_warning` threshold when the subscription doesn't set one.
+pub const DEFAULT_CR_WARNING: f64 = 1.8;
+
+/// `cr_critical` threshold when the subscription doesn't set one.
+pub const DEFAULT_CR_CRITICAL: f64 = 1.5;
+
+/// CR below which `liquidation_imminent` fires, just above the minimum
+/// collateral ratio at which agents go into liquidation.
+pub const LIQUIDATION_IMMINENT_CR: f64 = 1.3;
+
+/// Minimum block time between two snapshots of an agent (default: 5 min).
+pub const DEFAULT_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Blocks between two reads of an agent vault on price ticks.
+pub const DEFAULT_REFRESH_BLOCKS: u64 = 200;
+
+/// Blocks to wait before retrying to read an agent vault that couldn't be read.
+const READ_RETRY_BLOCKS: u64 = 100;
+
+/// Synthetic event types raised by the calculator.
+pub const CR_EVENT_TYPES: [EventType; 3] = [
+    EventType::CrWarning,
+    EventType::CrCritical,
+    EventType::LiquidationImminent,
+];
+
+/// AssetManager events of an agent, after which its vault is read again.
+const AGENT_EVENT_TYPES: [EventType; 7] = [
+    EventType::CollateralDeposited,
+    EventType::CollateralWithdrawn,
+    EventType::CollateralReserved,
+    EventType::MintingExecuted,
+    EventType::RedemptionRequested,
+    EventType::RedemptionPerformed,
+    EventType::LiquidationStarted,
+];
+
+/// FTSO feed category of crypto feeds.
+pub(crate) const CRYPTO_FEED_CATEGORY: u8 = 1;
+
+/// Decimals of the native token held as pool collateral.
+const NATIVE_DECIMALS: i32 = 18;
+
+/// How an FAsset's lots and its agents' vault collateral are valued.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetSettings {
+    /// FTSO feed of the underlying asset, e.g. `XRP/USD`
+    pub asset_feed: String,
+    /// Decimals of the underlying asset's base unit (UBA)
+    pub asset_decimals: i32,
+    /// Underlying asset units per lot
+    pub lot_size: f64,
+    /// FTSO feed of the vault collateral token
+    pub vault_feed: String,
+    /// Decimals of the vault collateral token
+    pub vault_decimals: i32,
+}
+
+impl AssetSettings {
+    /// Settings for an asset backed by USDT vault collateral.
+    pub fn usdt_backed(asset_feed: &str, asset_decimals: i32, lot_size: f64) -> Self {
+        Self {
+            asset_feed: asset_feed.to_string(),
+            asset_decimals,
+            lot_size,
+            vault_feed: "USDT/USD".to_string(),
+            vault_decimals: 6,
+        }
+    }
+}
+
+/// Settings for the known FAssets. Lot sizes follow the AssetManager settings at
+/// launch; governance can change them, so deployments override with `with_asset`.
+fn default_assets() -> HashMap<String, AssetSettings> {
+    HashMap::from([
+        (
+            "FXRP".to_string(),
+            AssetSettings::usdt_backed("XRP/USD", 6, 10.0),
+        ),
+        (
+            "FBTC".to_string(),
+            AssetSettings::usdt_backed("BTC/USD", 8, 0.0001),
+        ),
+        (
+            "FDOGE".to_string(),
+            AssetSettings::usdt_backed("DOGE/USD", 8, 100.0),
+        ),
+    ])
+}
+
+/// FTSO feed of the native token held as pool collateral on `chain`.
+pub fn native_feed(chain: Chain) -> &'static str {
+    match chain {
+        Chain::Flare | Chain::Coston2 => "FLR/USD",
+        Chain::Songbird | Chain::Coston => "SGB/USD",
+    }
+}
+
+/// Collateral and backed lots of one agent vault.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AgentVault {
+    /// FAsset symbol from the indexer's `fasset` tag
+    pub fasset: Option<String>,
+    /// Vault collateral in token base units
+    pub vault_collateral: f64,
+    /// Pool collateral in native token base units (wei)
+    pub pool_collateral: f64,
+    pub minted_lots: u64,
+    pub reserved_lots: u64,
+    pub redeeming_lots: u64,
+}
+
+impl AgentVault {
+    /// Vault of an FAsset agent as its AssetManager reports it. Backed amounts are
+    /// rounded down to whole lots.
+    pub fn from_state(state: &AgentState, fasset: &str, settings: &AssetSettings) -> Self {
+        let lot = settings.lot_size * 10f64.powi(settings.asset_decimals);
+        let lots = |uba: f64| (uba / lot).floor() as u64;
+        Self {
+            fasset: Some(fasset.to_string()),
+            vault_collateral: state.vault_collateral,
+            pool_collateral: state.pool_collateral,
+            minted_lots: lots(state.minted_uba),
+            reserved_lots: lots(state.reserved_uba),
+            redeeming_lots: lots(state.redeeming_uba),
+        }
+    }
+
+    /// Lots the collateral has to cover: minted, reserved for minting and being redeemed.
+    pub fn backed_lots(&self) -> u64 {
+        self.minted_lots + self.reserved_lots + self.redeeming_lots
+    }
+}
+
+/// Vault and pool collateral ratios of an agent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollateralRatios {
+    pub vault: f64,
+    /// `None` while the agent has no pool collateral or its price is unknown
+    pub pool: Option<f64>,
+}
+
+impl CollateralRatios {
+    /// The ratio that counts for alerts: the lower of vault and pool CR.
+    pub fn cr(&self) -> f64 {
+        self.pool.map_or(self.vault, |pool| pool.min(self.vault))
+    }
+}
+
+/// USD value of an agent's collateral and of the lots it backs.
+///
+/// Each value is `None` while the price it needs is unknown.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VaultValuation {
+    pub vault_collateral_usd: Option<f64>,
+    pub pool_collateral_usd: Option<f64>,
+    /// Value of the minted, reserved and redeeming lots
+    pub minted_value_usd: Option<f64>,
+}
+
+impl VaultValuation {
+    /// Collateral ratios of the valued vault.
+    ///
+    /// `None` if it backs no lots or the asset or vault collateral price is unknown.
+    pub fn ratios(&self) -> Option<CollateralRatios> {
+        let backed = self.minted_value_usd.filter(|value| *value > 0.0)?;
+        Some(CollateralRatios {
+            vault: self.vault_collateral_usd? / backed,
+            pool: self
+                .pool_collateral_usd
+                .filter(|value| *value > 0.0)
+                .map(|value| value / backed),
+        })
+    }
+}
+
+/// Value `vault`, with `price` returning the latest price of a feed.
+pub fn valuation(
+    vault: &AgentVault,
+    settings: &AssetSettings,
+    native_feed: &str,
+    price: impl Fn(&str) -> Option<f64>,
+) -> VaultValuation {
+    VaultValuation {
+        vault_collateral_usd: price(&settings.vault_feed)
+            .map(|p| vault.vault_collateral * 10f64.powi(-settings.vault_decimals) * p),
+        pool_collateral_usd: price(native_feed)
+            .map(|p| vault.pool_collateral * 10f64.powi(-NATIVE_DECIMALS) * p),
+        minted_value_usd: price(&settings.asset_feed)
+            .map(|p| vault.backed_lots() as f64 * settings.lot_size * p),
+    }
+}
+
+/// Collateral ratios of `vault`, with `price` returning the latest price of a feed.
+///
+/// `None` if the vault backs no lots or the asset or vault collateral price is unknown.
+pub fn collateral_ratios(
+    vault: &AgentVault,
+    settings: &AssetSettings,
+    native_feed: &str,
+    price: impl Fn(&str) -> Option<f64>,
+) -> Option<CollateralRatios> {
+    valuation(vault, settings, native_feed, price).ratios()
+}
+
+/// Whether an agent last snapshotted at `last` is due another snapshot at `now`.
+pub fn snapshot_due(last: Option<DateTime<Utc>>, now: DateTime<Utc>, interval: Duration) -> bool {
+    last.is_none_or(|last| {
+        (now - last)
+            .to_std()
+            .is_ok_and(|elapsed| elapsed >= interval)
+    })
+}
+
+/// Whether a vault last read at the end of block `read_at` is due another read at
+/// `block`: always after a new block's agent event, otherwise once `interval`
+/// blocks old.
+pub fn read_due(read_at: Option<u64>, block: u64, agent_event: bool, interval: u64) -> bool {
+    read_at.is_none_or(|read_at| {
+        if agent_event {
+            block > read_at
+        } else {
+            block >= read_at.saturating_add(interval)
+        }
+    })
+}
+
+/// Whether moving from `previous` to `cr` crosses below `threshold`.
+///
+/// With no previous CR (first evaluation), any CR below the threshold counts.
+pub fn crossed(previous: Option<f64>, cr: f64, threshold: f64) -> bool {
+    cr < threshold && previous.is_none_or(|previous| previous >= threshold)
+}
+
+/// The CR below which a subscription to a synthetic CR event type alerts.
+pub fn subscription_threshold(event_type: &EventType, config: &ThresholdConfig) -> Option<f64> {
+    match event_type {
+        EventType::CrWarning => Some(config.cr_warning.unwrap_or(DEFAULT_CR_WARNING)),
+        EventType::CrCritical => Some(config.cr_critical.unwrap_or(DEFAULT_CR_CRITICAL)),
+        EventType::LiquidationImminent => Some(LIQUIDATION_IMMINENT_CR),
+        _ => None,
+    }
+}
+
+/// Whether a synthetic CR event crossed the subscription's own threshold.
+///
+/// The calculator raises one event per type once any subscribed threshold is
+/// crossed; subscriptions whose own threshold wasn't crossed must not match it.
+/// Other event types always pass.
+pub fn crosses_subscription_threshold(subscription: &Subscription, event: &DecodedEvent) -> bool {
+    let config: ThresholdConfig =
+        serde_json::from_value(subscription.threshold_config.clone()).unwrap_or_default();
+    let Some(threshold) = subscription_threshold(&event.event_type, &config) else {
+        return true;
+    };
+    let Some(cr) = event.decoded_data.get("cr").and_then(|v| v.as_f64()) else {
+        return false;
+    };
+    let previous = event
+        .decoded_data
+        .get("previous_cr")
+        .and_then(|v| v.as_f64());
+    crossed(previous, cr, threshold)
+}
+
+/// Chain and lowercase address of an agent.
+type AgentKey = (Chain, String);
+
+/// An agent vault and its CR subscriptions.
+#[derive(Debug, Clone)]
+struct TrackedAgent {
+    /// Address synthetic events are emitted for: the monitored address as stored
+    /// if the agent is subscribed to, its lowercase address otherwise
+    address: String,
+    /// AssetManager that emitted the agent's events
+    asset_manager: Option<Address>,
+    vault: AgentVault,
+    /// Block whose end state the vault was last read at; `None` until seeded
+    read_at: Option<u64>,
+    /// Earliest block to retry reading at after a failed attempt
+    retry_at: u64,
+    /// Subscribed event types and their thresholds
+    thresholds: Vec<(EventType, f64)>,
+    /// CR at the last evaluation
+    last_cr: Option<f64>,
+    /// Block time of the last snapshot
+    snapshot_at: Option<DateTime<Utc>>,
+}
+
+impl TrackedAgent {
+    fn new(address: String) -> Self {
+        Self {
+            address,
+            asset_manager: None,
+            vault: AgentVault::default(),
+            read_at: None,
+            retry_at: 0,
+            thresholds: Vec::new(),
+            last_cr: None,
+            snapshot_at: None,
+        }
+    }
+}
+
+/// Synthetic events and snapshots produced by one observed event.
+#[derive(Debug, Clone, Default)]
+pub struct CrUpdate {
+    pub events: Vec<DecodedEvent>,
+    pub snapshots: Vec<AgentVaultSnapshot>,
+}
+
+/// Per-agent collateral ratio tracker.
+pub struct CrCalculator {
+    assets: HashMap<String, AssetSettings>,
+    /// Agents by chain and lowercase address
+    agents: HashMap<AgentKey, TrackedAgent>,
+    /// Latest price by chain and feed name
+    prices: HashMap<(Chain, String), f64>,
+    /// Whether `agents` has been loaded from stored events
+    loaded: bool,
+    snapshot_interval: Duration,
+    refresh_blocks: u64,
+    agent_info: AgentInfoReader,
+}
 
 impl CrCalculator {
     pub fn new() -> Self {
-        Self
+        Self {
+            assets: default_assets(),
+            agents: HashMap::new(),
+            prices: HashMap::new(),
+            loaded: false,
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            refresh_blocks: DEFAULT_REFRESH_BLOCKS,
+            agent_info: AgentInfoReader::new(),
+        }
+    }
+
+    /// Read vaults from chain state through `agent_info`. Without an RPC endpoint
+    /// for a chain, its agents are never seeded and never alerted on.
+    pub fn with_agent_info(mut self, agent_info: AgentInfoReader) -> Self {
+        self.agent_info = agent_info;
+        self
+    }
+
+    /// Override the minimum block time between two snapshots of an agent.
+    pub fn with_snapshot_interval(mut self, interval: Duration) -> Self {
+        self.snapshot_interval = interval;
+        self
+    }
+
+    /// Override the number of blocks between two reads of an agent vault on price ticks.
+    pub fn with_refresh_interval(mut self, blocks: u64) -> Self {
+        self.refresh_blocks = blocks;
+        self
+    }
+
+    /// Add or replace the settings of an FAsset, by symbol (e.g. `FXRP`).
+    pub fn with_asset(mut self, symbol: impl Into<String>, settings: AssetSettings) -> Self {
+        self.assets.insert(symbol.into().to_uppercase(), settings);
+        self
+    }
+
+    /// Number of agents currently tracked.
+    pub fn tracked_agents(&self) -> usize {
+        self.agents.len()
+    }
+
+    /// Forget all vault state and prices, e.g. after reorged events were retracted.
+    /// Vaults are seeded again from the canonical chain.
+    pub fn reset(&mut self) {
+        self.agents.clear();
+        self.prices.clear();
+        self.loaded = false;
+    }
+
+    /// Sync the agents' thresholds with active CR subscriptions.
+    ///
+    /// On the first call (and after `reset`), the agents seen in events up to
+    /// `up_to_event_id` (the worker cursor) are loaded first; their vaults are
+    /// seeded when `observe` next needs them.
+    pub async fn refresh_agents(
+        &mut self,
+        pool: &PgPool,
+        up_to_event_id: i64,
+    ) -> anyhow::Result<()> {
+        if !self.loaded {
+            self.load_agents(pool, up_to_event_id).await?;
+            self.loaded = true;
+        }
+
+        let event_types: Vec<String> = CR_EVENT_TYPES.iter().map(|t| t.to_string()).collect();
+        let rows: Vec<(Chain, String, EventType, serde_json::Value)> = sqlx::query_as(
+            r#"
+            SELECT ma.chain, ma.address, s.event_type, s.threshold_config
+            FROM subscriptions s
+            JOIN monitored_addresses ma ON ma.id = s.address_id
+            WHERE s.active AND s.event_type = ANY($1)
+            ORDER BY ma.address
+            "#,
+        )
+        .bind(&event_types)
+        .fetch_all(pool)
+        .await?;
+
+        let mut subscribed: HashMap<AgentKey, (String, Vec<(EventType, f64)>)> = HashMap::new();
+        for (chain, address, event_type, threshold_config) in rows {
+            let config: ThresholdConfig =
+                serde_json::from_value(threshold_config).unwrap_or_default();
+            let Some(threshold) = subscription_threshold(&event_type, &config) else {
+                continue;
+            };
+            subscribed
+                .entry((chain, address.to_lowercase()))
+                .or_insert_with(|| (address, Vec::new()))
+                .1
+                .push((event_type, threshold));
+        }
+
+        for agent in self.agents.values_mut() {
+            agent.thresholds.clear();
+        }
+        for (key, (address, thresholds)) in subscribed {
+            let agent = self
+                .agents
+                .entry(key)
+                .or_insert_with(|| TrackedAgent::new(address.clone()));
+            agent.address = address;
+            agent.thresholds = thresholds;
+        }
+
+        Ok(())
+    }
+
+    /// Load every agent with non-reorged FAsset events up to `up_to_event_id`,
+    /// with the AssetManager and FAsset of its latest one.
+    async fn load_agents(&mut self, pool: &PgPool, up_to_event_id: i64) -> anyhow::Result<()> {
+        let event_types: Vec<String> = AGENT_EVENT_TYPES.iter().map(|t| t.to_string()).collect();
+        let rows: Vec<(Chain, String, String, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT ON (chain, LOWER(decoded_data->>'agent'))
+                   chain, LOWER(decoded_data->>'agent'), address, decoded_data->>'fasset'
+            FROM indexed_events
+            WHERE event_type = ANY($1) AND decoded_data ? 'agent'
+              AND NOT is_reorged AND id <= $2
+            ORDER BY chain, LOWER(decoded_data->>'agent'), id DESC
+            "#,
+        )
+        .bind(&event_types)
+        .bind(up_to_event_id)
+        .fetch_all(pool)
+        .await?;
+
+        for (chain, agent, asset_manager, fasset) in rows {
+            let tracked = self
+                .agents
+                .entry((chain, agent.clone()))
+                .or_insert_with(|| TrackedAgent::new(agent));
+            tracked.asset_manager = asset_manager.parse().ok();
+            tracked.vault.fasset = fasset;
+        }
+
+        tracing::info!(
+            agents = self.agents.len(),
+            up_to_event_id,
+            "Loaded agents from FAsset events"
+        );
+        Ok(())
+    }
+
+    /// Read an agent's vault from its AssetManager at the end of `block` if due (see
+    /// `read_due`). When seeding, the CR of its last snapshot up to that block
+    /// becomes the previous CR.
+    ///
+    /// A failed read is logged and retried after `READ_RETRY_BLOCKS`; until then an
+    /// unseeded agent stays unseeded and a seeded one keeps its last read vault.
+    async fn read_vault(
+        &mut self,
+        pool: &PgPool,
+        key: &AgentKey,
+        block: u64,
+        agent_event: bool,
+    ) -> anyhow::Result<()> {
+        let Some(agent) = self.agents.get(key) else {
+            return Ok(());
+        };
+        if block < agent.retry_at
+            || !read_due(agent.read_at, block, agent_event, self.refresh_blocks)
+        {
+            return Ok(());
+        }
+        let (Some(asset_manager), Some(fasset)) = (agent.asset_manager, agent.vault.fasset.clone())
+        else {
+            return Ok(());
+        };
+        let Some(settings) = self.assets.get(&fasset) else {
+            return Ok(());
+        };
+        let Ok(address) = key.1.parse::<Address>() else {
+            return Ok(());
+        };
+        let seeding = agent.read_at.is_none();
+
+        let state = match self
+            .agent_info
+            .agent_state(key.0, asset_manager, address, block)
+            .await
+        {
+            Ok(state) => state,
+            Err(e) => {
+                if seeding {
+                    tracing::warn!(
+                        chain = %key.0,
+                        agent = %key.1,
+                        block,
+                        error = %e,
+                        "Failed to read agent vault, its CR alerts are held back"
+                    );
+                } else {
+                    tracing::warn!(
+                        chain = %key.0,
+                        agent = %key.1,
+                        block,
+                        error = %e,
+                        "Failed to read agent vault, keeping its last read state"
+                    );
+                }
+                if let Some(agent) = self.agents.get_mut(key) {
+                    agent.retry_at = block.saturating_add(READ_RETRY_BLOCKS);
+                }
+                return Ok(());
+            }
+        };
+        let vault = AgentVault::from_state(&state, &fasset, settings);
+
+        let last_cr: Option<(Option<f64>, DateTime<Utc>)> = if seeding {
+            sqlx::query_as(
+                r#"
+                SELECT cr, recorded_at FROM agent_vault_snapshots
+                WHERE chain = $1 AND agent = $2 AND block_number <= $3
+                ORDER BY recorded_at DESC
+                LIMIT 1
+                "#,
+            )
+            .bind(key.0.to_string())
+            .bind(&key.1)
+            .bind(block as i64)
+            .fetch_optional(pool)
+            .await?
+        } else {
+            None
+        };
+
+        if let Some(agent) = self.agents.get_mut(key) {
+            agent.vault = vault;
+            agent.read_at = Some(block);
+            if seeding {
+                agent.last_cr = last_cr.and_then(|(cr, _)| cr);
+                agent.snapshot_at = last_cr.map(|(_, recorded_at)| recorded_at);
+                tracing::info!(
+                    chain = %key.0,
+                    agent = %key.1,
+                    block,
+                    minted_lots = agent.vault.minted_lots,
+                    previous_cr = ?agent.last_cr,
+                    "Seeded agent vault from its AssetManager"
+                );
+            } else {
+                tracing::debug!(
+                    chain = %key.0,
+                    agent = %key.1,
+                    block,
+                    minted_lots = agent.vault.minted_lots,
+                    "Read agent vault from its AssetManager"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Load the latest price of every feed the agents on `chain` are valued with.
+    async fn load_prices(&mut self, pool: &PgPool, chain: Chain) -> anyhow::Result<()> {
+        let mut feeds: HashSet<String> = HashSet::from([native_feed(chain).to_string()]);
+        for ((agent_chain, _), agent) in &self.agents {
+            if *agent_chain != chain {
+                continue;
+            }
+            if let Some(settings) = agent
+                .vault
+                .fasset
+                .as_ref()
+                .and_then(|symbol| self.assets.get(symbol))
+            {
+                feeds.insert(settings.asset_feed.clone());
+                feeds.insert(settings.vault_feed.clone());
+            }
+        }
+
+        let ids: HashMap<String, String> = feeds
+            .into_iter()
+            .filter_map(|name| {
+                feed_id(CRYPTO_FEED_CATEGORY, &name).map(|id| (feed_id_hex(&id), name))
+            })
+            .collect();
+        let id_list: Vec<&String> = ids.keys().collect();
+
+        let rows: Vec<(String, f64)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT ON (feed_id) feed_id, price::float8
+            FROM ftso_price_ticks
+            WHERE chain = $1 AND feed_id = ANY($2)
+            ORDER BY feed_id, block_timestamp DESC, id DESC
+            "#,
+        )
+        .bind(chain.to_string())
+        .bind(&id_list)
+        .fetch_all(pool)
+        .await?;
+
+        for (id, price) in rows {
+            if let Some(name) = ids.get(&id) {
+                self.prices.insert((chain, name.clone()), price);
+            }
+        }
+        Ok(())
+    }
+
+    /// Update state from a processed event and recompute the affected agents.
+    ///
+    /// Returns the synthetic CR events and snapshots to persist.
+    pub async fn observe(
+        &mut self,
+        pool: &PgPool,
+        event: &DecodedEvent,
+    ) -> anyhow::Result<CrUpdate> {
+        let chain = event.chain;
+        let agent_event = AGENT_EVENT_TYPES.contains(&event.event_type);
+        let keys: Vec<AgentKey> = match event.event_type {
+            EventType::FtsoFeedPublished | EventType::FastUpdateFeeds => {
+                let keys: Vec<AgentKey> = self
+                    .agents
+                    .keys()
+                    .filter(|(c, _)| *c == chain)
+                    .cloned()
+                    .collect();
+                if !keys.is_empty() {
+                    self.load_prices(pool, chain).await?;
+                }
+                keys
+            }
+            _ if agent_event => {
+                let Some(agent) = event
+                    .decoded_data
+                    .get("agent")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_lowercase)
+                else {
+                    return Ok(CrUpdate::default());
+                };
+                let key = (chain, agent);
+                let tracked = self
+                    .agents
+                    .entry(key.clone())
+                    .or_insert_with(|| TrackedAgent::new(key.1.clone()));
+                let had_fasset = tracked.vault.fasset.is_some();
+                if let Ok(asset_manager) = event.address.parse() {
+                    tracked.asset_manager = Some(asset_manager);
+                }
+                if let Some(symbol) = event.decoded_data.get("fasset").and_then(|v| v.as_str()) {
+                    tracked.vault.fasset = Some(symbol.to_string());
+                }
+                // First tagged event of this agent: its feeds haven't been loaded yet
+                if !had_fasset || !self.prices.keys().any(|(c, _)| *c == chain) {
+                    self.load_prices(pool, chain).await?;
+                }
+                vec![key]
+            }
+            _ => return Ok(CrUpdate::default()),
+        };
+
+        let mut update = CrUpdate::default();
+        for key in keys {
+            self.read_vault(pool, &key, event.block_number, agent_event)
+                .await?;
+            self.evaluate(&key, event, &mut update);
+        }
+        Ok(update)
+    }
+
+    /// Recompute a seeded agent's CR: snapshot it if due, and build the synthetic
+    /// events for crossed thresholds.
+    fn evaluate(&mut self, key: &AgentKey, trigger: &DecodedEvent, update: &mut CrUpdate) {
+        let Some(agent) = self
+            .agents
+            .get_mut(key)
+            .filter(|agent| agent.read_at.is_some())
+        else {
+            return;
+        };
+        let Some(settings) = agent
+            .vault
+            .fasset
+            .as_ref()
+            .and_then(|symbol| self.assets.get(symbol))
+        else {
+            return;
+        };
+        let chain = key.0;
+        let prices = &self.prices;
+        let valuation = valuation(&agent.vault, settings, native_feed(chain), |feed| {
+            prices.get(&(chain, feed.to_string())).copied()
+        });
+        let ratios = valuation.ratios();
+
+        if snapshot_due(
+            agent.snapshot_at,
+            trigger.block_timestamp,
+            self.snapshot_interval,
+        ) {
+            agent.snapshot_at = Some(trigger.block_timestamp);
+            let vault = &agent.vault;
+            update.snapshots.push(AgentVaultSnapshot {
+                chain,
+                agent: key.1.clone(),
+                fasset: vault.fasset.clone(),
+                cr: ratios.map(|r| r.cr()),
+                vault_cr: ratios.map(|r| r.vault),
+                pool_cr: ratios.and_then(|r| r.pool),
+                vault_collateral: vault.vault_collateral * 10f64.powi(-settings.vault_decimals),
+                pool_collateral: vault.pool_collateral * 10f64.powi(-NATIVE_DECIMALS),
+                vault_collateral_usd: valuation.vault_collateral_usd,
+                pool_collateral_usd: valuation.pool_collateral_usd,
+                minted_value_usd: valuation.minted_value_usd,
+                minted_lots: vault.minted_lots as i64,
+                reserved_lots: vault.reserved_lots as i64,
+                redeeming_lots: vault.redeeming_lots as i64,
+                block_number: trigger.block_number as i64,
+                recorded_at: trigger.block_timestamp,
+            });
+        }
+
+        let Some(ratios) = ratios else {
+            return;
+        };
+        let cr = ratios.cr();
+        // Without a previous CR (a new agent), this one is only the baseline
+        let Some(previous) = agent.last_cr.replace(cr) else {
+            return;
+        };
+
+        let mut emitted = 0;
+        for event_type in CR_EVENT_TYPES {
+            let crossed_any = agent
+                .thresholds
+                .iter()
+                .any(|(t, threshold)| *t == event_type && crossed(Some(previous), cr, *threshold));
+            if !crossed_any {
+                continue;
+            }
+
+            let vault = &agent.vault;
+            emitted += 1;
+            update.events.push(DecodedEvent {
+                tx_hash: trigger.tx_hash.clone(),
+                log_index: Some(synthetic_log_index(trigger, &key.1, &event_type)),
+                block_number: trigger.block_number,
+                block_timestamp: trigger.block_timestamp,
+                chain,
+                address: agent.address.clone(),
+                event_type,
+                decoded_data: serde_json::json!({
+                    "agent": key.1,
+                    "fasset": vault.fasset,
+                    "cr": cr,
+                    "previous_cr": previous,
+                    "vault_cr": ratios.vault,
+                    "pool_cr": ratios.pool,
+                    "vault_collateral": vault.vault_collateral.to_string(),
+                    "pool_collateral": vault.pool_collateral.to_string(),
+                    "minted_lots": vault.minted_lots,
+                    "reserved_lots": vault.reserved_lots,
+                    "redeeming_lots": vault.redeeming_lots,
+                    "trigger": trigger.event_type.to_string(),
+                }),
+            });
+        }
+
+        if emitted > 0 {
+            tracing::info!(
+                chain = %chain,
+                agent = %key.1,
+                cr,
+                previous_cr = previous,
+                events = emitted,
+                "Agent collateral ratio crossed a threshold"
+            );
+        }
     }
 }
 
@@ -20,3 +855,273 @@ impl Default for CrCalculator {
         Self::new()
     }
 }
+
+/// Write vault health snapshots. Returns the number written; a snapshot of the
+/// same agent at the same block time (a replayed trigger) is skipped.
+pub async fn insert_snapshots(
+    pool: &PgPool,
+    snapshots: &[AgentVaultSnapshot],
+) -> anyhow::Result<u64> {
+    if snapshots.is_empty() {
+        return Ok(0);
+    }
+
+    let chains: Vec<String> = snapshots.iter().map(|s| s.chain.to_string()).collect();
+    let agents: Vec<&str> = snapshots.iter().map(|s| s.agent.as_str()).collect();
+    let fassets: Vec<Option<&str>> = snapshots.iter().map(|s| s.fasset.as_deref()).collect();
+    let crs: Vec<Option<f64>> = snapshots.iter().map(|s| s.cr).collect();
+    let vault_crs: Vec<Option<f64>> = snapshots.iter().map(|s| s.vault_cr).collect();
+    let pool_crs: Vec<Option<f64>> = snapshots.iter().map(|s| s.pool_cr).collect();
+    let vault_collateral: Vec<f64> = snapshots.iter().map(|s| s.vault_collateral).collect();
+    let pool_collateral: Vec<f64> = snapshots.iter().map(|s| s.pool_collateral).collect();
+    let vault_usd: Vec<Option<f64>> = snapshots.iter().map(|s| s.vault_collateral_usd).collect();
+    let pool_usd: Vec<Option<f64>> = snapshots.iter().map(|s| s.pool_collateral_usd).collect();
+    let minted_usd: Vec<Option<f64>> = snapshots.iter().map(|s| s.minted_value_usd).collect();
+    let minted_lots: Vec<i64> = snapshots.iter().map(|s| s.minted_lots).collect();
+    let reserved_lots: Vec<i64> = snapshots.iter().map(|s| s.reserved_lots).collect();
+    let redeeming_lots: Vec<i64> = snapshots.iter().map(|s| s.redeeming_lots).collect();
+    let block_numbers: Vec<i64> = snapshots.iter().map(|s| s.block_number).collect();
+    let recorded_at: Vec<DateTime<Utc>> = snapshots.iter().map(|s| s.recorded_at).collect();
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO agent_vault_snapshots (
+            chain, agent, fasset, cr, vault_cr, pool_cr, vault_collateral, pool_collateral,
+            vault_collateral_usd, pool_collateral_usd, minted_value_usd,
+            minted_lots, reserved_lots, redeeming_lots, block_number, recorded_at
+        )
+        SELECT * FROM UNNEST(
+            $1::text[], $2::text[], $3::text[], $4::float8[], $5::float8[], $6::float8[],
+            $7::float8[], $8::float8[], $9::float8[], $10::float8[], $11::float8[],
+            $12::int8[], $13::int8[], $14::int8[], $15::int8[], $16::timestamptz[]
+        )
+        ON CONFLICT (chain, agent, recorded_at) DO NOTHING
+        "#,
+    )
+    .bind(chains)
+    .bind(agents)
+    .bind(fassets)
+    .bind(crs)
+    .bind(vault_crs)
+    .bind(pool_crs)
+    .bind(vault_collateral)
+    .bind(pool_collateral)
+    .bind(vault_usd)
+    .bind(pool_usd)
+    .bind(minted_usd)
+    .bind(minted_lots)
+    .bind(reserved_lots)
+    .bind(redeeming_lots)
+    .bind(block_numbers)
+    .bind(recorded_at)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn make_event(event_type: EventType, data: serde_json::Value) -> DecodedEvent {
+        DecodedEvent {
+            tx_hash: "0xabc".to_string(),
+            log_index: Some(3),
+            block_number: 100,
+            block_timestamp: Utc::now(),
+            chain: Chain::Flare,
+            address: "0xassetmanager".to_string(),
+            event_type,
+            decoded_data: data,
+        }
+    }
+
+    fn prices(feed: &str) -> Option<f64> {
+        match feed {
+            "XRP/USD" => Some(0.5),
+            "USDT/USD" => Some(1.0),
+            "FLR/USD" => Some(0.02),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_vault_from_agent_state() {
+        let settings = AssetSettings::usdt_backed("XRP/USD", 6, 10.0);
+        // 1 lot = 10 XRP = 10_000_000 UBA
+        let state = AgentState {
+            vault_collateral: 100_000_000.0,
+            pool_collateral: 3750e18,
+            minted_uba: 85_000_000.0,
+            reserved_uba: 20_000_000.0,
+            redeeming_uba: 9_999_999.0,
+        };
+
+        let vault = AgentVault::from_state(&state, "FXRP", &settings);
+        assert_eq!(vault.fasset.as_deref(), Some("FXRP"));
+        assert_eq!(vault.vault_collateral, 100_000_000.0);
+        assert_eq!(vault.pool_collateral, 3750e18);
+        assert_eq!(vault.minted_lots, 8);
+        assert_eq!(vault.reserved_lots, 2);
+        assert_eq!(vault.redeeming_lots, 0);
+        assert_eq!(vault.backed_lots(), 10);
+    }
+
+    #[test]
+    fn test_read_due_on_agent_events_and_interval() {
+        // Never read: always due
+        assert!(read_due(None, 100, false, 200));
+        assert!(read_due(None, 100, true, 200));
+        // Agent events of a later block are always due, of the same block never
+        assert!(read_due(Some(100), 101, true, 200));
+        assert!(!read_due(Some(100), 100, true, 200));
+        // Price ticks wait for the interval
+        assert!(!read_due(Some(100), 299, false, 200));
+        assert!(read_due(Some(100), 300, false, 200));
+    }
+
+    #[test]
+    fn test_collateral_ratios_use_lower_of_vault_and_pool() {
+        let settings = AssetSettings::usdt_backed("XRP/USD", 6, 10.0);
+        // 10 lots × 10 XRP × $0.5 = $50 backed
+        let mut vault = AgentVault {
+            fasset: Some("FXRP".to_string()),
+            vault_collateral: 100_000_000.0, // 100 USDT
+            minted_lots: 10,
+            ..Default::default()
+        };
+
+        let ratios = collateral_ratios(&vault, &settings, "FLR/USD", prices).unwrap();
+        assert_eq!(ratios.vault, 2.0);
+        assert_eq!(ratios.pool, None);
+        assert_eq!(ratios.cr(), 2.0);
+
+        // 3750 FLR × $0.02 = $75
+        vault.pool_collateral = 3750e18;
+        let ratios = collateral_ratios(&vault, &settings, "FLR/USD", prices).unwrap();
+        assert!((ratios.pool.unwrap() - 1.5).abs() < 1e-9);
+        assert!((ratios.cr() - 1.5).abs() < 1e-9);
+
+        // Nothing backed or no asset price: no ratio
+        vault.minted_lots = 0;
+        assert_eq!(
+            collateral_ratios(&vault, &settings, "FLR/USD", prices),
+            None
+        );
+        vault.minted_lots = 10;
+        let unknown = AssetSettings::usdt_backed("ABC/USD", 6, 1.0);
+        assert_eq!(collateral_ratios(&vault, &unknown, "FLR/USD", prices), None);
+    }
+
+    #[test]
+    fn test_valuation_without_prices() {
+        let settings = AssetSettings::usdt_backed("XRP/USD", 6, 10.0);
+        let vault = AgentVault {
+            vault_collateral: 100_000_000.0,
+            pool_collateral: 1000e18,
+            minted_lots: 10,
+            ..Default::default()
+        };
+
+        let valuation = valuation(&vault, &settings, "SGB/USD", prices);
+        assert_eq!(valuation.vault_collateral_usd, Some(100.0));
+        assert_eq!(valuation.pool_collateral_usd, None);
+        assert_eq!(valuation.minted_value_usd, Some(50.0));
+        assert_eq!(
+            valuation.ratios(),
+            Some(CollateralRatios {
+                vault: 2.0,
+                pool: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_snapshot_due_after_interval() {
+        let now = Utc::now();
+        let interval = Duration::from_secs(300);
+        assert!(snapshot_due(None, now, interval));
+        assert!(!snapshot_due(
+            Some(now - chrono::Duration::seconds(299)),
+            now,
+            interval
+        ));
+        assert!(snapshot_due(
+            Some(now - chrono::Duration::seconds(300)),
+            now,
+            interval
+        ));
+        // Block time going backwards (replay) is never due
+        assert!(!snapshot_due(
+            Some(now + chrono::Duration::seconds(1)),
+            now,
+            interval
+        ));
+    }
+
+    #[test]
+    fn test_crossed_only_on_downward_crossing() {
+        assert!(crossed(None, 1.7, 1.8));
+        assert!(!crossed(None, 1.9, 1.8));
+        assert!(crossed(Some(2.0), 1.7, 1.8));
+        assert!(crossed(Some(1.8), 1.79, 1.8));
+        assert!(!crossed(Some(1.7), 1.6, 1.8));
+        assert!(!crossed(Some(1.7), 1.9, 1.8));
+    }
+
+    #[test]
+    fn test_crosses_subscription_threshold() {
+        let subscription = |event_type, threshold_config| Subscription {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            address_id: Uuid::new_v4(),
+            channel_id: Uuid::new_v4(),
+            event_type,
+            threshold_config,
+            active: true,
+            created_at: Utc::now(),
+        };
+        let event = make_event(
+            EventType::CrWarning,
+            serde_json::json!({ "cr": 1.7, "previous_cr": 1.85 }),
+        );
+
+        // Default warning threshold 1.8 was crossed; a custom 1.9 one already had been
+        assert!(crosses_subscription_threshold(
+            &subscription(EventType::CrWarning, serde_json::json!({})),
+            &event
+        ));
+        assert!(!crosses_subscription_threshold(
+            &subscription(
+                EventType::CrWarning,
+                serde_json::json!({ "cr_warning": 1.9 })
+            ),
+            &event
+        ));
+
+        let critical = make_event(
+            EventType::CrCritical,
+            serde_json::json!({ "cr": 1.45, "previous_cr": null }),
+        );
+        assert!(crosses_subscription_threshold(
+            &subscription(EventType::CrCritical, serde_json::json!({})),
+            &critical
+        ));
+        assert!(!crosses_subscription_threshold(
+            &subscription(
+                EventType::CrCritical,
+                serde_json::json!({ "cr_critical": 1.4 })
+            ),
+            &critical
+        ));
+
+        // Other event types are not gated
+        assert!(crosses_subscription_threshold(
+            &subscription(EventType::LiquidationStarted, serde_json::json!({})),
+            &make_event(EventType::LiquidationStarted, serde_json::json!({}))
+        ));
+    }
+}
@@ -0,0 +1,645 @@
+//! Feed monitor — alerts for feed-scoped subscriptions.
+//!
+//! Users can subscribe to an FTSO feed instead of a contract: a `monitored_addresses`
+//! row of type `ftso_feed` whose address is the feed name, e.g. `FLR/USD`. The
+//! monitor follows each subscribed feed through `ftso_price_ticks` as the worker
+//! processes the `FtsoFeedPublished` / `FastUpdateFeeds` events that wrote them,
+//! and evaluates every subscription:
+//!
+//! - `price_threshold`: the price is below `min_value` or above `max_value`
+//! - `price_move`: the price moved at least `deviation_pct` percent away from the
+//!   feed's price `window_secs` earlier (the rolling baseline)
+//! - `feed_stale`: no update for `stale_epochs` voting epochs of block time, checked
+//!   on every event processed on the feed's chain
+//!
+//! Alerts fire when a condition starts to hold, not while it keeps holding. A
+//! synthetic event is then written for that one subscription (its ID is in
+//! `decoded_data.subscription_id`, see `targets_subscription`). Condition state is
+//! in memory, so a condition still holding after a restart alerts once more.
+//!
+//! The same rolling baseline backs `deviation_pct` on indexed feed events, which
+//! carry no `baseline` of their own (see `with_rolling_baseline`).
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use flare_common::types::{Chain, DecodedEvent, EventType, Subscription, ThresholdConfig};
+use flare_decoders::ftso::{feed_id, feed_id_hex};
+
+use crate::cr_calculator::CRYPTO_FEED_CATEGORY;
+use crate::synthetic::{is_synthetic, synthetic_log_index};
+
+/// Synthetic event types raised by the monitor.
+pub const FEED_EVENT_TYPES: [EventType; 3] = [
+    EventType::PriceThreshold,
+    EventType::PriceMove,
+    EventType::FeedStale,
+];
+
+/// `window_secs` when the subscription doesn't set one.
+pub const DEFAULT_WINDOW_SECS: u64 = 3600;
+
+/// `stale_epochs` when the subscription doesn't set one.
+pub const DEFAULT_STALE_EPOCHS: u64 = 3;
+
+/// Length of an FTSO v2 voting epoch.
+pub const VOTING_EPOCH: Duration = Duration::from_secs(90);
+
+/// Normalize a crypto feed name (`flr/usd` → `FLR/USD`) and return it with its hex feed ID.
+pub fn parse_feed(name: &str) -> Option<(String, String)> {
+    let name = name.trim().to_uppercase();
+    let (base, quote) = name.split_once('/')?;
+    if base.is_empty() || quote.is_empty() {
+        return None;
+    }
+    let id = feed_id(CRYPTO_FEED_CATEGORY, &name)?;
+    Some((name, feed_id_hex(&id)))
+}
+
+fn window(config: &ThresholdConfig) -> Duration {
+    Duration::from_secs(config.window_secs.unwrap_or(DEFAULT_WINDOW_SECS))
+}
+
+/// `price_threshold`: details of the crossed bound if `price` is outside
+/// `min_value`..`max_value`.
+pub fn price_threshold(config: &ThresholdConfig, price: f64) -> Option<serde_json::Value> {
+    if let Some(min) = config.min_value
+        && price < min
+    {
+        return Some(json!({ "direction": "below", "threshold": min }));
+    }
+    if let Some(max) = config.max_value
+        && price > max
+    {
+        return Some(json!({ "direction": "above", "threshold": max }));
+    }
+    None
+}
+
+/// `price_move`: details of the move if `price` is at least `deviation_pct`
+/// percent away from `baseline`.
+pub fn price_move(
+    config: &ThresholdConfig,
+    price: f64,
+    baseline: Option<f64>,
+) -> Option<serde_json::Value> {
+    let threshold = config.deviation_pct?;
+    let baseline = baseline.filter(|baseline| *baseline != 0.0)?;
+    let change_pct = (price - baseline) / baseline * 100.0;
+    (change_pct.abs() >= threshold).then(|| {
+        json!({
+            "baseline": baseline,
+            "change_pct": change_pct,
+            "window_secs": window(config).as_secs(),
+        })
+    })
+}
+
+/// `feed_stale`: details of the gap if the feed last updated at `last_update`
+/// hasn't updated for `stale_epochs` voting epochs at `now`.
+pub fn feed_stale(
+    config: &ThresholdConfig,
+    last_update: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Option<serde_json::Value> {
+    let epochs = config.stale_epochs.unwrap_or(DEFAULT_STALE_EPOCHS);
+    let age = (now - last_update).to_std().ok()?;
+    (age >= VOTING_EPOCH * epochs as u32).then(|| {
+        json!({
+            "last_update": last_update,
+            "stale_secs": age.as_secs(),
+            "stale_epochs": epochs,
+        })
+    })
+}
+
+/// Whether a synthetic feed event was raised for `subscription`. Other event
+/// types always pass.
+pub fn targets_subscription(subscription: &Subscription, event: &DecodedEvent) -> bool {
+    if !FEED_EVENT_TYPES.contains(&event.event_type) {
+        return true;
+    }
+    event
+        .decoded_data
+        .get("subscription_id")
+        .and_then(|v| v.as_str())
+        .is_some_and(|id| id == subscription.id.to_string())
+}
+
+/// For a subscription with `deviation_pct`, a copy of an indexed feed event with
+/// `baseline` set to the feed's price `window_secs` before the event.
+///
+/// `None` if the subscription has no `deviation_pct`, the event already carries
+/// a baseline or isn't for a single feed, or no tick is old enough. Anchor feed
+/// events carry the unscaled value, so the baseline is unscaled with their `decimals`.
+pub async fn with_rolling_baseline(
+    pool: &PgPool,
+    subscription: &Subscription,
+    event: &DecodedEvent,
+) -> anyhow::Result<Option<DecodedEvent>> {
+    let config: ThresholdConfig =
+        serde_json::from_value(subscription.threshold_config.clone()).unwrap_or_default();
+    let data = &event.decoded_data;
+    if config.deviation_pct.is_none() || data.get("baseline").is_some() {
+        return Ok(None);
+    }
+    let Some(feed_id) = data.get("feed_id").and_then(|v| v.as_str()) else {
+        return Ok(None);
+    };
+
+    let baseline: Option<(f64,)> = sqlx::query_as(
+        r#"
+        SELECT price::float8 FROM ftso_price_ticks
+        WHERE chain = $1 AND feed_id = $2 AND block_timestamp <= $3
+        ORDER BY block_timestamp DESC, id DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(event.chain.to_string())
+    .bind(feed_id)
+    .bind(event.block_timestamp - window(&config))
+    .fetch_optional(pool)
+    .await?;
+    let Some((baseline,)) = baseline else {
+        return Ok(None);
+    };
+
+    let decimals = data.get("decimals").and_then(|v| v.as_i64()).unwrap_or(0);
+    let mut enriched = event.clone();
+    enriched.decoded_data["baseline"] = json!(baseline * 10f64.powi(decimals as i32));
+    Ok(Some(enriched))
+}
+
+/// Recent prices of one feed.
+#[derive(Debug, Clone, Default)]
+struct FeedState {
+    /// (block time, price) of recent ticks, oldest first
+    history: VecDeque<(DateTime<Utc>, f64)>,
+    /// Whether `history` has been loaded from stored ticks
+    seeded: bool,
+    last_update: Option<DateTime<Utc>>,
+    last_price: Option<f64>,
+}
+
+impl FeedState {
+    /// Record a tick, keeping enough history for baselines `keep` back.
+    fn push(&mut self, at: DateTime<Utc>, price: f64, keep: Duration) {
+        self.history.push_back((at, price));
+        self.last_update = Some(at);
+        self.last_price = Some(price);
+
+        // Keep the newest sample at or before the window start: it is the baseline
+        let Ok(keep) = chrono::Duration::from_std(keep) else {
+            return;
+        };
+        while self.history.len() > 1 && self.history[1].0 <= at - keep {
+            self.history.pop_front();
+        }
+    }
+
+    /// The latest price at or before `at`.
+    fn price_at(&self, at: DateTime<Utc>) -> Option<f64> {
+        self.history
+            .iter()
+            .rev()
+            .find(|(t, _)| *t <= at)
+            .map(|(_, price)| *price)
+    }
+}
+
+/// A feed-scoped subscription.
+#[derive(Debug, Clone)]
+struct FeedSubscription {
+    id: Uuid,
+    event_type: EventType,
+    config: ThresholdConfig,
+    /// Feed name as stored in `monitored_addresses`, which synthetic events are emitted for
+    feed: String,
+    /// Whether the condition held at the last evaluation
+    met: bool,
+}
+
+/// Chain and hex feed ID.
+type FeedKey = (Chain, String);
+
+/// Evaluates feed-scoped subscriptions against price ticks.
+pub struct FeedMonitor {
+    subscriptions: HashMap<FeedKey, Vec<FeedSubscription>>,
+    feeds: HashMap<FeedKey, FeedState>,
+}
+
+impl FeedMonitor {
+    pub fn new() -> Self {
+        Self {
+            subscriptions: HashMap::new(),
+            feeds: HashMap::new(),
+        }
+    }
+
+    /// Forget all feed prices, e.g. after reorged events were retracted.
+    /// The next `refresh` and ticks reload them from the canonical chain.
+    pub fn reset(&mut self) {
+        self.feeds.clear();
+    }
+
+    /// Sync with active feed-scoped subscriptions.
+    ///
+    /// Newly subscribed feeds start from their latest stored tick, so staleness can
+    /// be detected before the feed updates again.
+    pub async fn refresh(&mut self, pool: &PgPool) -> anyhow::Result<()> {
+        let event_types: Vec<String> = FEED_EVENT_TYPES.iter().map(|t| t.to_string()).collect();
+        let rows: Vec<(Uuid, EventType, serde_json::Value, Chain, String)> = sqlx::query_as(
+            r#"
+            SELECT s.id, s.event_type, s.threshold_config, ma.chain, ma.address
+            FROM subscriptions s
+            JOIN monitored_addresses ma ON ma.id = s.address_id
+            WHERE s.active AND ma.address_type = 'ftso_feed' AND s.event_type = ANY($1)
+            "#,
+        )
+        .bind(&event_types)
+        .fetch_all(pool)
+        .await?;
+
+        let previously_met: HashSet<Uuid> = self
+            .subscriptions
+            .values()
+            .flatten()
+            .filter(|sub| sub.met)
+            .map(|sub| sub.id)
+            .collect();
+
+        let mut subscriptions: HashMap<FeedKey, Vec<FeedSubscription>> = HashMap::new();
+        for (id, event_type, threshold_config, chain, address) in rows {
+            let Some((_, feed_id)) = parse_feed(&address) else {
+                tracing::warn!(subscription_id = %id, feed = %address, "Skipping subscription to an invalid feed name");
+                continue;
+            };
+            subscriptions
+                .entry((chain, feed_id))
+                .or_default()
+                .push(FeedSubscription {
+                    id,
+                    event_type,
+                    config: serde_json::from_value(threshold_config).unwrap_or_default(),
+                    feed: address,
+                    met: previously_met.contains(&id),
+                });
+        }
+
+        self.feeds.retain(|key, _| subscriptions.contains_key(key));
+        for key in subscriptions.keys() {
+            if self.feeds.contains_key(key) {
+                continue;
+            }
+            let latest: Option<(DateTime<Utc>, f64)> = sqlx::query_as(
+                r#"
+                SELECT block_timestamp, price::float8 FROM ftso_price_ticks
+                WHERE chain = $1 AND feed_id = $2
+                ORDER BY block_timestamp DESC, id DESC
+                LIMIT 1
+                "#,
+            )
+            .bind(key.0.to_string())
+            .bind(&key.1)
+            .fetch_optional(pool)
+            .await?;
+            self.feeds.insert(
+                key.clone(),
+                FeedState {
+                    last_update: latest.map(|(at, _)| at),
+                    last_price: latest.map(|(_, price)| price),
+                    ..Default::default()
+                },
+            );
+        }
+
+        self.subscriptions = subscriptions;
+        Ok(())
+    }
+
+    /// Update feed prices from a processed event and evaluate the affected subscriptions.
+    ///
+    /// Returns the synthetic feed events to persist.
+    pub async fn observe(
+        &mut self,
+        pool: &PgPool,
+        event: &DecodedEvent,
+    ) -> anyhow::Result<Vec<DecodedEvent>> {
+        // Synthetic events carry their trigger's block time, which the monitor has seen
+        if is_synthetic(event) {
+            return Ok(Vec::new());
+        }
+
+        let chain = event.chain;
+        let subscribed: Vec<FeedKey> = self
+            .subscriptions
+            .keys()
+            .filter(|(c, _)| *c == chain)
+            .cloned()
+            .collect();
+        if subscribed.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut emitted = Vec::new();
+        if matches!(
+            event.event_type,
+            EventType::FtsoFeedPublished | EventType::FastUpdateFeeds
+        ) {
+            // The indexer wrote this event's ticks in the same transaction
+            let feed_ids: Vec<&String> = subscribed.iter().map(|(_, id)| id).collect();
+            let ticks: Vec<(String, f64)> = sqlx::query_as(
+                r#"
+                SELECT feed_id, price::float8 FROM ftso_price_ticks
+                WHERE chain = $1 AND tx_hash = $2 AND log_index = $3 AND feed_id = ANY($4)
+                "#,
+            )
+            .bind(chain.to_string())
+            .bind(&event.tx_hash)
+            .bind(event.log_index.unwrap_or_default() as i64)
+            .bind(&feed_ids)
+            .fetch_all(pool)
+            .await?;
+
+            for (feed_id, price) in ticks {
+                let key = (chain, feed_id);
+                let keep = self.longest_window(&key);
+                if !self.feeds.get(&key).is_some_and(|state| state.seeded) {
+                    let history =
+                        Self::load_history(pool, &key, event.block_timestamp, keep).await?;
+                    let state = self.feeds.entry(key.clone()).or_default();
+                    state.history = history;
+                    state.seeded = true;
+                }
+                if let Some(state) = self.feeds.get_mut(&key) {
+                    state.push(event.block_timestamp, price, keep);
+                }
+                emitted.extend(self.evaluate(
+                    &key,
+                    event,
+                    &[EventType::PriceThreshold, EventType::PriceMove],
+                ));
+            }
+        }
+
+        for key in &subscribed {
+            emitted.extend(self.evaluate(key, event, &[EventType::FeedStale]));
+        }
+        Ok(emitted)
+    }
+
+    /// The longest `window_secs` among the subscriptions to a feed.
+    fn longest_window(&self, key: &FeedKey) -> Duration {
+        self.subscriptions
+            .get(key)
+            .into_iter()
+            .flatten()
+            .filter(|sub| sub.event_type == EventType::PriceMove)
+            .map(|sub| window(&sub.config))
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Stored ticks of a feed before `before`, from the last one at or before the
+    /// start of the `keep` window on.
+    async fn load_history(
+        pool: &PgPool,
+        key: &FeedKey,
+        before: DateTime<Utc>,
+        keep: Duration,
+    ) -> anyhow::Result<VecDeque<(DateTime<Utc>, f64)>> {
+        let start = before - chrono::Duration::from_std(keep).unwrap_or_default();
+        let rows: Vec<(DateTime<Utc>, f64)> = sqlx::query_as(
+            r#"
+            SELECT block_timestamp, price::float8 FROM ftso_price_ticks
+            WHERE chain = $1 AND feed_id = $2 AND block_timestamp < $3
+              AND block_timestamp >= COALESCE((
+                  SELECT MAX(block_timestamp) FROM ftso_price_ticks
+                  WHERE chain = $1 AND feed_id = $2 AND block_timestamp <= $4
+              ), $4)
+            ORDER BY block_timestamp, id
+            "#,
+        )
+        .bind(key.0.to_string())
+        .bind(&key.1)
+        .bind(before)
+        .bind(start)
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into())
+    }
+
+    /// Evaluate the subscriptions of the given types to a feed and build the
+    /// synthetic events for conditions that started to hold.
+    fn evaluate(
+        &mut self,
+        key: &FeedKey,
+        trigger: &DecodedEvent,
+        event_types: &[EventType],
+    ) -> Vec<DecodedEvent> {
+        let (Some(subscriptions), Some(state)) =
+            (self.subscriptions.get_mut(key), self.feeds.get(key))
+        else {
+            return Vec::new();
+        };
+        let now = trigger.block_timestamp;
+
+        let mut emitted = Vec::new();
+        for sub in subscriptions
+            .iter_mut()
+            .filter(|sub| event_types.contains(&sub.event_type))
+        {
+            let details = match (sub.event_type.clone(), state.last_price) {
+                (EventType::PriceThreshold, Some(price)) => price_threshold(&sub.config, price),
+                (EventType::PriceMove, Some(price)) => {
+                    let start =
+                        now - chrono::Duration::from_std(window(&sub.config)).unwrap_or_default();
+                    price_move(&sub.config, price, state.price_at(start))
+                }
+                (EventType::FeedStale, _) => state
+                    .last_update
+                    .and_then(|last_update| feed_stale(&sub.config, last_update, now)),
+                _ => None,
+            };
+
+            let fired = details.is_some() && !sub.met;
+            sub.met = details.is_some();
+            let Some(serde_json::Value::Object(details)) = details.filter(|_| fired) else {
+                continue;
+            };
+
+            let mut data = json!({
+                "feed": sub.feed,
+                "feed_id": key.1,
+                "subscription_id": sub.id.to_string(),
+                "price": state.last_price,
+                "trigger": trigger.event_type.to_string(),
+            });
+            if let Some(data) = data.as_object_mut() {
+                data.extend(details);
+            }
+
+            tracing::info!(
+                chain = %key.0,
+                feed = %sub.feed,
+                subscription_id = %sub.id,
+                event_type = %sub.event_type,
+                "Feed subscription condition started to hold"
+            );
+            emitted.push(DecodedEvent {
+                tx_hash: trigger.tx_hash.clone(),
+                log_index: Some(synthetic_log_index(
+                    trigger,
+                    &sub.id.to_string(),
+                    &sub.event_type,
+                )),
+                block_number: trigger.block_number,
+                block_timestamp: trigger.block_timestamp,
+                chain: key.0,
+                address: sub.feed.clone(),
+                event_type: sub.event_type.clone(),
+                decoded_data: data,
+            });
+        }
+        emitted
+    }
+}
+
+impl Default for FeedMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(value: serde_json::Value) -> ThresholdConfig {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_feed() {
+        let (name, id) = parse_feed(" flr/usd ").unwrap();
+        assert_eq!(name, "FLR/USD");
+        assert_eq!(
+            id,
+            format!("0x01{}{}", alloy::hex::encode("FLR/USD"), "00".repeat(13))
+        );
+        assert_eq!(parse_feed("FLRUSD"), None);
+        assert_eq!(parse_feed("/USD"), None);
+        assert_eq!(parse_feed("A_VERY_LONG_FEED_NAME/USD"), None);
+    }
+
+    #[test]
+    fn test_price_threshold() {
+        let config = config(json!({ "min_value": 0.01, "max_value": 0.03 }));
+        assert_eq!(price_threshold(&config, 0.02), None);
+        assert_eq!(
+            price_threshold(&config, 0.005),
+            Some(json!({ "direction": "below", "threshold": 0.01 }))
+        );
+        assert_eq!(
+            price_threshold(&config, 0.031),
+            Some(json!({ "direction": "above", "threshold": 0.03 }))
+        );
+    }
+
+    #[test]
+    fn test_price_move_against_baseline() {
+        let config = config(json!({ "deviation_pct": 5.0, "window_secs": 600 }));
+        assert_eq!(price_move(&config, 1.04, Some(1.0)), None);
+        assert_eq!(price_move(&config, 1.0, None), None);
+
+        let details = price_move(&config, 0.94, Some(1.0)).unwrap();
+        assert!((details["change_pct"].as_f64().unwrap() + 6.0).abs() < 1e-9);
+        assert_eq!(details["window_secs"], 600);
+    }
+
+    #[test]
+    fn test_feed_stale_after_epochs() {
+        let now = Utc::now();
+        let config = config(json!({ "stale_epochs": 2 }));
+        assert_eq!(
+            feed_stale(&config, now - chrono::Duration::seconds(179), now),
+            None
+        );
+        let details = feed_stale(&config, now - chrono::Duration::seconds(180), now).unwrap();
+        assert_eq!(details["stale_secs"], 180);
+        // Default: 3 epochs
+        assert!(
+            feed_stale(
+                &ThresholdConfig::default(),
+                now - chrono::Duration::seconds(200),
+                now
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_history_keeps_baseline_sample() {
+        let start = Utc::now();
+        let at = |secs| start + chrono::Duration::seconds(secs);
+        let keep = Duration::from_secs(60);
+        let mut state = FeedState::default();
+        for (secs, price) in [(0, 1.0), (30, 1.1), (70, 1.2), (100, 1.3)] {
+            state.push(at(secs), price, keep);
+        }
+
+        // 100 - 60 = 40: the sample at 30 is the baseline, the one at 0 is dropped
+        assert_eq!(state.history.len(), 3);
+        assert_eq!(state.price_at(at(40)), Some(1.1));
+        assert_eq!(state.price_at(at(100)), Some(1.3));
+        assert_eq!(state.last_price, Some(1.3));
+    }
+
+    #[test]
+    fn test_targets_subscription() {
+        let subscription = Subscription {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            address_id: Uuid::new_v4(),
+            channel_id: Uuid::new_v4(),
+            event_type: EventType::PriceMove,
+            threshold_config: json!({}),
+            active: true,
+            created_at: Utc::now(),
+        };
+        let event = |event_type, data| DecodedEvent {
+            tx_hash: "0xabc".to_string(),
+            log_index: Some(0),
+            block_number: 1,
+            block_timestamp: Utc::now(),
+            chain: Chain::Flare,
+            address: "FLR/USD".to_string(),
+            event_type,
+            decoded_data: data,
+        };
+
+        assert!(targets_subscription(
+            &subscription,
+            &event(
+                EventType::PriceMove,
+                json!({ "subscription_id": subscription.id.to_string() })
+            )
+        ));
+        assert!(!targets_subscription(
+            &subscription,
+            &event(
+                EventType::PriceMove,
+                json!({ "subscription_id": Uuid::new_v4().to_string() })
+            )
+        ));
+        assert!(targets_subscription(
+            &subscription,
+            &event(EventType::FtsoFeedPublished, json!({}))
+        ));
+    }
+}
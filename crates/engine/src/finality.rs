@@ -0,0 +1,104 @@
+//! Confirmation-depth gating for alerts.
+//!
+//! A subscription with `threshold_config.confirmations = N` still gets its alert
+//! created as soon as the event matches, but the notification is stored as `held`
+//! instead of being queued. Once the indexed chain head (`indexer_state`) is `N`
+//! blocks past the event, the notification is released to `pending` and queued.
+//!
+//! Held notifications are never released while their event is flagged as reorged.
+//! If the event doesn't come back, the alert is retracted and the held notification
+//! cancelled along with other undelivered ones (see `retraction`), so nothing is
+//! ever sent for it.
+
+use redis::aio::ConnectionManager;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use flare_common::queue;
+use flare_common::types::{Subscription, ThresholdConfig};
+
+/// Confirmations a subscription requires before its alerts are delivered.
+pub fn required_confirmations(subscription: &Subscription) -> u64 {
+    let config: ThresholdConfig =
+        serde_json::from_value(subscription.threshold_config.clone()).unwrap_or_default();
+    config.confirmations.unwrap_or(0)
+}
+
+/// Release held notifications whose events have reached their confirmation depth.
+///
+/// Returns the number of notifications released to the delivery queue.
+pub async fn release_confirmed(
+    pool: &PgPool,
+    redis: &mut ConnectionManager,
+) -> anyhow::Result<usize> {
+    let released: Vec<(Uuid,)> = sqlx::query_as(
+        r#"
+        UPDATE notifications n SET status = 'pending'
+        FROM alerts a
+        JOIN indexed_events e ON e.id = a.event_id
+        JOIN indexer_state s ON s.chain = e.chain
+        WHERE n.alert_id = a.id
+          AND n.status = 'held'
+          AND a.retracted_at IS NULL
+          AND NOT e.is_reorged
+          AND s.last_block >= e.block_number + a.confirmations
+        RETURNING n.id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // Released rows are `pending`, so the stale sweep covers a failed push
+    for (id,) in &released {
+        if let Err(e) = queue::enqueue_notification(redis, *id).await {
+            tracing::warn!(
+                notification_id = %id,
+                error = %e,
+                "Failed to enqueue confirmed notification — will be picked up by the pending sweep"
+            );
+        }
+    }
+
+    if !released.is_empty() {
+        tracing::info!(
+            count = released.len(),
+            "Released notifications that reached their confirmation depth"
+        );
+    }
+
+    Ok(released.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use flare_common::types::EventType;
+
+    fn make_subscription(threshold_config: serde_json::Value) -> Subscription {
+        Subscription {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            address_id: Uuid::new_v4(),
+            channel_id: Uuid::new_v4(),
+            event_type: EventType::CollateralWithdrawn,
+            threshold_config,
+            active: true,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_required_confirmations() {
+        assert_eq!(
+            required_confirmations(&make_subscription(serde_json::json!({}))),
+            0
+        );
+        assert_eq!(
+            required_confirmations(&make_subscription(
+                serde_json::json!({ "confirmations": 12 })
+            )),
+            12
+        );
+    }
+}
@@ -1,7 +1,16 @@
+pub mod agent_info;
 pub mod analyzer;
+pub mod channel;
 pub mod cooldown;
 pub mod cr_calculator;
+pub mod feed_monitor;
+pub mod finality;
 pub mod hysteresis;
 pub mod matcher;
+pub mod notification;
 pub mod processor;
+pub mod retraction;
+pub mod signing_policy;
 pub mod subscription;
+pub mod synthetic;
+pub mod worker;
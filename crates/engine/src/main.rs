@@ -0,0 +1,75 @@
+//! FlareEmissary alert engine binary entrypoint.
+
+use std::time::Duration;
+
+use flare_common::config::AppConfig;
+use flare_common::db;
+use flare_common::redis_pool::create_redis_pool;
+use flare_engine::agent_info::AgentInfoReader;
+use flare_engine::cr_calculator::CrCalculator;
+use flare_engine::worker::EventWorker;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Initialize tracing
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "flare_engine=info".into()),
+        )
+        .json()
+        .init();
+
+    tracing::info!("FlareEmissary Engine starting...");
+
+    // Load configuration
+    let config = AppConfig::from_env()?;
+
+    // Connect to database
+    let pool = db::create_pool(&config.database_url, config.db_max_connections).await?;
+
+    // Run migrations
+    sqlx::migrate!("../../migrations").run(&pool).await?;
+    tracing::info!("Database migrations applied");
+
+    // Connect to Redis (cooldown state)
+    let redis = create_redis_pool(&config.redis_url).await?;
+
+    // Agent vaults are seeded through each indexed chain's RPC endpoint
+    let agent_info = config
+        .indexer_chains
+        .iter()
+        .try_fold(AgentInfoReader::new(), |reader, chain| {
+            reader.with_chain(chain.chain, &chain.rpc_url)
+        })?;
+
+    let mut worker = EventWorker::new(
+        pool,
+        redis,
+        config.engine_poll_interval_ms,
+        config.engine_batch_size,
+    )
+    .with_retraction_notices(config.engine_retraction_notices)
+    .with_cr_calculator(
+        CrCalculator::new()
+            .with_snapshot_interval(Duration::from_secs(config.engine_snapshot_interval_secs))
+            .with_refresh_interval(config.engine_vault_refresh_blocks)
+            .with_agent_info(agent_info),
+    );
+
+    // Run with graceful shutdown on Ctrl+C
+    tokio::select! {
+        result = worker.run() => {
+            if let Err(e) = result {
+                tracing::error!(error = %e, "Event worker exited with error");
+                return Err(e);
+            }
+        }
+        _ = tokio::signal::ctrl_c() => {
+            tracing::info!("Received shutdown signal, stopping gracefully...");
+        }
+    }
+
+    tracing::info!("FlareEmissary Engine stopped.");
+    Ok(())
+}
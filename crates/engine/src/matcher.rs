@@ -1,13 +1,24 @@
 //! Alert matcher — evaluates incoming events against user subscriptions.
 //!
 //! For each incoming event:
-//! 1. Load active subscriptions filtering by address + event_type
-//! 2. Evaluate threshold_config against event decoded_data
-//! 3. Return qualifying subscriptions for further hysteresis/cooldown evaluation
+//! 1. Load active subscriptions filtering by chain + address + event_type, where the
+//!    address is the emitting contract or, for voter events, the voter
+//! 2. Drop events for other FAssets than the subscription's `fasset`, if set
+//! 3. Evaluate threshold_config against event decoded_data
+//! 4. Return qualifying subscriptions for further hysteresis/cooldown evaluation
 
 use sqlx::PgPool;
 
-use flare_common::types::{DecodedEvent, Subscription, ThresholdConfig};
+use flare_common::types::{DecodedEvent, EventType, Subscription, ThresholdConfig};
+
+/// Flare Systems Protocol events that also match subscriptions on their `voter`.
+const VOTER_EVENT_TYPES: [EventType; 5] = [
+    EventType::VoterRegistered,
+    EventType::VoterRemoved,
+    EventType::SigningPolicySigned,
+    EventType::RewardsSigned,
+    EventType::UptimeVoteSigned,
+];
 
 /// Alert matcher that evaluates events against subscriptions.
 pub struct AlertMatcher;
@@ -17,8 +28,9 @@ impl AlertMatcher {
         Self
     }
 
-    /// Find all active subscriptions whose monitored address and event type
-    /// match the incoming event.
+    /// Find all active subscriptions whose monitored address (on the event's
+    /// chain) is one of the event's `subject_addresses` and whose event type
+    /// matches the incoming event.
     pub async fn find_matching_subscriptions(
         &self,
         event: &DecodedEvent,
@@ -29,12 +41,14 @@ impl AlertMatcher {
             SELECT s.*
             FROM subscriptions s
             JOIN monitored_addresses ma ON s.address_id = ma.id
-            WHERE ma.address = $1
-              AND s.event_type = $2
+            WHERE ma.address = ANY($1)
+              AND ma.chain = $2
+              AND s.event_type = $3
               AND s.active = true
             "#,
         )
-        .bind(&event.address)
+        .bind(Self::subject_addresses(event))
+        .bind(event.chain.to_string())
         .bind(event.event_type.to_string())
         .fetch_all(pool)
         .await?;
@@ -42,12 +56,47 @@ impl AlertMatcher {
         Ok(subs)
     }
 
+    /// Addresses whose subscriptions an event can match: the emitting contract, the
+    /// voter of voter events, and the voters that dropped out of a signing policy
+    /// (see `signing_policy`).
+    pub fn subject_addresses(event: &DecodedEvent) -> Vec<String> {
+        let data = &event.decoded_data;
+        let mut addresses = vec![event.address.clone()];
+        if VOTER_EVENT_TYPES.contains(&event.event_type)
+            && let Some(voter) = data.get("voter").and_then(|v| v.as_str())
+        {
+            addresses.push(voter.to_string());
+        }
+        if let Some(dropped) = data.get("dropped_voters").and_then(|v| v.as_array()) {
+            addresses.extend(dropped.iter().filter_map(|v| v.as_str()).map(String::from));
+        }
+        addresses
+    }
+
+    /// Whether the event is for the FAsset the subscription is scoped to.
+    ///
+    /// Subscriptions without `fasset` match every event; scoped ones only match
+    /// events tagged with the same symbol by the indexer.
+    pub fn matches_fasset(subscription: &Subscription, event: &DecodedEvent) -> bool {
+        let config: ThresholdConfig =
+            serde_json::from_value(subscription.threshold_config.clone()).unwrap_or_default();
+        match config.fasset {
+            None => true,
+            Some(symbol) => event
+                .decoded_data
+                .get("fasset")
+                .and_then(|v| v.as_str())
+                .is_some_and(|tagged| tagged.eq_ignore_ascii_case(&symbol)),
+        }
+    }
+
     /// Evaluate whether an event meets a subscription's threshold criteria.
     ///
     /// Threshold config fields:
     /// - `min_value`: alert if event value drops below this
     /// - `max_value`: alert if event value rises above this
-    /// - `deviation_pct`: alert if percentage deviation exceeds this
+    /// - `deviation_pct`: alert if percentage deviation from the event's `baseline`
+    ///   exceeds this (for feed events, the processor supplies a rolling baseline)
     ///
     /// If no threshold fields are set, the subscription always matches
     /// (useful for "notify on any event of this type" subscriptions).
@@ -232,4 +281,40 @@ mod tests {
         // Can't extract value → doesn't match
         assert!(!AlertMatcher::evaluate_threshold(&sub, &event));
     }
+
+    #[test]
+    fn test_subject_addresses() {
+        let mut event = make_event(serde_json::json!({ "voter": "0xvoter" }));
+        assert_eq!(AlertMatcher::subject_addresses(&event), vec!["0xtest"]);
+
+        event.event_type = EventType::VoterRemoved;
+        assert_eq!(
+            AlertMatcher::subject_addresses(&event),
+            vec!["0xtest", "0xvoter"]
+        );
+
+        event.event_type = EventType::SigningPolicyInitialized;
+        event.decoded_data = serde_json::json!({ "dropped_voters": ["0xa", "0xb"] });
+        assert_eq!(
+            AlertMatcher::subject_addresses(&event),
+            vec!["0xtest", "0xa", "0xb"]
+        );
+    }
+
+    #[test]
+    fn test_fasset_scope() {
+        let fxrp = make_event(serde_json::json!({"agent": "0xagent", "fasset": "FXRP"}));
+        let untagged = make_event(serde_json::json!({"agent": "0xagent"}));
+
+        let any = make_subscription(serde_json::json!({}));
+        assert!(AlertMatcher::matches_fasset(&any, &fxrp));
+        assert!(AlertMatcher::matches_fasset(&any, &untagged));
+
+        let scoped = make_subscription(serde_json::json!({"fasset": "fxrp"}));
+        assert!(AlertMatcher::matches_fasset(&scoped, &fxrp));
+        assert!(!AlertMatcher::matches_fasset(&scoped, &untagged));
+
+        let other = make_subscription(serde_json::json!({"fasset": "FBTC"}));
+        assert!(!AlertMatcher::matches_fasset(&other, &fxrp));
+    }
 }
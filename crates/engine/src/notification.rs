@@ -0,0 +1,102 @@
+//! Notification service — inspection and re-drive of dead-lettered deliveries.
+//!
+//! The notifier moves notifications to `dead_letter` when a provider rejects them
+//! permanently or they run out of retry attempts. Once the cause is fixed (e.g., the
+//! channel config is corrected), re-driving resets them to `pending` with a fresh
+//! attempt budget; callers then push the returned IDs onto the delivery queue.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use flare_common::error::AppError;
+use flare_common::types::Notification;
+
+/// Maximum number of dead letters returned by one listing.
+const MAX_DEAD_LETTER_LIMIT: i64 = 500;
+
+/// Service layer for notification operations.
+pub struct NotificationService;
+
+impl NotificationService {
+    /// List a user's dead-lettered notifications, newest first.
+    pub async fn list_dead_letters(
+        pool: &PgPool,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Notification>, AppError> {
+        let notifications: Vec<Notification> = sqlx::query_as(
+            r#"
+            SELECT n.*
+            FROM notifications n
+            JOIN notification_channels c ON c.id = n.channel_id
+            WHERE c.user_id = $1 AND n.status = 'dead_letter'
+            ORDER BY n.created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit.clamp(1, MAX_DEAD_LETTER_LIMIT))
+        .fetch_all(pool)
+        .await?;
+
+        Ok(notifications)
+    }
+
+    /// Reset one of the user's dead letters to `pending`.
+    pub async fn redrive(
+        pool: &PgPool,
+        notification_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Notification, AppError> {
+        let notification: Notification = sqlx::query_as(
+            r#"
+            UPDATE notifications n
+            SET status = 'pending', attempts = 0, next_attempt_at = NULL, error_detail = NULL
+            FROM notification_channels c
+            WHERE n.id = $1
+              AND c.id = n.channel_id
+              AND c.user_id = $2
+              AND n.status = 'dead_letter'
+            RETURNING n.*
+            "#,
+        )
+        .bind(notification_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(format!(
+                "Dead-lettered notification {} not found",
+                notification_id
+            ))
+        })?;
+
+        tracing::info!(notification_id = %notification_id, "Dead letter re-driven");
+
+        Ok(notification)
+    }
+
+    /// Reset all of the user's dead letters to `pending`. Returns their IDs.
+    pub async fn redrive_all(pool: &PgPool, user_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let ids: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            UPDATE notifications n
+            SET status = 'pending', attempts = 0, next_attempt_at = NULL, error_detail = NULL
+            FROM notification_channels c
+            WHERE c.id = n.channel_id
+              AND c.user_id = $1
+              AND n.status = 'dead_letter'
+            RETURNING n.id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        if !ids.is_empty() {
+            tracing::info!(user_id = %user_id, count = ids.len(), "Dead letters re-driven");
+        }
+
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
+}
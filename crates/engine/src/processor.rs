@@ -5,17 +5,24 @@
 //! 2. Matches against active subscriptions (via `AlertMatcher`)
 //! 3. Evaluates hysteresis and cooldown (via `HysteresisEngine` + `CooldownEngine`)
 //! 4. Creates `Alert` + `Notification` records for qualifying events
+//! 5. Enqueues the notification ID on the Redis delivery queue, or holds it if the
+//!    subscription requires confirmations (see `finality`)
 
 use chrono::Utc;
 use redis::aio::ConnectionManager;
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use flare_common::queue;
 use flare_common::types::{DecodedEvent, DeliveryStatus, EventType, NotificationPayload, Severity};
 
 use crate::cooldown::CooldownEngine;
+use crate::cr_calculator::crosses_subscription_threshold;
+use crate::feed_monitor::{targets_subscription, with_rolling_baseline};
+use crate::finality::required_confirmations;
 use crate::hysteresis::HysteresisEngine;
 use crate::matcher::AlertMatcher;
+use crate::signing_policy::with_policy_dropouts;
 
 /// Central event processor that orchestrates the alert pipeline.
 pub struct EventProcessor {
@@ -46,6 +53,9 @@ impl EventProcessor {
         pool: &PgPool,
         redis: &mut ConnectionManager,
     ) -> anyhow::Result<u32> {
+        // Compare signing policies with the previous one so dropped voters are alerted
+        let enriched = with_policy_dropouts(pool, event).await?;
+        let event = enriched.as_ref().unwrap_or(event);
         let payload = Self::translate_event(event);
 
         // Find all active subscriptions matching this event's address + type
@@ -61,8 +71,16 @@ impl EventProcessor {
         let mut alerts_created = 0u32;
 
         for sub in &subscriptions {
-            // Evaluate threshold
-            if !AlertMatcher::evaluate_threshold(sub, event) {
+            // deviation_pct on feed events is measured against the feed's rolling baseline
+            let enriched = with_rolling_baseline(pool, sub, event).await?;
+            let event = enriched.as_ref().unwrap_or(event);
+
+            // Evaluate asset scope and threshold
+            if !AlertMatcher::matches_fasset(sub, event)
+                || !targets_subscription(sub, event)
+                || !crosses_subscription_threshold(sub, event)
+                || !AlertMatcher::evaluate_threshold(sub, event)
+            {
                 continue;
             }
 
@@ -80,16 +98,23 @@ impl EventProcessor {
             let alert_id = Uuid::new_v4();
             let notification_id = Uuid::new_v4();
             let now = Utc::now();
+            let confirmations = required_confirmations(sub);
 
-            // Insert alert
-            sqlx::query(
+            // Insert alert + notification atomically. The unique (subscription_id, event_id)
+            // index makes replays of an already-processed event a no-op.
+            let mut tx = pool.begin().await?;
+
+            let inserted = sqlx::query(
                 r#"
-                INSERT INTO alerts (id, subscription_id, event_id, severity, message, triggered_at)
+                INSERT INTO alerts (id, subscription_id, event_id, severity, message, triggered_at, confirmations)
                 VALUES ($1, $2, (
                     SELECT id FROM indexed_events
                     WHERE tx_hash = $3 AND COALESCE(log_index, -1) = COALESCE($4, -1)
+                      AND NOT is_reorged
+                    ORDER BY id DESC
                     LIMIT 1
-                ), $5, $6, $7)
+                ), $5, $6, $7, $8)
+                ON CONFLICT (subscription_id, event_id) DO NOTHING
                 "#,
             )
             .bind(alert_id)
@@ -99,10 +124,26 @@ impl EventProcessor {
             .bind(payload.severity.to_string())
             .bind(&payload.body)
             .bind(now)
-            .execute(pool)
-            .await?;
+            .bind(confirmations as i32)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+            if inserted == 0 {
+                tracing::debug!(
+                    subscription_id = %sub.id,
+                    tx_hash = %event.tx_hash,
+                    "Alert already exists for this event — skipping"
+                );
+                continue;
+            }
 
-            // Insert notification (pending delivery)
+            // Insert notification (pending delivery, or held until confirmed)
+            let status = if confirmations > 0 {
+                DeliveryStatus::Held
+            } else {
+                DeliveryStatus::Pending
+            };
             sqlx::query(
                 r#"
                 INSERT INTO notifications (id, alert_id, channel_id, status, created_at)
@@ -112,11 +153,36 @@ impl EventProcessor {
             .bind(notification_id)
             .bind(alert_id)
             .bind(sub.channel_id)
-            .bind(DeliveryStatus::Pending.to_string())
+            .bind(status.to_string())
             .bind(now)
-            .execute(pool)
+            .execute(&mut *tx)
             .await?;
 
+            tx.commit().await?;
+
+            if status == DeliveryStatus::Held {
+                tracing::info!(
+                    alert_id = %alert_id,
+                    subscription_id = %sub.id,
+                    event_type = %event.event_type,
+                    block = event.block_number,
+                    confirmations,
+                    "Alert created, held until confirmed"
+                );
+                alerts_created += 1;
+                continue;
+            }
+
+            // Hand off to the notifier. The row stays `pending`, so the notifier's
+            // periodic sweep still delivers it if this push fails.
+            if let Err(e) = queue::enqueue_notification(redis, notification_id).await {
+                tracing::warn!(
+                    notification_id = %notification_id,
+                    error = %e,
+                    "Failed to enqueue notification"
+                );
+            }
+
             tracing::info!(
                 alert_id = %alert_id,
                 subscription_id = %sub.id,
@@ -202,6 +268,193 @@ impl EventProcessor {
                     Severity::Info,
                 )
             }
+            EventType::FtsoFeedPublished => {
+                let feed = event
+                    .decoded_data
+                    .get("feed_name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown");
+                let round = event
+                    .decoded_data
+                    .get("voting_round_id")
+                    .and_then(|v| v.as_u64())
+                    .map(|r| format!("#{}", r))
+                    .unwrap_or_else(|| "unknown".to_string());
+                let price = match (
+                    event.decoded_data.get("value").and_then(|v| v.as_i64()),
+                    event.decoded_data.get("decimals").and_then(|v| v.as_i64()),
+                ) {
+                    (Some(value), Some(decimals)) => {
+                        format!("{}", value as f64 / 10f64.powi(decimals as i32))
+                    }
+                    _ => "N/A".to_string(),
+                };
+
+                (
+                    "FTSO Feed Published".to_string(),
+                    format!(
+                        "Feed {} published for voting round {}: value = {}",
+                        feed, round, price
+                    ),
+                    Severity::Info,
+                )
+            }
+            EventType::FastUpdateFeeds => {
+                let epoch = event
+                    .decoded_data
+                    .get("voting_epoch_id")
+                    .and_then(|v| v.as_u64())
+                    .map(|e| format!("#{}", e))
+                    .unwrap_or_else(|| "unknown".to_string());
+                let feeds = event
+                    .decoded_data
+                    .get("values")
+                    .and_then(|v| v.as_array())
+                    .map_or(0, |v| v.len());
+
+                (
+                    "FTSO Fast Updates".to_string(),
+                    format!("{} feed values updated in voting epoch {}", feeds, epoch),
+                    Severity::Info,
+                )
+            }
+
+            // Flare Systems Protocol events
+            EventType::SigningPolicyInitialized => {
+                let data = &event.decoded_data;
+                let epoch = data
+                    .get("reward_epoch_id")
+                    .and_then(|v| v.as_u64())
+                    .map(|e| format!("#{}", e))
+                    .unwrap_or_else(|| "unknown".to_string());
+                let voters = data
+                    .get("voters")
+                    .and_then(|v| v.as_array())
+                    .map_or(0, |v| v.len());
+                let dropped: Vec<&str> = data
+                    .get("dropped_voters")
+                    .and_then(|v| v.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|v| v.as_str())
+                    .collect();
+
+                if dropped.is_empty() {
+                    (
+                        "Signing Policy Initialized".to_string(),
+                        format!(
+                            "Signing policy for reward epoch {} initialized with {} voters",
+                            epoch, voters
+                        ),
+                        Severity::Info,
+                    )
+                } else {
+                    (
+                        "Voter Dropped From Signing Policy".to_string(),
+                        format!(
+                            "Signing policy for reward epoch {} initialized with {} voters. Dropped since the previous epoch: {}",
+                            epoch,
+                            voters,
+                            dropped.join(", ")
+                        ),
+                        Severity::Critical,
+                    )
+                }
+            }
+            EventType::ProtocolMessageRelayed => {
+                let protocol = event
+                    .decoded_data
+                    .get("protocol_id")
+                    .and_then(|v| v.as_u64())
+                    .map(|p| p.to_string())
+                    .unwrap_or_else(|| "unknown".to_string());
+                let round = event
+                    .decoded_data
+                    .get("voting_round_id")
+                    .and_then(|v| v.as_u64())
+                    .map(|r| format!("#{}", r))
+                    .unwrap_or_else(|| "unknown".to_string());
+
+                (
+                    "Protocol Message Relayed".to_string(),
+                    format!(
+                        "Protocol {} result for voting round {} relayed",
+                        protocol, round
+                    ),
+                    Severity::Info,
+                )
+            }
+            EventType::VoterRegistered
+            | EventType::VoterRemoved
+            | EventType::SigningPolicySigned
+            | EventType::RewardsSigned
+            | EventType::UptimeVoteSigned => {
+                let data = &event.decoded_data;
+                let voter = data
+                    .get("voter")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown");
+                let epoch = data
+                    .get("reward_epoch_id")
+                    .and_then(|v| v.as_u64())
+                    .map(|e| format!("#{}", e))
+                    .unwrap_or_else(|| "unknown".to_string());
+                let threshold =
+                    if data.get("threshold_reached").and_then(|v| v.as_bool()) == Some(true) {
+                        " (signing threshold reached)"
+                    } else {
+                        ""
+                    };
+
+                match event.event_type {
+                    EventType::VoterRegistered => {
+                        let weight = data
+                            .get("registration_weight")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("N/A");
+                        (
+                            "Voter Registered".to_string(),
+                            format!(
+                                "Voter {} registered for reward epoch {} with weight {}",
+                                voter, epoch, weight
+                            ),
+                            Severity::Info,
+                        )
+                    }
+                    EventType::VoterRemoved => (
+                        "Voter Removed".to_string(),
+                        format!(
+                            "Voter {} was removed from the registration for reward epoch {} and will not be in its signing policy",
+                            voter, epoch
+                        ),
+                        Severity::Critical,
+                    ),
+                    EventType::SigningPolicySigned => (
+                        "Signing Policy Signed".to_string(),
+                        format!(
+                            "Voter {} signed the signing policy for reward epoch {}{}",
+                            voter, epoch, threshold
+                        ),
+                        Severity::Info,
+                    ),
+                    EventType::RewardsSigned => (
+                        "Rewards Signed".to_string(),
+                        format!(
+                            "Voter {} signed the rewards for reward epoch {}{}",
+                            voter, epoch, threshold
+                        ),
+                        Severity::Info,
+                    ),
+                    _ => (
+                        "Uptime Vote Signed".to_string(),
+                        format!(
+                            "Voter {} signed the uptime vote for reward epoch {}{}",
+                            voter, epoch, threshold
+                        ),
+                        Severity::Info,
+                    ),
+                }
+            }
 
             // FDC events
             EventType::AttestationRequested => {
@@ -279,13 +532,18 @@ impl EventProcessor {
             EventType::MintingExecuted => {
                 let amount = event
                     .decoded_data
-                    .get("amount")
+                    .get("minted_amount_uba")
                     .and_then(|v| v.as_str())
                     .unwrap_or("N/A");
+                let agent = event
+                    .decoded_data
+                    .get("agent")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown");
 
                 (
                     "FAsset Minting Executed".to_string(),
-                    format!("Minting executed: {} FAssets at {}", amount, event.address),
+                    format!("Minting executed: {} UBA by agent {}", amount, agent),
                     Severity::Info,
                 )
             }
@@ -305,6 +563,146 @@ impl EventProcessor {
                 ),
                 Severity::Critical,
             ),
+            EventType::CollateralReserved | EventType::RedemptionPerformed => {
+                let (title, action, amount_key) =
+                    if event.event_type == EventType::CollateralReserved {
+                        (
+                            "FAsset Collateral Reserved",
+                            "reserved for minting",
+                            "value_uba",
+                        )
+                    } else {
+                        (
+                            "FAsset Redemption Performed",
+                            "redeemed",
+                            "redemption_amount_uba",
+                        )
+                    };
+                let amount = event
+                    .decoded_data
+                    .get(amount_key)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("N/A");
+                let agent = event
+                    .decoded_data
+                    .get("agent")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown");
+
+                (
+                    title.to_string(),
+                    format!("{} UBA {} by agent {}", amount, action, agent),
+                    Severity::Info,
+                )
+            }
+
+            // Synthetic collateral ratio events
+            EventType::CrWarning | EventType::CrCritical | EventType::LiquidationImminent => {
+                let cr = event
+                    .decoded_data
+                    .get("cr")
+                    .and_then(|v| v.as_f64())
+                    .map(|cr| format!("{:.1}%", cr * 100.0))
+                    .unwrap_or_else(|| "N/A".to_string());
+                let fasset = event
+                    .decoded_data
+                    .get("fasset")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("FAsset");
+                match event.event_type {
+                    EventType::CrWarning => (
+                        "Collateral Ratio Warning".to_string(),
+                        format!(
+                            "{} agent {} collateral ratio fell to {}",
+                            fasset, event.address, cr
+                        ),
+                        Severity::Warning,
+                    ),
+                    EventType::CrCritical => (
+                        "Collateral Ratio Critical".to_string(),
+                        format!(
+                            "{} agent {} collateral ratio is critically low at {}. Top up collateral.",
+                            fasset, event.address, cr
+                        ),
+                        Severity::Critical,
+                    ),
+                    _ => (
+                        "⚠️ Liquidation Imminent".to_string(),
+                        format!(
+                            "CRITICAL: {} agent {} collateral ratio is {}, close to liquidation!",
+                            fasset, event.address, cr
+                        ),
+                        Severity::Critical,
+                    ),
+                }
+            }
+
+            // Synthetic feed events
+            EventType::PriceThreshold | EventType::PriceMove | EventType::FeedStale => {
+                let data = &event.decoded_data;
+                let price = data
+                    .get("price")
+                    .and_then(|v| v.as_f64())
+                    .map(|p| p.to_string())
+                    .unwrap_or_else(|| "N/A".to_string());
+                match event.event_type {
+                    EventType::PriceThreshold => {
+                        let direction = data
+                            .get("direction")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("past");
+                        let threshold = data
+                            .get("threshold")
+                            .and_then(|v| v.as_f64())
+                            .map(|t| t.to_string())
+                            .unwrap_or_else(|| "N/A".to_string());
+                        (
+                            "Price Alert".to_string(),
+                            format!(
+                                "{} is at {}, {} your threshold of {}",
+                                event.address, price, direction, threshold
+                            ),
+                            Severity::Warning,
+                        )
+                    }
+                    EventType::PriceMove => {
+                        let change = data
+                            .get("change_pct")
+                            .and_then(|v| v.as_f64())
+                            .map(|c| format!("{:+.2}%", c))
+                            .unwrap_or_else(|| "N/A".to_string());
+                        let window = data
+                            .get("window_secs")
+                            .and_then(|v| v.as_u64())
+                            .unwrap_or_default();
+                        (
+                            "Price Move".to_string(),
+                            format!(
+                                "{} moved {} in the last {} min, to {}",
+                                event.address,
+                                change,
+                                window / 60,
+                                price
+                            ),
+                            Severity::Warning,
+                        )
+                    }
+                    _ => {
+                        let stale = data
+                            .get("stale_secs")
+                            .and_then(|v| v.as_i64())
+                            .unwrap_or_default();
+                        (
+                            "Feed Stale".to_string(),
+                            format!(
+                                "{} has not updated for {} s (last price {})",
+                                event.address, stale, price
+                            ),
+                            Severity::Critical,
+                        )
+                    }
+                }
+            }
 
             // Generic
             EventType::GenericEvent => (
@@ -387,6 +785,36 @@ mod tests {
         assert_eq!(payload.severity, Severity::Warning);
     }
 
+    #[test]
+    fn test_translate_signing_policy_dropouts() {
+        let data = serde_json::json!({ "reward_epoch_id": 251, "voters": ["0xaa", "0xbb"] });
+        let payload = EventProcessor::translate_event(&make_event(
+            EventType::SigningPolicyInitialized,
+            data.clone(),
+        ));
+        assert_eq!(payload.severity, Severity::Info);
+        assert!(payload.body.contains("#251 initialized with 2 voters"));
+
+        let mut data = data;
+        data["dropped_voters"] = serde_json::json!(["0xcc"]);
+        let payload =
+            EventProcessor::translate_event(&make_event(EventType::SigningPolicyInitialized, data));
+        assert_eq!(payload.severity, Severity::Critical);
+        assert!(payload.body.contains("0xcc"));
+    }
+
+    #[test]
+    fn test_translate_voter_removed() {
+        let event = make_event(
+            EventType::VoterRemoved,
+            serde_json::json!({ "voter": "0xaa", "reward_epoch_id": 251 }),
+        );
+        let payload = EventProcessor::translate_event(&event);
+        assert_eq!(payload.title, "Voter Removed");
+        assert!(payload.body.contains("0xaa"));
+        assert_eq!(payload.severity, Severity::Critical);
+    }
+
     #[test]
     fn test_translate_liquidation_started() {
         let event = make_event(EventType::LiquidationStarted, serde_json::json!({}));
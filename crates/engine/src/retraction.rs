@@ -0,0 +1,217 @@
+//! Alert retraction after chain reorgs.
+//!
+//! The indexer records every reorg in `chain_reorgs` and flags the replaced events
+//! as `is_reorged`. Once it has re-indexed past the block where the reorg was
+//! detected, alerts still pointing at a flagged event are for events gone from the
+//! canonical chain (an event re-included in a replacement block was stored again,
+//! and its alerts moved to the new row).
+//!
+//! For each such reorg, alerts raised from flagged events are marked retracted and
+//! their undelivered notifications (including ones still `held` for confirmations)
+//! are cancelled. Channels that already received the alert can get a follow-up
+//! `retraction` notification.
+
+use redis::aio::ConnectionManager;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use flare_common::queue;
+use flare_common::types::{
+    Alert, DecodedEvent, DeliveryStatus, NotificationKind, NotificationPayload, Severity,
+};
+
+/// Summary of one retraction pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetractionStats {
+    /// Reorgs whose alerts were checked
+    pub reorgs: usize,
+    /// Alerts retracted
+    pub alerts: usize,
+    /// Undelivered notifications cancelled
+    pub cancelled: u64,
+    /// Follow-up notifications queued
+    pub notices: usize,
+}
+
+/// Retract alerts invalidated by reorgs the indexer has finished re-indexing.
+///
+/// With `notify` set, a `retraction` notification is queued for every channel
+/// that was already sent one of the retracted alerts.
+pub async fn retract_reorged_alerts(
+    pool: &PgPool,
+    redis: &mut ConnectionManager,
+    notify: bool,
+) -> anyhow::Result<RetractionStats> {
+    let mut tx = pool.begin().await?;
+
+    // Only reorgs whose replacement blocks have been indexed: until then a flagged
+    // event may still be re-included and its alerts moved to the canonical row
+    let reorgs: Vec<(i64, String, i64)> = sqlx::query_as(
+        r#"
+        SELECT r.id, r.chain, r.fork_block
+        FROM chain_reorgs r
+        JOIN indexer_state s ON s.chain = r.chain
+        WHERE r.alerts_checked_at IS NULL AND s.last_block >= r.detected_at_block
+        ORDER BY r.id
+        FOR UPDATE OF r SKIP LOCKED
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut stats = RetractionStats::default();
+    if reorgs.is_empty() {
+        return Ok(stats);
+    }
+
+    let mut alert_ids: Vec<Uuid> = Vec::new();
+    for (reorg_id, chain, fork_block) in &reorgs {
+        let retracted: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            UPDATE alerts a SET retracted_at = NOW()
+            FROM indexed_events e
+            WHERE a.event_id = e.id
+              AND e.chain = $1 AND e.block_number >= $2 AND e.is_reorged
+              AND a.retracted_at IS NULL
+            RETURNING a.id
+            "#,
+        )
+        .bind(chain)
+        .bind(fork_block)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE chain_reorgs SET alerts_checked_at = NOW() WHERE id = $1")
+            .bind(reorg_id)
+            .execute(&mut *tx)
+            .await?;
+
+        alert_ids.extend(retracted.into_iter().map(|(id,)| id));
+    }
+    stats.reorgs = reorgs.len();
+    stats.alerts = alert_ids.len();
+
+    // Dead letters are cancelled too so a re-drive can't deliver a retracted alert
+    stats.cancelled = sqlx::query(
+        r#"
+        UPDATE notifications
+        SET status = $1, next_attempt_at = NULL,
+            error_detail = 'Alert retracted: triggering event was reorged out'
+        WHERE alert_id = ANY($2) AND kind = $3
+          AND status IN ('pending', 'failed', 'dead_letter', 'held')
+        "#,
+    )
+    .bind(DeliveryStatus::Cancelled.to_string())
+    .bind(&alert_ids)
+    .bind(NotificationKind::Alert.to_string())
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+
+    let mut notices: Vec<(Uuid,)> = Vec::new();
+    if notify {
+        notices = sqlx::query_as(
+            r#"
+            INSERT INTO notifications (id, alert_id, channel_id, status, kind)
+            SELECT uuid_generate_v4(), alert_id, channel_id, $1, $2
+            FROM (
+                SELECT DISTINCT alert_id, channel_id FROM notifications
+                WHERE alert_id = ANY($3) AND kind = $4
+                  AND (status = 'sent'
+                       -- Cancelled mid-send by a delivery worker: it may have gone out
+                       OR (status = 'cancelled' AND lease_expires_at > NOW()))
+            ) delivered
+            RETURNING id
+            "#,
+        )
+        .bind(DeliveryStatus::Pending.to_string())
+        .bind(NotificationKind::Retraction.to_string())
+        .bind(&alert_ids)
+        .bind(NotificationKind::Alert.to_string())
+        .fetch_all(&mut *tx)
+        .await?;
+    }
+    stats.notices = notices.len();
+
+    tx.commit().await?;
+
+    // As with alerts, the notifier's stale sweep picks up any notice whose push fails
+    for (id,) in &notices {
+        if let Err(e) = queue::enqueue_notification(redis, *id).await {
+            tracing::warn!(
+                notification_id = %id,
+                error = %e,
+                "Failed to enqueue retraction notice — will be picked up by the pending sweep"
+            );
+        }
+    }
+
+    if stats.alerts > 0 {
+        tracing::info!(
+            reorgs = stats.reorgs,
+            alerts = stats.alerts,
+            cancelled = stats.cancelled,
+            notices = stats.notices,
+            "Retracted alerts for reorged events"
+        );
+    }
+
+    Ok(stats)
+}
+
+/// Render the follow-up sent to a channel that received a now-retracted alert.
+pub fn retraction_payload(alert: &Alert, event: &DecodedEvent) -> NotificationPayload {
+    NotificationPayload {
+        title: "Alert retracted".to_string(),
+        body: format!(
+            "The previous alert was invalidated by a chain reorg: transaction {} is no longer \
+             in block {} on {}.\n\nRetracted alert: {}",
+            event.tx_hash, event.block_number, event.chain, alert.message
+        ),
+        severity: Severity::Info,
+        metadata: serde_json::json!({
+            "retracted_alert_id": alert.id,
+            "tx_hash": event.tx_hash,
+            "block_number": event.block_number,
+            "chain": event.chain.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+    use flare_common::types::{Chain, EventType};
+
+    #[test]
+    fn test_retraction_payload_references_original_alert() {
+        let event = DecodedEvent {
+            tx_hash: "0xabc".to_string(),
+            log_index: Some(0),
+            block_number: 42,
+            block_timestamp: Utc::now(),
+            chain: Chain::Flare,
+            address: "0x1234".to_string(),
+            event_type: EventType::LiquidationStarted,
+            decoded_data: serde_json::json!({}),
+        };
+        let alert = Alert {
+            id: Uuid::new_v4(),
+            subscription_id: Uuid::new_v4(),
+            event_id: 7,
+            severity: Severity::Critical,
+            message: "Agent 0xagent is being liquidated".to_string(),
+            triggered_at: Utc::now(),
+            retracted_at: Some(Utc::now()),
+        };
+
+        let payload = retraction_payload(&alert, &event);
+        assert_eq!(payload.severity, Severity::Info);
+        assert!(payload.body.contains("invalidated by a chain reorg"));
+        assert!(payload.body.contains("block 42"));
+        assert!(payload.body.contains(&alert.message));
+        assert_eq!(payload.metadata["retracted_alert_id"], alert.id.to_string());
+    }
+}
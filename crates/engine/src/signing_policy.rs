@@ -0,0 +1,173 @@
+//! Signing policy dropouts.
+//!
+//! A voter falls out of the FTSO v2 signing policy when it isn't in the next
+//! reward epoch's policy, whether it didn't register or `VoterRegistry` removed it
+//! for a heavier voter. `SigningPolicyInitialized` only lists the signing-policy
+//! addresses that made it, and a voter may register a new one each epoch. The
+//! processor maps both epochs' policies to voters through their `VoterRegistered`
+//! events and adds the previous epoch's missing voters as
+//! `decoded_data.dropped_voters`, with their signing-policy addresses as
+//! `dropped_signing_policy_addresses`. The matcher then also routes the event to
+//! subscriptions on those voters (see `AlertMatcher::subject_addresses`).
+
+use std::collections::{HashMap, HashSet};
+
+use sqlx::PgPool;
+
+use flare_common::types::{Chain, DecodedEvent, EventType};
+
+/// For a `SigningPolicyInitialized` event, a copy with `dropped_voters` set to the
+/// voters of the previous reward epoch's policy that aren't in this one.
+///
+/// `None` for other events, and when the previous policy or either epoch's voter
+/// registrations weren't indexed.
+pub async fn with_policy_dropouts(
+    pool: &PgPool,
+    event: &DecodedEvent,
+) -> anyhow::Result<Option<DecodedEvent>> {
+    let data = &event.decoded_data;
+    if event.event_type != EventType::SigningPolicyInitialized
+        || data.get("dropped_voters").is_some()
+    {
+        return Ok(None);
+    }
+    let Some((epoch, previous_epoch)) = data
+        .get("reward_epoch_id")
+        .and_then(|v| v.as_i64())
+        .and_then(|epoch| Some((epoch, epoch.checked_sub(1)?)))
+    else {
+        return Ok(None);
+    };
+
+    let previous: Option<(serde_json::Value,)> = sqlx::query_as(
+        r#"
+        SELECT COALESCE(decoded_data->'voters', '[]'::jsonb) FROM indexed_events
+        WHERE chain = $1 AND address = $2 AND event_type = $3 AND NOT is_reorged
+          AND (decoded_data->>'reward_epoch_id')::bigint = $4
+        ORDER BY id DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(event.chain.to_string())
+    .bind(&event.address)
+    .bind(EventType::SigningPolicyInitialized.to_string())
+    .bind(previous_epoch)
+    .fetch_optional(pool)
+    .await?;
+    let Some((previous,)) = previous else {
+        return Ok(None);
+    };
+
+    let previous_voters = registered_voters(pool, event.chain, previous_epoch).await?;
+    let current_voters = registered_voters(pool, event.chain, epoch).await?;
+    if previous_voters.is_empty() || current_voters.is_empty() {
+        return Ok(None);
+    }
+
+    let current = data.get("voters").unwrap_or(&serde_json::Value::Null);
+    let (voters, signing_policy_addresses): (Vec<String>, Vec<String>) =
+        dropped_voters(&previous, &previous_voters, current, &current_voters)
+            .into_iter()
+            .unzip();
+    let mut enriched = event.clone();
+    enriched.decoded_data["dropped_voters"] = serde_json::json!(voters);
+    enriched.decoded_data["dropped_signing_policy_addresses"] =
+        serde_json::json!(signing_policy_addresses);
+    Ok(Some(enriched))
+}
+
+/// Voters registered for `epoch` on `chain`, by lowercase signing-policy address.
+async fn registered_voters(
+    pool: &PgPool,
+    chain: Chain,
+    epoch: i64,
+) -> anyhow::Result<HashMap<String, String>> {
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT LOWER(decoded_data->>'signing_policy_address'), LOWER(decoded_data->>'voter')
+        FROM indexed_events
+        WHERE chain = $1 AND event_type = $2 AND NOT is_reorged
+          AND (decoded_data->>'reward_epoch_id')::bigint = $3
+          AND decoded_data ? 'signing_policy_address' AND decoded_data ? 'voter'
+        ORDER BY id
+        "#,
+    )
+    .bind(chain.to_string())
+    .bind(EventType::VoterRegistered.to_string())
+    .bind(epoch)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// Voters of the `previous` policy missing from the `current` one, with their
+/// signing-policy addresses in the previous policy.
+///
+/// Policies list signing-policy addresses; each is compared by the voter it was
+/// registered for in its epoch, or by itself if it wasn't.
+fn dropped_voters(
+    previous: &serde_json::Value,
+    previous_voters: &HashMap<String, String>,
+    current: &serde_json::Value,
+    current_voters: &HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let policy = |value: &serde_json::Value, registered: &HashMap<String, String>| {
+        value
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str())
+            .map(str::to_lowercase)
+            .map(|address| {
+                let voter = registered.get(&address).cloned().unwrap_or(address.clone());
+                (voter, address)
+            })
+            .collect::<Vec<_>>()
+    };
+    let current: HashSet<String> = policy(current, current_voters)
+        .into_iter()
+        .map(|(voter, _)| voter)
+        .collect();
+    policy(previous, previous_voters)
+        .into_iter()
+        .filter(|(voter, _)| !current.contains(voter))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_dropped_voters() {
+        let registered = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(address, voter)| (address.to_string(), voter.to_string()))
+                .collect()
+        };
+        // Voter 0xb2 registered a new signing-policy address; 0xa1 dropped out
+        let previous = json!(["0xaa", "0xBB", "0xcc"]);
+        let previous_voters = registered(&[("0xaa", "0xa1"), ("0xbb", "0xb2"), ("0xcc", "0xc3")]);
+        let current = json!(["0xcc", "0xee", "0xdd"]);
+        let current_voters = registered(&[("0xcc", "0xc3"), ("0xee", "0xb2"), ("0xdd", "0xd4")]);
+        assert_eq!(
+            dropped_voters(&previous, &previous_voters, &current, &current_voters),
+            vec![("0xa1".to_string(), "0xaa".to_string())]
+        );
+        assert!(dropped_voters(&json!([]), &previous_voters, &current, &current_voters).is_empty());
+
+        // Unregistered addresses stand for themselves
+        assert_eq!(
+            dropped_voters(
+                &json!(["0xff"]),
+                &previous_voters,
+                &current,
+                &current_voters
+            ),
+            vec![("0xff".to_string(), "0xff".to_string())]
+        );
+    }
+}
@@ -2,12 +2,19 @@
 //!
 //! Subscriptions link a user to a monitored address + event type + notification channel,
 //! with optional threshold configuration for conditional alerting.
+//!
+//! Feed-scoped subscriptions (`create_for_feed`) monitor an FTSO feed by name instead
+//! of a contract; the feed is tracked as a `monitored_addresses` row of type `ftso_feed`.
 
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use flare_common::error::AppError;
-use flare_common::types::Subscription;
+use flare_common::signing;
+use flare_common::types::{AddressType, Chain, EventType, Subscription, ThresholdConfig};
+
+use crate::analyzer::AddressAnalyzer;
+use crate::feed_monitor::{FEED_EVENT_TYPES, parse_feed};
 
 /// Service layer for subscription CRUD operations.
 pub struct SubscriptionService;
@@ -21,6 +28,19 @@ pub struct CreateSubscriptionParams {
     pub threshold_config: Option<serde_json::Value>,
 }
 
+/// Parameters for creating a feed-scoped subscription.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct CreateFeedSubscriptionParams {
+    /// Feed name, e.g. `FLR/USD`
+    pub feed: String,
+    /// Chain of the feed (default: flare)
+    pub chain: Option<String>,
+    pub channel_id: Uuid,
+    /// `price_threshold`, `price_move` or `feed_stale`
+    pub event_type: String,
+    pub threshold_config: Option<serde_json::Value>,
+}
+
 /// Parameters for updating an existing subscription.
 #[derive(Debug, Clone, serde::Deserialize)]
 pub struct UpdateSubscriptionParams {
@@ -35,6 +55,15 @@ impl SubscriptionService {
         "price_epoch_finalized",
         "vote_power_changed",
         "reward_epoch_started",
+        "ftso_feed_published",
+        "fast_update_feeds",
+        "signing_policy_initialized",
+        "protocol_message_relayed",
+        "voter_registered",
+        "voter_removed",
+        "signing_policy_signed",
+        "rewards_signed",
+        "uptime_vote_signed",
         "attestation_requested",
         "attestation_proved",
         "round_finalized",
@@ -43,7 +72,15 @@ impl SubscriptionService {
         "minting_executed",
         "redemption_requested",
         "liquidation_started",
+        "collateral_reserved",
+        "redemption_performed",
+        "cr_warning",
+        "cr_critical",
+        "liquidation_imminent",
         "generic_event",
+        "price_threshold",
+        "price_move",
+        "feed_stale",
     ];
 
     /// Create a new subscription for a user.
@@ -93,6 +130,94 @@ impl SubscriptionService {
         Ok(sub)
     }
 
+    /// Create a subscription to an FTSO feed, tracking the feed if it isn't yet.
+    ///
+    /// `price_threshold` requires `min_value` or `max_value`, and `price_move`
+    /// requires `deviation_pct`.
+    pub async fn create_for_feed(
+        pool: &PgPool,
+        user_id: Uuid,
+        params: &CreateFeedSubscriptionParams,
+    ) -> Result<Subscription, AppError> {
+        let (feed, _) = parse_feed(&params.feed).ok_or_else(|| {
+            AppError::Validation(format!(
+                "Invalid feed '{}': expected a name like FLR/USD of at most 20 bytes",
+                params.feed
+            ))
+        })?;
+        let chain: Chain = match params.chain.as_deref() {
+            Some(chain) => chain.parse().map_err(AppError::Validation)?,
+            None => Chain::Flare,
+        };
+
+        let Some(event_type) = FEED_EVENT_TYPES
+            .into_iter()
+            .find(|t| t.to_string() == params.event_type)
+        else {
+            return Err(AppError::Validation(format!(
+                "Invalid feed event_type '{}'. Valid types: price_threshold, price_move, feed_stale",
+                params.event_type
+            )));
+        };
+
+        let config: ThresholdConfig = match &params.threshold_config {
+            Some(config) => serde_json::from_value(config.clone())
+                .map_err(|e| AppError::Validation(format!("Invalid threshold_config: {}", e)))?,
+            None => ThresholdConfig::default(),
+        };
+        match event_type {
+            EventType::PriceThreshold
+                if config.min_value.is_none() && config.max_value.is_none() =>
+            {
+                return Err(AppError::Validation(
+                    "price_threshold requires min_value or max_value".to_string(),
+                ));
+            }
+            EventType::PriceMove if !config.deviation_pct.is_some_and(|pct| pct > 0.0) => {
+                return Err(AppError::Validation(
+                    "price_move requires a positive deviation_pct".to_string(),
+                ));
+            }
+            EventType::FeedStale if config.stale_epochs == Some(0) => {
+                return Err(AppError::Validation(
+                    "stale_epochs must be at least 1".to_string(),
+                ));
+            }
+            _ => {}
+        }
+
+        let detected_events: Vec<String> = AddressAnalyzer::events_for_type(&AddressType::FtsoFeed)
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        let (address_id,): (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO monitored_addresses (address, chain, address_type, detected_events)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (address, chain) DO UPDATE SET address = EXCLUDED.address
+            RETURNING id
+            "#,
+        )
+        .bind(&feed)
+        .bind(chain.to_string())
+        .bind(AddressType::FtsoFeed.to_string())
+        .bind(serde_json::json!(detected_events))
+        .fetch_one(pool)
+        .await?;
+
+        Self::create(
+            pool,
+            user_id,
+            &CreateSubscriptionParams {
+                address_id,
+                channel_id: params.channel_id,
+                event_type: params.event_type.clone(),
+                threshold_config: params.threshold_config.clone(),
+            },
+        )
+        .await
+    }
+
     /// List all subscriptions for a user.
     pub async fn list_by_user(pool: &PgPool, user_id: Uuid) -> Result<Vec<Subscription>, AppError> {
         let subs: Vec<Subscription> = sqlx::query_as(
@@ -208,4 +333,89 @@ impl SubscriptionService {
 
         Ok(subs)
     }
+
+    /// Build a signed, non-expiring token that deactivates one subscription.
+    ///
+    /// Format: `<subscription_id>.<hex hmac>`. Embedded in the unsubscribe link of
+    /// every email so recipients can opt out without logging in.
+    pub fn unsubscribe_token(subscription_id: Uuid, key: &[u8]) -> String {
+        let signature = signing::sign(key, Self::unsubscribe_message(subscription_id).as_bytes());
+        format!("{}.{}", subscription_id, signature)
+    }
+
+    /// Check an unsubscribe token and return the subscription it was issued for.
+    pub fn verify_unsubscribe_token(token: &str, key: &[u8]) -> Option<Uuid> {
+        let (id, signature) = token.split_once('.')?;
+        let subscription_id = Uuid::parse_str(id).ok()?;
+        signing::verify(
+            key,
+            Self::unsubscribe_message(subscription_id).as_bytes(),
+            signature,
+        )
+        .then_some(subscription_id)
+    }
+
+    /// Deactivate the subscription named by a signed unsubscribe token.
+    pub async fn unsubscribe(
+        pool: &PgPool,
+        token: &str,
+        key: &[u8],
+    ) -> Result<Subscription, AppError> {
+        let subscription_id = Self::verify_unsubscribe_token(token, key)
+            .ok_or_else(|| AppError::Auth("Invalid unsubscribe link".to_string()))?;
+
+        let sub: Subscription =
+            sqlx::query_as("UPDATE subscriptions SET active = false WHERE id = $1 RETURNING *")
+                .bind(subscription_id)
+                .fetch_optional(pool)
+                .await?
+                .ok_or_else(|| {
+                    AppError::NotFound(format!("Subscription {} not found", subscription_id))
+                })?;
+
+        tracing::info!(subscription_id = %subscription_id, "Subscription unsubscribed via link");
+
+        Ok(sub)
+    }
+
+    /// Domain-separated message signed for unsubscribe tokens.
+    fn unsubscribe_message(subscription_id: Uuid) -> String {
+        format!("unsubscribe:{}", subscription_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsubscribe_token_round_trip() {
+        let id = Uuid::new_v4();
+        let token = SubscriptionService::unsubscribe_token(id, b"secret");
+        assert_eq!(
+            SubscriptionService::verify_unsubscribe_token(&token, b"secret"),
+            Some(id)
+        );
+    }
+
+    #[test]
+    fn test_unsubscribe_token_rejects_tampering() {
+        let id = Uuid::new_v4();
+        let token = SubscriptionService::unsubscribe_token(id, b"secret");
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!("{}.{}", Uuid::new_v4(), signature);
+
+        assert_eq!(
+            SubscriptionService::verify_unsubscribe_token(&token, b"other"),
+            None
+        );
+        assert_eq!(
+            SubscriptionService::verify_unsubscribe_token(&forged, b"secret"),
+            None
+        );
+        assert_eq!(
+            SubscriptionService::verify_unsubscribe_token("garbage", b"secret"),
+            None
+        );
+    }
 }
@@ -0,0 +1,117 @@
+//! Synthetic events derived by the engine.
+//!
+//! The CR calculator and the feed monitor raise events that have no log of their
+//! own. They are written to `indexed_events` like indexed logs, so the worker runs
+//! them through the alert pipeline and alerts can reference them. Each keeps the
+//! transaction hash and block of the event that triggered it, with a negative log
+//! index derived from the trigger so a replayed trigger doesn't write it twice.
+
+use alloy::primitives::keccak256;
+use sqlx::PgPool;
+
+use flare_common::types::{DecodedEvent, EventType};
+
+/// Deterministic log index of a synthetic event raised by `trigger` for `key`
+/// (e.g. an agent address). The top bit is set, so it is stored as a negative
+/// `BIGINT` that can't collide with a real log.
+pub fn synthetic_log_index(trigger: &DecodedEvent, key: &str, event_type: &EventType) -> u64 {
+    let key = format!(
+        "{}:{}:{}:{}",
+        trigger.tx_hash,
+        trigger.log_index.unwrap_or_default(),
+        key,
+        event_type
+    );
+    let hash = keccak256(key.as_bytes());
+    let bits = u64::from_be_bytes(hash[..8].try_into().unwrap_or_default());
+    bits | (1 << 63)
+}
+
+/// Whether an event was raised by the engine rather than indexed from a log.
+pub fn is_synthetic(event: &DecodedEvent) -> bool {
+    event.log_index.is_some_and(|index| index & (1 << 63) != 0)
+}
+
+/// Write synthetic events to `indexed_events`. Returns the number written;
+/// events already written by an earlier run of the same trigger are skipped.
+pub async fn insert_synthetic_events(
+    pool: &PgPool,
+    events: &[DecodedEvent],
+) -> anyhow::Result<u64> {
+    if events.is_empty() {
+        return Ok(0);
+    }
+
+    let tx_hashes: Vec<&str> = events.iter().map(|e| e.tx_hash.as_str()).collect();
+    let log_indexes: Vec<i64> = events
+        .iter()
+        .map(|e| e.log_index.unwrap_or_default() as i64)
+        .collect();
+    let block_numbers: Vec<i64> = events.iter().map(|e| e.block_number as i64).collect();
+    let block_timestamps: Vec<_> = events.iter().map(|e| e.block_timestamp).collect();
+    let chains: Vec<String> = events.iter().map(|e| e.chain.to_string()).collect();
+    let addresses: Vec<&str> = events.iter().map(|e| e.address.as_str()).collect();
+    let event_types: Vec<String> = events.iter().map(|e| e.event_type.to_string()).collect();
+    let data: Vec<&serde_json::Value> = events.iter().map(|e| &e.decoded_data).collect();
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO indexed_events (tx_hash, log_index, block_number, block_timestamp, chain, address, event_type, decoded_data)
+        SELECT * FROM UNNEST($1::text[], $2::int8[], $3::int8[], $4::timestamptz[], $5::text[], $6::text[], $7::text[], $8::jsonb[])
+        ON CONFLICT (tx_hash, log_index) WHERE NOT is_reorged DO NOTHING
+        "#,
+    )
+    .bind(tx_hashes)
+    .bind(log_indexes)
+    .bind(block_numbers)
+    .bind(block_timestamps)
+    .bind(chains)
+    .bind(addresses)
+    .bind(event_types)
+    .bind(data)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use flare_common::types::Chain;
+
+    #[test]
+    fn test_synthetic_log_index_is_negative_and_deterministic() {
+        let trigger = DecodedEvent {
+            tx_hash: "0xabc".to_string(),
+            log_index: Some(3),
+            block_number: 100,
+            block_timestamp: Utc::now(),
+            chain: Chain::Flare,
+            address: "0xfastupdater".to_string(),
+            event_type: EventType::FastUpdateFeeds,
+            decoded_data: serde_json::json!({}),
+        };
+        let a = synthetic_log_index(&trigger, "0xa", &EventType::CrWarning);
+        assert_eq!(
+            a,
+            synthetic_log_index(&trigger, "0xa", &EventType::CrWarning)
+        );
+        assert_ne!(
+            a,
+            synthetic_log_index(&trigger, "0xa", &EventType::CrCritical)
+        );
+        assert_ne!(
+            a,
+            synthetic_log_index(&trigger, "0xb", &EventType::CrWarning)
+        );
+        assert!((a as i64) < 0);
+
+        assert!(!is_synthetic(&trigger));
+        assert!(is_synthetic(&DecodedEvent {
+            log_index: Some(a),
+            ..trigger
+        }));
+    }
+}
@@ -0,0 +1,240 @@
+//! Event worker — feeds persisted events into the alert pipeline.
+//!
+//! The indexer writes decoded events to `indexed_events`; this worker tails that
+//! table in `id` order and runs every non-reorged event through `EventProcessor`.
+//! Events written by an indexer backfill are history, not news, and are skipped.
+//!
+//! Writers to `indexed_events` are serialized by a transaction-level lock (see
+//! migration 017), so ids become visible in order: a row never commits behind
+//! the cursor, even with one poller per chain and this worker's synthetic events
+//! writing concurrently.
+//!
+//! Progress is stored in `engine_state` as the last processed event ID, advanced
+//! after each event. Combined with the unique `(subscription_id, event_id)` alert
+//! index, a crash mid-batch replays at most one event without duplicating alerts.
+//!
+//! Each poll also retracts alerts whose events were reorged out (see `retraction`)
+//! and releases held alerts that reached their confirmation depth (see `finality`).
+//!
+//! Every processed event also feeds the `CrCalculator`; the synthetic CR events it
+//! raises are written to `indexed_events` and processed in a later batch, and its
+//! vault health snapshots to `agent_vault_snapshots`. The `FeedMonitor` likewise
+//! raises synthetic events for feed-scoped subscriptions.
+
+use std::time::Duration;
+
+use redis::aio::ConnectionManager;
+use sqlx::PgPool;
+
+use flare_common::types::{DecodedEvent, IndexedEvent};
+
+use crate::cr_calculator::{CrCalculator, insert_snapshots};
+use crate::feed_monitor::FeedMonitor;
+use crate::finality::release_confirmed;
+use crate::processor::EventProcessor;
+use crate::retraction::retract_reorged_alerts;
+use crate::synthetic::insert_synthetic_events;
+
+/// Default cursor name for the alerting worker.
+pub const DEFAULT_WORKER_NAME: &str = "event_processor";
+
+/// Long-running worker that tails `indexed_events` and raises alerts.
+pub struct EventWorker {
+    name: String,
+    pool: PgPool,
+    redis: ConnectionManager,
+    processor: EventProcessor,
+    cr_calculator: CrCalculator,
+    feed_monitor: FeedMonitor,
+    poll_interval: Duration,
+    batch_size: u32,
+    /// Queue follow-up notifications for delivered alerts that get retracted
+    retraction_notices: bool,
+}
+
+impl EventWorker {
+    pub fn new(
+        pool: PgPool,
+        redis: ConnectionManager,
+        poll_interval_ms: u64,
+        batch_size: u32,
+    ) -> Self {
+        Self {
+            name: DEFAULT_WORKER_NAME.to_string(),
+            pool,
+            redis,
+            processor: EventProcessor::new(),
+            cr_calculator: CrCalculator::new(),
+            feed_monitor: FeedMonitor::new(),
+            poll_interval: Duration::from_millis(poll_interval_ms),
+            batch_size,
+            retraction_notices: true,
+        }
+    }
+
+    /// Enable or disable follow-up notifications for retracted alerts (default: enabled).
+    pub fn with_retraction_notices(mut self, enabled: bool) -> Self {
+        self.retraction_notices = enabled;
+        self
+    }
+
+    /// Replace the collateral ratio calculator (e.g., to override FAsset settings).
+    pub fn with_cr_calculator(mut self, cr_calculator: CrCalculator) -> Self {
+        self.cr_calculator = cr_calculator;
+        self
+    }
+
+    /// Override the cursor name (e.g., to run an isolated worker in tests).
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Start the processing loop. Runs indefinitely until the task is cancelled.
+    pub async fn run(&mut self) -> anyhow::Result<()> {
+        // On first start, begin at the current tip instead of alerting on history
+        if self.get_cursor().await?.is_none() {
+            let (tip,): (i64,) = sqlx::query_as("SELECT COALESCE(MAX(id), 0) FROM indexed_events")
+                .fetch_one(&self.pool)
+                .await?;
+            self.update_cursor(tip).await?;
+            tracing::info!(
+                worker = %self.name,
+                start_event_id = tip,
+                "No previous cursor found, starting from latest indexed event"
+            );
+        }
+
+        tracing::info!(
+            worker = %self.name,
+            poll_interval_ms = self.poll_interval.as_millis() as u64,
+            batch_size = self.batch_size,
+            "Event worker started"
+        );
+
+        loop {
+            let retracted =
+                retract_reorged_alerts(&self.pool, &mut self.redis, self.retraction_notices)
+                    .await?;
+            // Vault state and feed prices may include reorged events: seed and
+            // load them again from the canonical chain
+            if retracted.reorgs > 0 {
+                self.cr_calculator.reset();
+                self.feed_monitor.reset();
+            }
+            release_confirmed(&self.pool, &mut self.redis).await?;
+            let processed = self.process_batch().await?;
+
+            // Only sleep when caught up; keep draining while there is backlog
+            if processed < self.batch_size as usize {
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        }
+    }
+
+    /// Process the next batch of events after the stored cursor.
+    ///
+    /// Returns the number of events read from `indexed_events`.
+    pub async fn process_batch(&mut self) -> anyhow::Result<usize> {
+        let cursor = self.get_cursor().await?.unwrap_or(0);
+
+        let rows: Vec<IndexedEvent> = sqlx::query_as(
+            r#"
+            SELECT id, tx_hash, log_index, block_number, block_timestamp, chain, address,
+                   event_type, decoded_data, is_reorged
+            FROM indexed_events
+            WHERE id > $1 AND NOT backfilled
+            ORDER BY id
+            LIMIT $2
+            "#,
+        )
+        .bind(cursor)
+        .bind(self.batch_size as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let count = rows.len();
+        if count > 0 {
+            self.cr_calculator
+                .refresh_agents(&self.pool, cursor)
+                .await?;
+            self.feed_monitor.refresh(&self.pool).await?;
+        }
+
+        for row in rows {
+            let event_id = row.id;
+
+            if !row.is_reorged {
+                let event = DecodedEvent::from(row);
+                let alerts = self
+                    .processor
+                    .process_event(&event, &self.pool, &mut self.redis)
+                    .await?;
+
+                if alerts > 0 {
+                    tracing::info!(
+                        event_id,
+                        event_type = %event.event_type,
+                        alerts,
+                        "Event produced alerts"
+                    );
+                }
+
+                let update = self.cr_calculator.observe(&self.pool, &event).await?;
+                insert_snapshots(&self.pool, &update.snapshots).await?;
+                let written = insert_synthetic_events(&self.pool, &update.events).await?;
+                if written > 0 {
+                    tracing::info!(
+                        event_id,
+                        event_type = %event.event_type,
+                        written,
+                        "Event raised collateral ratio events"
+                    );
+                }
+
+                let feed_events = self.feed_monitor.observe(&self.pool, &event).await?;
+                let written = insert_synthetic_events(&self.pool, &feed_events).await?;
+                if written > 0 {
+                    tracing::info!(
+                        event_id,
+                        event_type = %event.event_type,
+                        written,
+                        "Event raised feed events"
+                    );
+                }
+            }
+
+            self.update_cursor(event_id).await?;
+        }
+
+        Ok(count)
+    }
+
+    /// Get the last processed event ID for this worker.
+    pub async fn get_cursor(&self) -> anyhow::Result<Option<i64>> {
+        let row: Option<(i64,)> =
+            sqlx::query_as("SELECT last_event_id FROM engine_state WHERE worker = $1")
+                .bind(&self.name)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(row.map(|(id,)| id))
+    }
+
+    /// Record the last processed event ID for this worker.
+    pub async fn update_cursor(&self, event_id: i64) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO engine_state (worker, last_event_id)
+            VALUES ($1, $2)
+            ON CONFLICT (worker) DO UPDATE SET last_event_id = $2, updated_at = NOW()
+            "#,
+        )
+        .bind(&self.name)
+        .bind(event_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
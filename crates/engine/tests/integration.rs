@@ -12,12 +12,18 @@ use chrono::Utc;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use flare_common::types::{Chain, DecodedEvent, EventType};
+use flare_common::types::{Chain, DecodedEvent, EventType, IndexedEvent};
+use flare_engine::agent_info::AgentInfoReader;
 use flare_engine::analyzer::AddressAnalyzer;
+use flare_engine::cr_calculator::{CrCalculator, insert_snapshots};
+use flare_engine::finality::release_confirmed;
 use flare_engine::matcher::AlertMatcher;
+use flare_engine::retraction::{RetractionStats, retract_reorged_alerts};
 use flare_engine::subscription::{
-    CreateSubscriptionParams, SubscriptionService, UpdateSubscriptionParams,
+    CreateFeedSubscriptionParams, CreateSubscriptionParams, SubscriptionService,
+    UpdateSubscriptionParams,
 };
+use flare_engine::worker::EventWorker;
 
 // ============================================================
 // Shared helpers
@@ -537,7 +543,7 @@ async fn test_analyzer_classify_cached_address(pool: PgPool) {
         flare_common::types::AddressType::FtsoProvider
     );
     assert_eq!(result.label, "FTSO Data Provider");
-    assert_eq!(result.subscribable_events.len(), 3);
+    assert_eq!(result.subscribable_events.len(), 9);
 }
 
 #[sqlx::test]
@@ -563,3 +569,1126 @@ async fn test_analyzer_idempotent_insert(pool: PgPool) {
         "Should only have one row after duplicate classify"
     );
 }
+
+// ============================================================
+// EventWorker: cursor tracking + idempotent replay
+// ============================================================
+
+/// Insert an indexed event row and return its ID.
+async fn insert_indexed_event(
+    executor: impl sqlx::PgExecutor<'_>,
+    address: &str,
+    tx_hash: &str,
+) -> i64 {
+    let (id,): (i64,) = sqlx::query_as(
+        r#"
+        INSERT INTO indexed_events (tx_hash, log_index, block_number, block_timestamp, chain, address, event_type, decoded_data)
+        VALUES ($1, 0, 1000, NOW(), 'flare', $2, 'liquidation_started', '{}'::jsonb)
+        RETURNING id
+        "#,
+    )
+    .bind(tx_hash)
+    .bind(address)
+    .fetch_one(executor)
+    .await
+    .unwrap();
+    id
+}
+
+#[sqlx::test]
+#[ignore] // Also requires Redis on REDIS_URL (default redis://localhost:6379)
+async fn test_worker_processes_events_once(pool: PgPool) {
+    setup(&pool).await;
+    sqlx::query("DELETE FROM engine_state")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let user_id = create_test_user(&pool).await;
+    let addr_id = create_monitored_address(&pool, "0xworker_addr", "flare").await;
+    let chan_id = create_notification_channel(&pool, user_id).await;
+
+    SubscriptionService::create(
+        &pool,
+        user_id,
+        &CreateSubscriptionParams {
+            address_id: addr_id,
+            channel_id: chan_id,
+            event_type: "liquidation_started".to_string(),
+            threshold_config: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+    let redis = flare_common::redis_pool::create_redis_pool(&redis_url)
+        .await
+        .unwrap();
+
+    let event_id =
+        insert_indexed_event(&pool, "0xworker_addr", &format!("0x{}", Uuid::new_v4())).await;
+
+    let mut worker = EventWorker::new(pool.clone(), redis, 100, 50)
+        .with_name(format!("test_{}", Uuid::new_v4()));
+    worker.update_cursor(event_id - 1).await.unwrap();
+
+    let processed = worker.process_batch().await.unwrap();
+    assert_eq!(processed, 1);
+    assert_eq!(worker.get_cursor().await.unwrap(), Some(event_id));
+
+    // Rewind the cursor to simulate a crash before the cursor was advanced
+    worker.update_cursor(event_id - 1).await.unwrap();
+    worker.process_batch().await.unwrap();
+
+    let alerts: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM alerts WHERE event_id = $1")
+        .bind(event_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(alerts.0, 1, "Replayed event must not create a second alert");
+
+    let notifications: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM notifications")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(notifications.0, 1);
+
+    // Backfilled history never raises alerts
+    let backfilled_id =
+        insert_indexed_event(&pool, "0xworker_addr", &format!("0x{}", Uuid::new_v4())).await;
+    sqlx::query("UPDATE indexed_events SET backfilled = true WHERE id = $1")
+        .bind(backfilled_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(worker.process_batch().await.unwrap(), 0);
+
+    let alerts: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM alerts WHERE event_id = $1")
+        .bind(backfilled_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(alerts.0, 0);
+}
+
+#[sqlx::test]
+#[ignore] // Also requires Redis on REDIS_URL (default redis://localhost:6379)
+async fn test_worker_does_not_skip_events_of_interleaved_writers(pool: PgPool) {
+    setup(&pool).await;
+    let user_id = create_test_user(&pool).await;
+    let chan_id = create_notification_channel(&pool, user_id).await;
+    for address in ["0xwriter_a", "0xwriter_b"] {
+        let addr_id = create_monitored_address(&pool, address, "flare").await;
+        SubscriptionService::create(
+            &pool,
+            user_id,
+            &CreateSubscriptionParams {
+                address_id: addr_id,
+                channel_id: chan_id,
+                event_type: "liquidation_started".to_string(),
+                threshold_config: None,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+    let redis = flare_common::redis_pool::create_redis_pool(&redis_url)
+        .await
+        .unwrap();
+    let mut worker = EventWorker::new(pool.clone(), redis, 100, 50)
+        .with_name(format!("test_{}", Uuid::new_v4()));
+    worker.update_cursor(0).await.unwrap();
+
+    // Writer A inserts first and stays open while writer B inserts and commits
+    let mut writer_a = pool.begin().await.unwrap();
+    let id_a = insert_indexed_event(
+        &mut *writer_a,
+        "0xwriter_a",
+        &format!("0x{}", Uuid::new_v4()),
+    )
+    .await;
+    let writer_b = tokio::spawn({
+        let pool = pool.clone();
+        async move {
+            let mut tx = pool.begin().await.unwrap();
+            let id = insert_indexed_event(&mut *tx, "0xwriter_b", &format!("0x{}", Uuid::new_v4()))
+                .await;
+            tx.commit().await.unwrap();
+            id
+        }
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+    // B waits for A instead of committing a higher id first
+    assert!(!writer_b.is_finished());
+    assert_eq!(worker.process_batch().await.unwrap(), 0);
+
+    writer_a.commit().await.unwrap();
+    let id_b = writer_b.await.unwrap();
+    assert!(id_a < id_b);
+    assert_eq!(worker.process_batch().await.unwrap(), 2);
+
+    let (alerted,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM alerts WHERE event_id = ANY($1)")
+        .bind(vec![id_a, id_b])
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(alerted, 2, "neither writer's event is skipped");
+}
+
+// ============================================================
+// Retraction of alerts for reorged events
+// ============================================================
+
+#[sqlx::test]
+#[ignore] // Also requires Redis on REDIS_URL (default redis://localhost:6379)
+async fn test_reorged_alerts_are_retracted(pool: PgPool) {
+    setup(&pool).await;
+    let user_id = create_test_user(&pool).await;
+    let chan_id = create_notification_channel(&pool, user_id).await;
+
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+    let mut redis = flare_common::redis_pool::create_redis_pool(&redis_url)
+        .await
+        .unwrap();
+
+    // One subscription per event so cooldowns don't interfere
+    let mut event_ids = Vec::new();
+    for name in ["delivered", "pending", "canonical"] {
+        let address = format!("0xretract_{}", name);
+        let addr_id = create_monitored_address(&pool, &address, "flare").await;
+        SubscriptionService::create(
+            &pool,
+            user_id,
+            &CreateSubscriptionParams {
+                address_id: addr_id,
+                channel_id: chan_id,
+                event_type: "liquidation_started".to_string(),
+                threshold_config: None,
+            },
+        )
+        .await
+        .unwrap();
+        event_ids
+            .push(insert_indexed_event(&pool, &address, &format!("0x{}", Uuid::new_v4())).await);
+    }
+
+    let mut worker = EventWorker::new(pool.clone(), redis.clone(), 100, 50)
+        .with_name(format!("test_{}", Uuid::new_v4()));
+    worker.update_cursor(event_ids[0] - 1).await.unwrap();
+    assert_eq!(worker.process_batch().await.unwrap(), 3);
+
+    let alert_for = |event_id: i64| {
+        let pool = pool.clone();
+        async move {
+            let (alert_id, retracted): (Uuid, bool) = sqlx::query_as(
+                "SELECT id, retracted_at IS NOT NULL FROM alerts WHERE event_id = $1",
+            )
+            .bind(event_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            (alert_id, retracted)
+        }
+    };
+    let (delivered_alert, _) = alert_for(event_ids[0]).await;
+    let (pending_alert, _) = alert_for(event_ids[1]).await;
+    sqlx::query("UPDATE notifications SET status = 'sent', sent_at = NOW() WHERE alert_id = $1")
+        .bind(delivered_alert)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // A reorg drops the first two events; the indexer hasn't re-indexed past it yet
+    sqlx::query("UPDATE indexed_events SET is_reorged = true WHERE id = ANY($1)")
+        .bind(&event_ids[..2])
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO chain_reorgs (chain, fork_block, detected_at_block) VALUES ('flare', 1000, 1002)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query("INSERT INTO indexer_state (chain, last_block) VALUES ('flare', 1001)")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        retract_reorged_alerts(&pool, &mut redis, true)
+            .await
+            .unwrap(),
+        RetractionStats::default(),
+        "retraction waits until the replacement blocks are indexed"
+    );
+
+    sqlx::query("UPDATE indexer_state SET last_block = 1002 WHERE chain = 'flare'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let stats = retract_reorged_alerts(&pool, &mut redis, true)
+        .await
+        .unwrap();
+    assert_eq!(
+        stats,
+        RetractionStats {
+            reorgs: 1,
+            alerts: 2,
+            cancelled: 1,
+            notices: 1,
+        }
+    );
+
+    assert!(alert_for(event_ids[0]).await.1);
+    assert!(alert_for(event_ids[1]).await.1);
+    assert!(
+        !alert_for(event_ids[2]).await.1,
+        "canonical event keeps its alert"
+    );
+
+    let notifications: Vec<(Uuid, String, String)> = sqlx::query_as(
+        "SELECT alert_id, kind, status FROM notifications WHERE alert_id = ANY($1) ORDER BY created_at, kind",
+    )
+    .bind([delivered_alert, pending_alert])
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert!(notifications.contains(&(pending_alert, "alert".into(), "cancelled".into())));
+    assert!(notifications.contains(&(delivered_alert, "alert".into(), "sent".into())));
+    assert!(notifications.contains(&(delivered_alert, "retraction".into(), "pending".into())));
+    assert_eq!(
+        notifications.len(),
+        3,
+        "only the delivered alert gets a follow-up"
+    );
+
+    // Each reorg is handled once
+    assert_eq!(
+        retract_reorged_alerts(&pool, &mut redis, true)
+            .await
+            .unwrap(),
+        RetractionStats::default()
+    );
+}
+
+// ============================================================
+// Confirmation-depth gating
+// ============================================================
+
+#[sqlx::test]
+#[ignore] // Also requires Redis on REDIS_URL (default redis://localhost:6379)
+async fn test_alerts_held_until_confirmed(pool: PgPool) {
+    setup(&pool).await;
+    let user_id = create_test_user(&pool).await;
+    let chan_id = create_notification_channel(&pool, user_id).await;
+
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+    let mut redis = flare_common::redis_pool::create_redis_pool(&redis_url)
+        .await
+        .unwrap();
+
+    // "immediate" fires at once; "confirmed" and "dropped" wait for 3 confirmations
+    let mut event_ids = Vec::new();
+    for (name, config) in [
+        ("immediate", None),
+        ("confirmed", Some(serde_json::json!({ "confirmations": 3 }))),
+        ("dropped", Some(serde_json::json!({ "confirmations": 3 }))),
+    ] {
+        let address = format!("0xconfirm_{}", name);
+        let addr_id = create_monitored_address(&pool, &address, "flare").await;
+        SubscriptionService::create(
+            &pool,
+            user_id,
+            &CreateSubscriptionParams {
+                address_id: addr_id,
+                channel_id: chan_id,
+                event_type: "liquidation_started".to_string(),
+                threshold_config: config,
+            },
+        )
+        .await
+        .unwrap();
+        event_ids
+            .push(insert_indexed_event(&pool, &address, &format!("0x{}", Uuid::new_v4())).await);
+    }
+    sqlx::query("INSERT INTO indexer_state (chain, last_block) VALUES ('flare', 1000)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let mut worker = EventWorker::new(pool.clone(), redis.clone(), 100, 50)
+        .with_name(format!("test_{}", Uuid::new_v4()));
+    worker.update_cursor(event_ids[0] - 1).await.unwrap();
+    assert_eq!(worker.process_batch().await.unwrap(), 3);
+
+    let status_of = |event_id: i64| {
+        let pool = pool.clone();
+        async move {
+            let (status,): (String,) = sqlx::query_as(
+                "SELECT n.status FROM notifications n JOIN alerts a ON a.id = n.alert_id WHERE a.event_id = $1",
+            )
+            .bind(event_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            status
+        }
+    };
+    assert_eq!(status_of(event_ids[0]).await, "pending");
+    assert_eq!(status_of(event_ids[1]).await, "held");
+    assert_eq!(status_of(event_ids[2]).await, "held");
+
+    // Not yet 3 blocks past the event
+    sqlx::query("UPDATE indexer_state SET last_block = 1002 WHERE chain = 'flare'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(release_confirmed(&pool, &mut redis).await.unwrap(), 0);
+
+    // The third event is reorged out before it is confirmed
+    sqlx::query("UPDATE indexed_events SET is_reorged = true WHERE id = $1")
+        .bind(event_ids[2])
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO chain_reorgs (chain, fork_block, detected_at_block) VALUES ('flare', 1000, 1002)",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query("UPDATE indexer_state SET last_block = 1003 WHERE chain = 'flare'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(release_confirmed(&pool, &mut redis).await.unwrap(), 1);
+    assert_eq!(status_of(event_ids[1]).await, "pending");
+    assert_eq!(
+        status_of(event_ids[2]).await,
+        "held",
+        "reorged events are never released"
+    );
+
+    let stats = retract_reorged_alerts(&pool, &mut redis, true)
+        .await
+        .unwrap();
+    assert_eq!((stats.alerts, stats.cancelled, stats.notices), (1, 1, 0));
+    assert_eq!(status_of(event_ids[2]).await, "cancelled");
+    assert_eq!(release_confirmed(&pool, &mut redis).await.unwrap(), 0);
+}
+
+// ============================================================
+// Collateral ratio alerts
+// ============================================================
+
+/// Insert a price tick for a crypto feed, e.g. `XRP/USD`.
+async fn insert_price_tick(pool: &PgPool, feed: &str, price: f64, block_number: i64) {
+    let feed_id = flare_decoders::ftso::feed_id(1, feed).unwrap();
+    sqlx::query(
+        r#"
+        INSERT INTO ftso_price_ticks (feed_id, price, decimals, block_number, block_timestamp, chain, tx_hash, log_index)
+        VALUES ($1, $2::float8::numeric, 5, $3, NOW(), 'flare', $4, 0)
+        "#,
+    )
+    .bind(flare_decoders::ftso::feed_id_hex(&feed_id))
+    .bind(price)
+    .bind(block_number)
+    .bind(format!("0x{}", Uuid::new_v4()))
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+/// AssetManager that the FAsset events of `insert_decoded_event` come from.
+const ASSET_MANAGER: &str = "0x00000000000000000000000000000000000a55e7";
+
+/// Vault collateral and minted UBA that `stub_agent_info` reports.
+type StubVault = std::sync::Arc<std::sync::Mutex<(u64, u64)>>;
+
+/// Serve a fake JSON-RPC node whose AssetManager reports every agent with `vault`
+/// collateral and `minted_uba` minted, and record the blocks read at. The returned
+/// state can be changed to report a different vault from then on.
+async fn stub_agent_info(
+    vault: u64,
+    minted_uba: u64,
+) -> (
+    AgentInfoReader,
+    std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+    StubVault,
+) {
+    let blocks: std::sync::Arc<std::sync::Mutex<Vec<String>>> = Default::default();
+    let recorded = blocks.clone();
+    let state: StubVault = std::sync::Arc::new(std::sync::Mutex::new((vault, minted_uba)));
+    let reported = state.clone();
+    let router = axum::Router::new().route(
+        "/",
+        axum::routing::post(
+            move |axum::Json(request): axum::Json<serde_json::Value>| async move {
+                let (vault, minted_uba) = *reported.lock().unwrap();
+                assert_eq!(request["method"], "eth_call");
+                assert_eq!(request["params"][0]["to"], ASSET_MANAGER);
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push(request["params"][1].as_str().unwrap().to_string());
+
+                // Offset of the `AgentInfo.Info` struct, then its head words
+                let mut words = vec![0u64; 41];
+                words[0] = 32;
+                words[1 + 13] = vault;
+                words[1 + 24] = minted_uba;
+                let result: String = words.iter().map(|w| format!("{:064x}", w)).collect();
+                axum::Json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": format!("0x{}", result),
+                }))
+            },
+        ),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    let reader = AgentInfoReader::new()
+        .with_chain(Chain::Flare, &format!("http://{}", addr))
+        .unwrap();
+    (reader, blocks, state)
+}
+
+/// Insert an event with decoded data and return its ID.
+async fn insert_decoded_event(
+    pool: &PgPool,
+    event_type: &str,
+    block_number: i64,
+    decoded_data: serde_json::Value,
+) -> i64 {
+    let (id,): (i64,) = sqlx::query_as(
+        r#"
+        INSERT INTO indexed_events (tx_hash, log_index, block_number, block_timestamp, chain, address, event_type, decoded_data)
+        VALUES ($1, 0, $2, NOW(), 'flare', $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(format!("0x{}", Uuid::new_v4()))
+    .bind(block_number)
+    .bind(ASSET_MANAGER)
+    .bind(event_type)
+    .bind(decoded_data)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    id
+}
+
+#[sqlx::test]
+#[ignore] // Also requires Redis on REDIS_URL (default redis://localhost:6379)
+async fn test_worker_raises_cr_alerts_from_price_ticks(pool: PgPool) {
+    setup(&pool).await;
+    let agent = "0x00000000000000000000000000000000000a11ce";
+    let user_id = create_test_user(&pool).await;
+    let addr_id = create_monitored_address(&pool, agent, "flare").await;
+    let chan_id = create_notification_channel(&pool, user_id).await;
+
+    let mut subscription_ids = Vec::new();
+    for (event_type, config) in [
+        ("cr_warning", None),
+        (
+            "cr_critical",
+            Some(serde_json::json!({ "cr_critical": 1.2 })),
+        ),
+    ] {
+        let sub = SubscriptionService::create(
+            &pool,
+            user_id,
+            &CreateSubscriptionParams {
+                address_id: addr_id,
+                channel_id: chan_id,
+                event_type: event_type.to_string(),
+                threshold_config: config,
+            },
+        )
+        .await
+        .unwrap();
+        subscription_ids.push(sub.id);
+    }
+
+    // Events before the cursor only make the agent known
+    insert_decoded_event(
+        &pool,
+        "collateral_deposited",
+        1000,
+        serde_json::json!({ "agent": agent, "amount": "100000000", "fasset": "FXRP" }),
+    )
+    .await;
+    let cursor = insert_decoded_event(
+        &pool,
+        "minting_executed",
+        1001,
+        serde_json::json!({
+            "agent": agent,
+            "collateral_reservation_id": "1",
+            "minted_amount_uba": "100000000",
+            "fasset": "FXRP",
+        }),
+    )
+    .await;
+    insert_price_tick(&pool, "USDT/USD", 1.0, 1000).await;
+    insert_price_tick(&pool, "XRP/USD", 0.5, 1000).await;
+
+    // 100 USDT backing 10 FXRP lots of 10 XRP
+    let (agent_info, blocks, vault) = stub_agent_info(100_000_000, 100_000_000).await;
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+    let redis = flare_common::redis_pool::create_redis_pool(&redis_url)
+        .await
+        .unwrap();
+    let mut worker = EventWorker::new(pool.clone(), redis, 100, 50)
+        .with_name(format!("test_{}", Uuid::new_v4()))
+        .with_cr_calculator(CrCalculator::new().with_agent_info(agent_info));
+    worker.update_cursor(cursor).await.unwrap();
+
+    // The next tick seeds the vault at its block: CR 2.0 at $0.50 is the baseline
+    insert_decoded_event(&pool, "fast_update_feeds", 1002, serde_json::json!({})).await;
+    assert_eq!(worker.process_batch().await.unwrap(), 1);
+    assert_eq!(*blocks.lock().unwrap(), vec!["0x3ea".to_string()]);
+
+    // The seeded vault is recorded as a health snapshot
+    let (snapshot_cr, minted_value): (Option<f64>, Option<f64>) =
+        sqlx::query_as("SELECT cr, minted_value_usd FROM agent_vault_snapshots WHERE agent = $1")
+            .bind(agent)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert!((snapshot_cr.unwrap() - 2.0).abs() < 1e-9);
+    assert!((minted_value.unwrap() - 50.0).abs() < 1e-9);
+
+    // XRP rises to $0.60: CR 1.67 is below the 1.8 warning but above the 1.2 critical
+    insert_price_tick(&pool, "XRP/USD", 0.6, 1003).await;
+    insert_decoded_event(&pool, "fast_update_feeds", 1003, serde_json::json!({})).await;
+    assert_eq!(worker.process_batch().await.unwrap(), 1);
+
+    let synthetic: Vec<(String, i64, serde_json::Value)> = sqlx::query_as(
+        "SELECT event_type, log_index, decoded_data FROM indexed_events WHERE address = $1",
+    )
+    .bind(agent)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(synthetic.len(), 1);
+    assert_eq!(synthetic[0].0, "cr_warning");
+    assert!(synthetic[0].1 < 0);
+    let cr = synthetic[0].2["cr"].as_f64().unwrap();
+    assert!((cr - 100.0 / 60.0).abs() < 1e-9);
+    assert_eq!(synthetic[0].2["fasset"], "FXRP");
+
+    // The synthetic event goes through the alert pipeline
+    assert_eq!(worker.process_batch().await.unwrap(), 1);
+    let alerts: Vec<(Uuid,)> = sqlx::query_as("SELECT subscription_id FROM alerts")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(alerts, vec![(subscription_ids[0],)]);
+
+    // Still below the warning threshold: no new crossing
+    insert_price_tick(&pool, "XRP/USD", 0.65, 1004).await;
+    insert_decoded_event(&pool, "fast_update_feeds", 1004, serde_json::json!({})).await;
+    worker.process_batch().await.unwrap();
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM indexed_events WHERE address = $1")
+        .bind(agent)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 1);
+
+    // Seeded once, and within the snapshot interval: no second snapshot
+    assert_eq!(blocks.lock().unwrap().len(), 1);
+    let (snapshots,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM agent_vault_snapshots")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(snapshots, 1);
+
+    // Another minting doubles the backed lots: the agent's event re-reads the vault
+    // at its block, and CR 0.77 at $0.65 crosses the 1.2 critical threshold
+    vault.lock().unwrap().1 = 200_000_000;
+    insert_decoded_event(
+        &pool,
+        "minting_executed",
+        1005,
+        serde_json::json!({
+            "agent": agent,
+            "collateral_reservation_id": "2",
+            "minted_amount_uba": "100000000",
+            "fasset": "FXRP",
+        }),
+    )
+    .await;
+    worker.process_batch().await.unwrap();
+    assert_eq!(
+        *blocks.lock().unwrap(),
+        vec!["0x3ea".to_string(), "0x3ed".to_string()]
+    );
+    let latest: (String, serde_json::Value) = sqlx::query_as(
+        "SELECT event_type, decoded_data FROM indexed_events WHERE address = $1 ORDER BY id DESC LIMIT 1",
+    )
+    .bind(agent)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(latest.0, "cr_critical");
+    assert_eq!(latest.1["minted_lots"], 20);
+    assert!((latest.1["cr"].as_f64().unwrap() - 100.0 / 130.0).abs() < 1e-9);
+    assert_eq!(latest.1["trigger"], "minting_executed");
+}
+
+/// Read an indexed event back as the worker hands it to the calculator.
+async fn decoded_event(pool: &PgPool, id: i64) -> DecodedEvent {
+    let row: IndexedEvent = sqlx::query_as(
+        r#"
+        SELECT id, tx_hash, log_index, block_number, block_timestamp, chain, address,
+               event_type, decoded_data, is_reorged
+        FROM indexed_events WHERE id = $1
+        "#,
+    )
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    DecodedEvent::from(row)
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_agents_already_below_thresholds_are_not_alerted_on_seeding(pool: PgPool) {
+    setup(&pool).await;
+    let agent = "0x00000000000000000000000000000000000a11ce";
+    let user_id = create_test_user(&pool).await;
+    let addr_id = create_monitored_address(&pool, agent, "flare").await;
+    let chan_id = create_notification_channel(&pool, user_id).await;
+    for event_type in ["cr_warning", "cr_critical", "liquidation_imminent"] {
+        SubscriptionService::create(
+            &pool,
+            user_id,
+            &CreateSubscriptionParams {
+                address_id: addr_id,
+                channel_id: chan_id,
+                event_type: event_type.to_string(),
+                threshold_config: None,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    // 100 USDT backing 10 FXRP lots at $0.90: CR 1.11, below every threshold
+    insert_price_tick(&pool, "USDT/USD", 1.0, 1000).await;
+    insert_price_tick(&pool, "XRP/USD", 0.9, 1000).await;
+    let (agent_info, blocks, _) = stub_agent_info(100_000_000, 100_000_000).await;
+    let mut calculator = CrCalculator::new().with_agent_info(agent_info);
+    calculator.refresh_agents(&pool, 0).await.unwrap();
+
+    // First event of an agent that minted before it: seeded, not a fresh crossing
+    let deposit = insert_decoded_event(
+        &pool,
+        "collateral_deposited",
+        1001,
+        serde_json::json!({ "agent": agent, "amount": "1", "fasset": "FXRP" }),
+    )
+    .await;
+    let update = calculator
+        .observe(&pool, &decoded_event(&pool, deposit).await)
+        .await
+        .unwrap();
+    assert!(update.events.is_empty());
+    assert_eq!(update.snapshots.len(), 1);
+    let cr = update.snapshots[0].cr.unwrap();
+    assert!((cr - 100.0 / 90.0).abs() < 1e-9);
+    insert_snapshots(&pool, &update.snapshots).await.unwrap();
+
+    // After a reset the vault is seeded again, with the stored CR as the previous one
+    calculator.reset();
+    calculator.refresh_agents(&pool, deposit).await.unwrap();
+    let tick = insert_decoded_event(&pool, "fast_update_feeds", 1002, serde_json::json!({})).await;
+    let update = calculator
+        .observe(&pool, &decoded_event(&pool, tick).await)
+        .await
+        .unwrap();
+    assert!(update.events.is_empty());
+    assert_eq!(
+        *blocks.lock().unwrap(),
+        vec!["0x3e9".to_string(), "0x3ea".to_string()]
+    );
+}
+
+/// Insert a feed event at `at`, with a FLR/USD tick written by it if `price` is set.
+async fn insert_feed_event(
+    pool: &PgPool,
+    event_type: &str,
+    address: &str,
+    at: chrono::DateTime<Utc>,
+    decoded_data: serde_json::Value,
+    price: Option<f64>,
+) -> i64 {
+    let tx_hash = format!("0x{}", Uuid::new_v4());
+    let block_number = 2000 + at.timestamp() % 1000;
+    if let Some(price) = price {
+        let feed_id = flare_decoders::ftso::feed_id(1, "FLR/USD").unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO ftso_price_ticks (feed_id, price, decimals, block_number, block_timestamp, chain, tx_hash, log_index)
+            VALUES ($1, $2::float8::numeric, 5, $3, $4, 'flare', $5, 0)
+            "#,
+        )
+        .bind(flare_decoders::ftso::feed_id_hex(&feed_id))
+        .bind(price)
+        .bind(block_number)
+        .bind(at)
+        .bind(&tx_hash)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    let (id,): (i64,) = sqlx::query_as(
+        r#"
+        INSERT INTO indexed_events (tx_hash, log_index, block_number, block_timestamp, chain, address, event_type, decoded_data)
+        VALUES ($1, 0, $2, $3, 'flare', $4, $5, $6)
+        RETURNING id
+        "#,
+    )
+    .bind(&tx_hash)
+    .bind(block_number)
+    .bind(at)
+    .bind(address)
+    .bind(event_type)
+    .bind(decoded_data)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    id
+}
+
+#[sqlx::test]
+#[ignore] // Also requires Redis on REDIS_URL (default redis://localhost:6379)
+async fn test_worker_raises_feed_alerts(pool: PgPool) {
+    setup(&pool).await;
+    let user_id = create_test_user(&pool).await;
+    let chan_id = create_notification_channel(&pool, user_id).await;
+    let publisher = "0x00000000000000000000000000000000000f7500";
+    let publisher_id = create_monitored_address(&pool, publisher, "flare").await;
+
+    let mut subscription_ids = Vec::new();
+    for (event_type, config) in [
+        ("price_threshold", serde_json::json!({ "max_value": 0.03 })),
+        (
+            "price_move",
+            serde_json::json!({ "deviation_pct": 10.0, "window_secs": 600 }),
+        ),
+        ("feed_stale", serde_json::json!({ "stale_epochs": 2 })),
+    ] {
+        let sub = SubscriptionService::create_for_feed(
+            &pool,
+            user_id,
+            &CreateFeedSubscriptionParams {
+                feed: "flr/usd".to_string(),
+                chain: None,
+                channel_id: chan_id,
+                event_type: event_type.to_string(),
+                threshold_config: Some(config),
+            },
+        )
+        .await
+        .unwrap();
+        subscription_ids.push(sub.id);
+    }
+    // deviation_pct on the anchor feed events of a publisher contract
+    let published = SubscriptionService::create(
+        &pool,
+        user_id,
+        &CreateSubscriptionParams {
+            address_id: publisher_id,
+            channel_id: chan_id,
+            event_type: "ftso_feed_published".to_string(),
+            threshold_config: Some(
+                serde_json::json!({ "deviation_pct": 30.0, "window_secs": 600 }),
+            ),
+        },
+    )
+    .await
+    .unwrap();
+
+    // All three feed subscriptions share one monitored feed
+    let (feeds,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM monitored_addresses WHERE address = 'FLR/USD' AND address_type = 'ftso_feed'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(feeds, 1);
+
+    // History before the worker starts
+    let t0 = Utc::now() - chrono::Duration::hours(1);
+    let at = |secs| t0 + chrono::Duration::seconds(secs);
+    insert_feed_event(
+        &pool,
+        "fast_update_feeds",
+        "0xfastupdater",
+        at(-900),
+        serde_json::json!({}),
+        Some(0.020),
+    )
+    .await;
+    let cursor = insert_feed_event(
+        &pool,
+        "fast_update_feeds",
+        "0xfastupdater",
+        at(-300),
+        serde_json::json!({}),
+        Some(0.021),
+    )
+    .await;
+
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+    let redis = flare_common::redis_pool::create_redis_pool(&redis_url)
+        .await
+        .unwrap();
+    let mut worker = EventWorker::new(pool.clone(), redis, 100, 50)
+        .with_name(format!("test_{}", Uuid::new_v4()));
+    worker.update_cursor(cursor).await.unwrap();
+
+    // +25% against the price 600s earlier (0.020): price_move
+    insert_feed_event(
+        &pool,
+        "fast_update_feeds",
+        "0xfastupdater",
+        at(0),
+        serde_json::json!({}),
+        Some(0.025),
+    )
+    .await;
+    // Anchor value 0.028 is +40% against the same baseline
+    let feed_id =
+        flare_decoders::ftso::feed_id_hex(&flare_decoders::ftso::feed_id(1, "FLR/USD").unwrap());
+    insert_feed_event(
+        &pool,
+        "ftso_feed_published",
+        publisher,
+        at(60),
+        serde_json::json!({ "feed_id": feed_id, "value": 2800, "decimals": 5 }),
+        Some(0.028),
+    )
+    .await;
+    // Above 0.03: price_threshold; the move still holds and doesn't alert again
+    insert_feed_event(
+        &pool,
+        "fast_update_feeds",
+        "0xfastupdater",
+        at(90),
+        serde_json::json!({}),
+        Some(0.031),
+    )
+    .await;
+    // No update for 300s, more than 2 epochs: feed_stale
+    insert_feed_event(
+        &pool,
+        "reward_epoch_started",
+        "0xother",
+        at(390),
+        serde_json::json!({}),
+        None,
+    )
+    .await;
+
+    while worker.process_batch().await.unwrap() > 0 {}
+
+    let synthetic: Vec<(String, serde_json::Value)> = sqlx::query_as(
+        "SELECT event_type, decoded_data FROM indexed_events WHERE address = 'FLR/USD' ORDER BY id",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    let types: Vec<&str> = synthetic.iter().map(|(t, _)| t.as_str()).collect();
+    assert_eq!(types, vec!["price_move", "price_threshold", "feed_stale"]);
+    assert!((synthetic[0].1["baseline"].as_f64().unwrap() - 0.020).abs() < 1e-9);
+    assert!((synthetic[0].1["change_pct"].as_f64().unwrap() - 25.0).abs() < 1e-6);
+    assert_eq!(synthetic[1].1["direction"], "above");
+    assert_eq!(synthetic[2].1["stale_secs"], 300);
+
+    // One alert per subscription: synthetic events only match their own subscription
+    let mut alerts: Vec<(Uuid,)> = sqlx::query_as("SELECT subscription_id FROM alerts")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    alerts.sort();
+    let mut expected: Vec<(Uuid,)> = subscription_ids
+        .iter()
+        .chain([&published.id])
+        .map(|id| (*id,))
+        .collect();
+    expected.sort();
+    assert_eq!(alerts, expected);
+}
+
+/// Insert a Flare Systems Protocol event emitted by `contract` and return its ID.
+async fn insert_protocol_event(
+    pool: &PgPool,
+    event_type: &str,
+    contract: &str,
+    block_number: i64,
+    decoded_data: serde_json::Value,
+) -> i64 {
+    let (id,): (i64,) = sqlx::query_as(
+        r#"
+        INSERT INTO indexed_events (tx_hash, log_index, block_number, block_timestamp, chain, address, event_type, decoded_data)
+        VALUES ($1, 0, $2, NOW(), 'flare', $3, $4, $5)
+        RETURNING id
+        "#,
+    )
+    .bind(format!("0x{}", Uuid::new_v4()))
+    .bind(block_number)
+    .bind(contract)
+    .bind(event_type)
+    .bind(decoded_data)
+    .fetch_one(pool)
+    .await
+    .unwrap();
+    id
+}
+
+#[sqlx::test]
+#[ignore] // Also requires Redis on REDIS_URL (default redis://localhost:6379)
+async fn test_voter_alerted_when_dropped_from_signing_policy(pool: PgPool) {
+    setup(&pool).await;
+    let voter = "0x00000000000000000000000000000000000000a1";
+    let other = "0x00000000000000000000000000000000000000b2";
+    let relay = "0x0000000000000000000000000000000000000e1a";
+    let registry = "0x0000000000000000000000000000000000000e9e";
+    let user_id = create_test_user(&pool).await;
+    let addr_id = create_monitored_address(&pool, voter, "flare").await;
+    let chan_id = create_notification_channel(&pool, user_id).await;
+
+    let mut subscription_ids = Vec::new();
+    for event_type in ["signing_policy_initialized", "voter_removed"] {
+        let sub = SubscriptionService::create(
+            &pool,
+            user_id,
+            &CreateSubscriptionParams {
+                address_id: addr_id,
+                channel_id: chan_id,
+                event_type: event_type.to_string(),
+                threshold_config: None,
+            },
+        )
+        .await
+        .unwrap();
+        subscription_ids.push(sub.id);
+    }
+
+    // Policies list signing-policy addresses, mapped to voters by their registrations
+    let register = |voter: &'static str, epoch: i64, signing_policy_address: &'static str| {
+        let pool = pool.clone();
+        async move {
+            insert_protocol_event(
+                &pool,
+                "voter_registered",
+                registry,
+                2999,
+                serde_json::json!({
+                    "voter": voter,
+                    "reward_epoch_id": epoch,
+                    "signing_policy_address": signing_policy_address,
+                }),
+            )
+            .await
+        }
+    };
+    let voter_signer = "0x00000000000000000000000000000000000005a1";
+    let other_signer = "0x00000000000000000000000000000000000005b2";
+    let other_new_signer = "0x00000000000000000000000000000000000005b3";
+    register(voter, 250, voter_signer).await;
+    register(other, 250, other_signer).await;
+    register(other, 251, other_new_signer).await;
+
+    // The voter is in the policy for epoch 250
+    let cursor = insert_protocol_event(
+        &pool,
+        "signing_policy_initialized",
+        relay,
+        3000,
+        serde_json::json!({ "reward_epoch_id": 250, "voters": [voter_signer, other_signer] }),
+    )
+    .await;
+
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+    let redis = flare_common::redis_pool::create_redis_pool(&redis_url)
+        .await
+        .unwrap();
+    let mut worker = EventWorker::new(pool.clone(), redis, 100, 50)
+        .with_name(format!("test_{}", Uuid::new_v4()));
+    worker.update_cursor(cursor).await.unwrap();
+
+    // Another voter is removed: not this subscription's voter
+    insert_protocol_event(
+        &pool,
+        "voter_removed",
+        registry,
+        3001,
+        serde_json::json!({ "voter": other, "reward_epoch_id": 251 }),
+    )
+    .await;
+    // The voter is removed from the epoch 251 registration and left out of its policy,
+    // while the other voter stays in with a new signing-policy address
+    insert_protocol_event(
+        &pool,
+        "voter_removed",
+        registry,
+        3002,
+        serde_json::json!({ "voter": voter, "reward_epoch_id": 251 }),
+    )
+    .await;
+    insert_protocol_event(
+        &pool,
+        "signing_policy_initialized",
+        relay,
+        3003,
+        serde_json::json!({ "reward_epoch_id": 251, "voters": [other_new_signer] }),
+    )
+    .await;
+    assert_eq!(worker.process_batch().await.unwrap(), 3);
+
+    let alerts: Vec<(Uuid, String, String)> = sqlx::query_as(
+        "SELECT subscription_id, severity, message FROM alerts ORDER BY triggered_at, id",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(alerts.len(), 2);
+    let removed = alerts
+        .iter()
+        .find(|(sub, _, _)| *sub == subscription_ids[1])
+        .unwrap();
+    assert!(removed.2.contains(voter));
+    let dropped = alerts
+        .iter()
+        .find(|(sub, _, _)| *sub == subscription_ids[0])
+        .unwrap();
+    assert_eq!(dropped.1, "critical");
+    assert!(
+        dropped
+            .2
+            .contains(&format!("Dropped since the previous epoch: {}", voter))
+    );
+    assert!(!dropped.2.contains(other));
+}
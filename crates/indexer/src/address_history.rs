@@ -0,0 +1,143 @@
+//! Block-range history of the contract addresses resolved from FlareContractRegistry.
+//!
+//! Every resolution is recorded in `contract_address_history`. A contract whose
+//! address changed gets its previous row closed at the block before the change and
+//! a new open row (`to_block IS NULL`) from the block the poller switched filters.
+
+use std::collections::HashMap;
+
+use alloy::primitives::Address;
+use sqlx::PgPool;
+
+use flare_common::types::Chain;
+
+use crate::registry::ResolvedAddresses;
+
+/// A contract whose resolved address differs from the one recorded as current.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressChange {
+    pub contract_name: String,
+    /// Address previously in use; `None` the first time the contract resolves
+    pub previous: Option<Address>,
+    pub address: Address,
+}
+
+/// Record `resolved` as the addresses in use from `block` on, returning the
+/// contracts whose address changed.
+///
+/// Contracts missing from `resolved` keep their current row.
+pub async fn record_resolution(
+    pool: &PgPool,
+    chain: Chain,
+    block: u64,
+    resolved: &ResolvedAddresses,
+) -> anyhow::Result<Vec<AddressChange>> {
+    let mut tx = pool.begin().await?;
+    let current: Vec<(String, String, i64)> = sqlx::query_as(
+        r#"
+        SELECT contract_name, address, from_block FROM contract_address_history
+        WHERE chain = $1 AND to_block IS NULL
+        FOR UPDATE
+        "#,
+    )
+    .bind(chain.to_string())
+    .fetch_all(&mut *tx)
+    .await?;
+    let current: HashMap<String, (String, i64)> = current
+        .into_iter()
+        .map(|(name, address, from_block)| (name, (address, from_block)))
+        .collect();
+
+    let mut names: Vec<&String> = resolved.addresses.keys().collect();
+    names.sort();
+
+    let mut changes = Vec::new();
+    for name in names {
+        let address = resolved.addresses[name];
+        let stored = format!("{:#x}", address);
+        let previous = match current.get(name) {
+            Some((current_address, _)) if *current_address == stored => continue,
+            Some((current_address, from_block)) => {
+                if *from_block as u64 >= block {
+                    // Re-resolved at or before the row's first block (after a rewind):
+                    // the new address replaces it outright
+                    sqlx::query(
+                        r#"
+                        DELETE FROM contract_address_history
+                        WHERE chain = $1 AND contract_name = $2 AND from_block = $3
+                        "#,
+                    )
+                    .bind(chain.to_string())
+                    .bind(name)
+                    .bind(from_block)
+                    .execute(&mut *tx)
+                    .await?;
+                } else {
+                    sqlx::query(
+                        r#"
+                        UPDATE contract_address_history SET to_block = $3
+                        WHERE chain = $1 AND contract_name = $2 AND to_block IS NULL
+                        "#,
+                    )
+                    .bind(chain.to_string())
+                    .bind(name)
+                    .bind(block as i64 - 1)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                current_address.parse().ok()
+            }
+            None => None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO contract_address_history (chain, contract_name, address, from_block)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(chain.to_string())
+        .bind(name)
+        .bind(&stored)
+        .bind(block as i64)
+        .execute(&mut *tx)
+        .await?;
+
+        changes.push(AddressChange {
+            contract_name: name.clone(),
+            previous,
+            address,
+        });
+    }
+
+    tx.commit().await?;
+    Ok(changes)
+}
+
+/// Every recorded contract address still in use at or after `from_block`, with
+/// its contract name.
+///
+/// Addresses first recorded after `from_block` are included too: an address was
+/// usually in use before it was first recorded, and a contract that didn't exist
+/// yet has no logs to match.
+pub async fn contracts_in_use_since(
+    pool: &PgPool,
+    chain: Chain,
+    from_block: u64,
+) -> anyhow::Result<Vec<(String, Address)>> {
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT contract_name, address FROM contract_address_history
+        WHERE chain = $1 AND (to_block IS NULL OR to_block >= $2)
+        ORDER BY contract_name, address
+        "#,
+    )
+    .bind(chain.to_string())
+    .bind(from_block as i64)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|(name, address)| Ok((name, address.parse()?)))
+        .collect()
+}
@@ -0,0 +1,362 @@
+//! Historical backfill over a fixed block range.
+//!
+//! Unlike `BlockPoller`, which follows the live cursor, a backfill fetches
+//! `eth_getLogs` over large chunked ranges (see `LogWindow`) and fetches headers
+//! only for blocks that actually contain logs. Progress is stored per
+//! range in `backfill_progress`, separately from `indexer_state`, so a backfill
+//! can run alongside live indexing and resume after a restart.
+//!
+//! Backfilled events are inserted with `backfilled = true`: they are available to
+//! queries and analysis, but the engine does not alert on them. Their FTSO price
+//! ticks are written too, with fast update feed IDs read as of the range's end.
+//!
+//! A backfill never passes the live cursor (`indexer_state.last_block`), checked
+//! before each chunk: blocks after it are left to the live poller, so their events
+//! are stored as live ones and alerted on.
+
+use std::collections::HashMap;
+
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use alloy::rpc::types::{Filter, Log};
+use chrono::{DateTime, TimeZone, Utc};
+use sqlx::{PgConnection, PgPool};
+
+use flare_common::types::Chain;
+use flare_decoders::DecoderRegistry;
+
+use crate::poller::{decode_logs, fetch_block_headers, insert_events};
+use crate::prices;
+use crate::registry;
+use crate::rpc::{FailoverConfig, FailoverTransport};
+use crate::window::{LogWindow, is_range_error};
+
+/// Fetches, decodes and persists logs for a historical block range.
+pub struct Backfiller {
+    rpc_url: String,
+    fallback_rpc_urls: Vec<String>,
+    failover: FailoverConfig,
+    chain: Chain,
+    pool: PgPool,
+    decoders: DecoderRegistry,
+    chunk_size: u64,
+    /// Contract addresses to filter logs by; empty means unfiltered.
+    contract_addresses: Vec<Address>,
+    /// FAsset symbol of each AssetManager, tagged onto its events
+    fasset_symbols: HashMap<Address, String>,
+    /// `FastUpdatesConfiguration` the fast update feed IDs are read from
+    fast_updates_configuration: Option<Address>,
+}
+
+/// Summary of a finished backfill run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillStats {
+    /// Blocks scanned in this run (excludes blocks done by earlier runs)
+    pub blocks: u64,
+    /// Events decoded in this run (including any that were already stored)
+    pub events: usize,
+}
+
+impl Backfiller {
+    pub fn new(rpc_url: String, chain: Chain, pool: PgPool, chunk_size: u64) -> Self {
+        Self {
+            rpc_url,
+            fallback_rpc_urls: Vec::new(),
+            failover: FailoverConfig::default(),
+            chain,
+            pool,
+            decoders: DecoderRegistry::new(),
+            chunk_size: chunk_size.max(1),
+            contract_addresses: Vec::new(),
+            fasset_symbols: HashMap::new(),
+            fast_updates_configuration: None,
+        }
+    }
+
+    /// Set the contract addresses to filter logs by.
+    pub fn with_contract_addresses(mut self, addresses: Vec<Address>) -> Self {
+        self.contract_addresses = addresses;
+        self
+    }
+
+    /// Also fetch these addresses' logs, storing the unmatched ones as generic events.
+    pub fn with_generic_addresses(mut self, addresses: Vec<Address>) -> Self {
+        self.decoders.set_generic_addresses(addresses);
+        self
+    }
+
+    /// Tag events from these AssetManagers with their FAsset symbol.
+    pub fn with_fasset_symbols(mut self, symbols: HashMap<Address, String>) -> Self {
+        self.fasset_symbols = symbols;
+        self
+    }
+
+    /// Read fast update feed IDs from this `FastUpdatesConfiguration` for price ticks.
+    pub fn with_fast_updates_configuration(mut self, configuration: Option<Address>) -> Self {
+        self.fast_updates_configuration = configuration;
+        self
+    }
+
+    /// Add fallback RPC endpoints (highest priority first) and failover tuning.
+    pub fn with_rpc_failover(mut self, fallback_urls: Vec<String>, config: FailoverConfig) -> Self {
+        self.fallback_rpc_urls = fallback_urls;
+        self.failover = config;
+        self
+    }
+
+    /// Backfill `from..=to`, resuming from stored progress for the same range.
+    pub async fn run(&self, from: u64, to: u64) -> anyhow::Result<BackfillStats> {
+        if from > to {
+            anyhow::bail!(
+                "Invalid backfill range: --from {} is after --to {}",
+                from,
+                to
+            );
+        }
+
+        let mut rpc_urls = vec![self.rpc_url.clone()];
+        rpc_urls.extend(self.fallback_rpc_urls.iter().cloned());
+        let transport = FailoverTransport::new(self.chain, rpc_urls, self.failover.clone())?;
+        let _health_checks = transport.spawn_health_checks();
+        let provider = transport.provider();
+        let start = self.start_block(from, to).await?;
+        let mut stats = BackfillStats {
+            blocks: 0,
+            events: 0,
+        };
+
+        if start > to {
+            tracing::info!(chain = %self.chain, from, to, "Backfill range already complete");
+            return Ok(stats);
+        }
+
+        tracing::info!(
+            chain = %self.chain,
+            from,
+            to,
+            resume_at = start,
+            chunk_size = self.chunk_size,
+            "Backfill started"
+        );
+
+        let fast_update_feed_ids = match self.fast_updates_configuration {
+            Some(configuration) => {
+                match registry::fast_update_feed_ids(&provider, configuration, Some(to)).await {
+                    Ok(feed_ids) => feed_ids,
+                    Err(e) => {
+                        tracing::warn!(
+                            chain = %self.chain,
+                            error = %e,
+                            "Failed to read fast update feed IDs, fast updates get no price ticks"
+                        );
+                        Vec::new()
+                    }
+                }
+            }
+            None => Vec::new(),
+        };
+
+        let mut window = LogWindow::new(self.chunk_size);
+        let mut next = start;
+        while next <= to {
+            let live_cursor = self.live_cursor().await?;
+            let limit = live_cursor.map_or(to, |cursor| to.min(cursor));
+            if next > limit {
+                tracing::info!(
+                    chain = %self.chain,
+                    next,
+                    to,
+                    live_cursor,
+                    "Backfill reached the live cursor, later blocks are indexed live"
+                );
+                break;
+            }
+            let end = window.end(next, limit);
+
+            let logs = match self.fetch_logs(&provider, next, end).await {
+                Ok(logs) => logs,
+                Err(e) if is_range_error(&e) && window.shrink() => {
+                    tracing::warn!(
+                        from = next,
+                        to = end,
+                        error = %e,
+                        new_chunk_size = window.size(),
+                        "eth_getLogs range rejected, shrinking backfill chunk"
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            window.grow();
+
+            let timestamps = self.block_timestamps(&provider, &logs).await?;
+            let events = decode_logs(
+                &self.decoders,
+                &logs,
+                self.chain,
+                &self.fasset_symbols,
+                |block| timestamps.get(&block).copied().unwrap_or_else(Utc::now),
+            );
+            // Events and progress commit together, so a resumed backfill never skips
+            let mut tx = self.pool.begin().await?;
+            insert_events(&mut tx, &events, true).await?;
+            prices::insert_price_ticks(
+                &mut tx,
+                &prices::price_ticks(&events, &fast_update_feed_ids),
+            )
+            .await?;
+            record_progress(&mut tx, self.chain, from, to, end + 1).await?;
+            tx.commit().await?;
+
+            stats.blocks += end - next + 1;
+            stats.events += events.len();
+            tracing::info!(
+                chain = %self.chain,
+                from = next,
+                to = end,
+                logs = logs.len(),
+                events = events.len(),
+                remaining = to - end,
+                "Backfilled chunk"
+            );
+
+            next = end + 1;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE backfill_progress SET completed_at = NOW(), updated_at = NOW()
+            WHERE chain = $1 AND from_block = $2 AND to_block = $3
+            "#,
+        )
+        .bind(self.chain.to_string())
+        .bind(from as i64)
+        .bind(to as i64)
+        .execute(&self.pool)
+        .await?;
+
+        tracing::info!(
+            chain = %self.chain,
+            from,
+            to,
+            blocks = stats.blocks,
+            events = stats.events,
+            "Backfill complete"
+        );
+        Ok(stats)
+    }
+
+    async fn fetch_logs(
+        &self,
+        provider: &impl Provider,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<Vec<Log>> {
+        let mut filter = Filter::new().from_block(from).to_block(to);
+        if !self.contract_addresses.is_empty() {
+            let mut addresses = self.contract_addresses.clone();
+            addresses.extend(self.decoders.generic_addresses().iter().copied());
+            filter = filter.address(addresses);
+        }
+        Ok(provider.get_logs(&filter).await?)
+    }
+
+    /// Block timestamps for every block referenced by `logs`.
+    ///
+    /// Uses the `blockTimestamp` log field where the node provides it and fetches
+    /// the other blocks' headers in batches.
+    async fn block_timestamps(
+        &self,
+        provider: &impl Provider,
+        logs: &[Log],
+    ) -> anyhow::Result<HashMap<u64, DateTime<Utc>>> {
+        let mut seconds = HashMap::new();
+        let mut missing = Vec::new();
+        for log in logs {
+            let Some(block) = log.block_number else {
+                continue;
+            };
+            match log.block_timestamp {
+                Some(timestamp) => {
+                    seconds.insert(block, timestamp);
+                }
+                None => missing.push(block),
+            }
+        }
+        missing.sort_unstable();
+        missing.dedup();
+        missing.retain(|block| !seconds.contains_key(block));
+        for header in fetch_block_headers(provider, &missing).await? {
+            seconds.insert(header.number, header.timestamp);
+        }
+
+        Ok(seconds
+            .into_iter()
+            .map(|(block, seconds)| {
+                let timestamp = Utc
+                    .timestamp_opt(seconds as i64, 0)
+                    .single()
+                    .unwrap_or_else(Utc::now);
+                (block, timestamp)
+            })
+            .collect())
+    }
+
+    /// Last block indexed by the live poller, if it has run on this chain.
+    async fn live_cursor(&self) -> anyhow::Result<Option<u64>> {
+        let row: Option<(i64,)> =
+            sqlx::query_as("SELECT last_block FROM indexer_state WHERE chain = $1")
+                .bind(self.chain.to_string())
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row.map(|(block,)| block as u64))
+    }
+
+    /// First block still to do for this range, creating its progress row if needed.
+    pub async fn start_block(&self, from: u64, to: u64) -> anyhow::Result<u64> {
+        let (next_block,): (i64,) = sqlx::query_as(
+            r#"
+            INSERT INTO backfill_progress (chain, from_block, to_block, next_block)
+            VALUES ($1, $2, $3, $2)
+            ON CONFLICT (chain, from_block, to_block) DO UPDATE SET updated_at = NOW()
+            RETURNING next_block
+            "#,
+        )
+        .bind(self.chain.to_string())
+        .bind(from as i64)
+        .bind(to as i64)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(next_block as u64)
+    }
+
+    /// Record that every block before `next_block` in the range is done.
+    pub async fn record_progress(&self, from: u64, to: u64, next_block: u64) -> anyhow::Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        record_progress(&mut conn, self.chain, from, to, next_block).await
+    }
+}
+
+async fn record_progress(
+    conn: &mut PgConnection,
+    chain: Chain,
+    from: u64,
+    to: u64,
+    next_block: u64,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        r#"
+        UPDATE backfill_progress SET next_block = $4, updated_at = NOW()
+        WHERE chain = $1 AND from_block = $2 AND to_block = $3
+        "#,
+    )
+    .bind(chain.to_string())
+    .bind(from as i64)
+    .bind(to as i64)
+    .bind(next_block as i64)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
@@ -1,3 +1,9 @@
+pub mod address_history;
+pub mod backfill;
 pub mod poller;
+pub mod prices;
 pub mod registry;
 pub mod reorg;
+pub mod rpc;
+pub mod subscriptions;
+pub mod window;
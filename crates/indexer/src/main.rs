@@ -1,10 +1,31 @@
-use alloy::providers::ProviderBuilder;
+//! FlareEmissary Indexer
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Live indexing of every chain in INDEXER_CHAINS, each from its stored cursor
+//! # (or the chain tip on first start)
+//! cargo run --bin flare-indexer
+//!
+//! # Backfill a historical range, resumable, alongside live indexing
+//! cargo run --bin flare-indexer -- backfill --from <BLOCK> --to <BLOCK> [--chain coston2]
+//!
+//! # Print every contract in the chain's FlareContractRegistry, marking the watched ones
+//! cargo run --bin flare-indexer -- registry dump [--chain coston2]
+//! ```
 
-use flare_common::config::AppConfig;
+use alloy::primitives::Address;
+use tracing::Instrument;
+
+use flare_common::config::{AppConfig, ChainConfig};
 use flare_common::db;
 use flare_common::types::Chain;
+use flare_indexer::address_history;
+use flare_indexer::backfill::Backfiller;
 use flare_indexer::poller::BlockPoller;
-use flare_indexer::registry::FlareContractRegistry;
+use flare_indexer::registry::{self, FlareContractRegistry, ResolvedAddresses};
+use flare_indexer::rpc::{FailoverConfig, FailoverTransport};
+use flare_indexer::subscriptions;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -19,9 +40,30 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("FlareEmissary Indexer starting...");
 
+    let command = Command::parse(std::env::args().skip(1))?;
+
     // Load configuration
     let config = AppConfig::from_env()?;
 
+    if let Some(addr) = &config.indexer_metrics_addr {
+        metrics_exporter_prometheus::PrometheusBuilder::new()
+            .with_http_listener(addr.parse::<std::net::SocketAddr>()?)
+            .install()?;
+        tracing::info!(addr = %addr, "Prometheus metrics exporter listening");
+    }
+
+    let failover = FailoverConfig {
+        health_check_interval: std::time::Duration::from_secs(
+            config.indexer_rpc_health_interval_secs,
+        ),
+        max_lag_blocks: config.indexer_rpc_max_lag_blocks,
+        ..FailoverConfig::default()
+    };
+
+    if let Command::RegistryDump { chain } = command {
+        return dump_registry(&chain_config(&config, chain)?, &failover).await;
+    }
+
     // Connect to database
     let pool = db::create_pool(&config.database_url, config.db_max_connections).await?;
 
@@ -29,46 +71,91 @@ async fn main() -> anyhow::Result<()> {
     sqlx::migrate!("../../migrations").run(&pool).await?;
     tracing::info!("Database migrations applied");
 
-    // Resolve contract addresses from the on-chain FlareContractRegistry
-    tracing::info!("Resolving contract addresses from FlareContractRegistry...");
-    let provider = ProviderBuilder::new().connect_http(config.flare_rpc_url.parse()?);
-    let registry = FlareContractRegistry::flare();
-    let resolved = match registry.resolve_all(&provider).await {
-        Ok(resolved) => {
-            tracing::info!(
-                count = resolved.len(),
-                "Contract address resolution successful"
-            );
-            resolved.all_addresses()
+    if let Command::Backfill { from, to, chain } = command {
+        let chain_config = chain_config(&config, chain)?;
+        let resolved = resolve_contract_addresses(&chain_config, &failover).await?;
+        let fast_updates_configuration =
+            resolved.get(registry::FAST_UPDATES_CONFIGURATION).copied();
+        let mut contracts: Vec<(String, Address)> = resolved.addresses.into_iter().collect();
+        if !contracts.is_empty() {
+            // Contracts replaced since the range was live are in the address history
+            contracts.extend(address_history::contracts_in_use_since(&pool, chain, from).await?);
         }
-        Err(e) => {
-            tracing::warn!(
-                error = %e,
-                "Failed to resolve contract addresses — falling back to unfiltered log fetching"
-            );
+        let mut addresses: Vec<Address> = contracts.iter().map(|(_, address)| *address).collect();
+        addresses.sort();
+        addresses.dedup();
+        let generic_addresses = if config.indexer_generic_subscriptions {
+            subscriptions::generic_event_addresses(&pool, chain).await?
+        } else {
             Vec::new()
+        };
+        let backfiller = Backfiller::new(
+            chain_config.rpc_url.clone(),
+            chain,
+            pool,
+            config.indexer_backfill_chunk_size,
+        )
+        .with_contract_addresses(addresses)
+        .with_generic_addresses(generic_addresses)
+        .with_fast_updates_configuration(fast_updates_configuration)
+        .with_fasset_symbols(registry::fasset_symbols(
+            contracts.iter().map(|(name, address)| (name, address)),
+        ))
+        .with_rpc_failover(chain_config.rpc_fallback_urls, failover);
+
+        tokio::select! {
+            result = backfiller.run(from, to) => { result?; }
+            _ = tokio::signal::ctrl_c() => {
+                tracing::info!("Received shutdown signal, backfill progress saved");
+            }
         }
-    };
+        return Ok(());
+    }
 
-    // Start block poller for Flare mainnet
-    let mut poller = BlockPoller::new(
-        config.flare_rpc_url.clone(),
-        config.indexer_poll_interval_ms,
-        Chain::Flare,
-        pool,
-        config.indexer_reorg_window,
-    )
-    .with_contract_addresses(resolved);
+    // One block poller per chain, each with its own RPC endpoints, cursor and reorg window
+    let mut pollers = tokio::task::JoinSet::new();
+    for chain_config in &config.indexer_chains {
+        let span = tracing::info_span!("poller", chain = %chain_config.chain);
+        let mut poller = BlockPoller::new(
+            chain_config.rpc_url.clone(),
+            chain_config.poll_interval_ms,
+            chain_config.chain,
+            pool.clone(),
+            chain_config.reorg_window,
+        )
+        .with_registry(
+            chain_registry(chain_config)?,
+            config.indexer_registry_refresh_blocks,
+        )
+        .with_rpc_failover(chain_config.rpc_fallback_urls.clone(), failover.clone())
+        .with_catch_up(
+            config.indexer_catchup_threshold,
+            config.indexer_catchup_window,
+        );
+        if config.indexer_generic_subscriptions {
+            poller = poller.with_generic_subscriptions(std::time::Duration::from_secs(
+                config.indexer_generic_refresh_secs,
+            ));
+        }
+        let chain = chain_config.chain;
+        pollers.spawn(async move { (chain, poller.run().await) }.instrument(span));
+    }
 
-    tracing::info!("Starting block poller for Flare mainnet");
+    tracing::info!(
+        chains = ?config.indexer_chains.iter().map(|c| c.chain).collect::<Vec<_>>(),
+        "Block pollers started"
+    );
 
-    // Run with graceful shutdown on Ctrl+C
+    // Run with graceful shutdown on Ctrl+C; a failed poller stops the process
     tokio::select! {
-        result = poller.run() => {
-            if let Err(e) = result {
-                tracing::error!(error = %e, "Block poller exited with error");
-                return Err(e);
-            }
+        Some(result) = pollers.join_next() => {
+            let (chain, e) = match result {
+                Ok((chain, Ok(()))) => (chain.to_string(), anyhow::anyhow!("Block poller exited unexpectedly")),
+                Ok((chain, Err(e))) => (chain.to_string(), e),
+                Err(e) => ("unknown".to_string(), e.into()),
+            };
+            tracing::error!(chain = %chain, error = %e, "Block poller exited with error");
+            return Err(e);
         }
         _ = tokio::signal::ctrl_c() => {
             tracing::info!("Received shutdown signal, stopping gracefully...");
@@ -78,3 +165,158 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("FlareEmissary Indexer stopped.");
     Ok(())
 }
+
+/// Indexer mode selected on the command line.
+enum Command {
+    /// Follow the chain tip
+    Live,
+    /// Index a historical block range of one chain and exit
+    Backfill { from: u64, to: u64, chain: Chain },
+    /// Print the chain's registry contents and exit
+    RegistryDump { chain: Chain },
+}
+
+impl Command {
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        const USAGE: &str = "usage: flare-indexer [backfill --from <BLOCK> --to <BLOCK> [--chain <CHAIN>] | registry dump [--chain <CHAIN>]]";
+
+        match args.next().as_deref() {
+            None => Ok(Command::Live),
+            Some("backfill") => {
+                let (mut from, mut to, mut chain) = (None, None, Chain::Flare);
+                while let Some(flag) = args.next() {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow::anyhow!("{} requires a value\n{}", flag, USAGE))?;
+                    let block = || -> anyhow::Result<u64> {
+                        value
+                            .parse()
+                            .map_err(|_| anyhow::anyhow!("{} must be a block number", flag))
+                    };
+                    match flag.as_str() {
+                        "--from" => from = Some(block()?),
+                        "--to" => to = Some(block()?),
+                        "--chain" => chain = value.parse().map_err(|e| anyhow::anyhow!("{}", e))?,
+                        other => anyhow::bail!("unknown argument '{}'\n{}", other, USAGE),
+                    }
+                }
+                match (from, to) {
+                    (Some(from), Some(to)) => Ok(Command::Backfill { from, to, chain }),
+                    _ => anyhow::bail!("backfill requires --from and --to\n{}", USAGE),
+                }
+            }
+            Some("registry") => {
+                if args.next().as_deref() != Some("dump") {
+                    anyhow::bail!("unknown registry command\n{}", USAGE);
+                }
+                let chain = match (args.next().as_deref(), args.next()) {
+                    (None, _) => Chain::Flare,
+                    (Some("--chain"), Some(value)) => {
+                        value.parse().map_err(|e| anyhow::anyhow!("{}", e))?
+                    }
+                    _ => anyhow::bail!("registry dump only takes --chain <CHAIN>\n{}", USAGE),
+                };
+                Ok(Command::RegistryDump { chain })
+            }
+            Some(other) => anyhow::bail!("unknown command '{}'\n{}", other, USAGE),
+        }
+    }
+}
+
+/// Resolve a chain's contract addresses from its on-chain FlareContractRegistry.
+///
+/// Returns no addresses (unfiltered log fetching) if resolution fails.
+async fn resolve_contract_addresses(
+    chain_config: &ChainConfig,
+    failover: &FailoverConfig,
+) -> anyhow::Result<ResolvedAddresses> {
+    tracing::info!(
+        chain = %chain_config.chain,
+        "Resolving contract addresses from FlareContractRegistry..."
+    );
+    let mut rpc_urls = vec![chain_config.rpc_url.clone()];
+    rpc_urls.extend(chain_config.rpc_fallback_urls.iter().cloned());
+    let provider =
+        FailoverTransport::new(chain_config.chain, rpc_urls, failover.clone())?.provider();
+    Ok(
+        match chain_registry(chain_config)?.resolve_all(&provider).await {
+            Ok(resolved) => {
+                tracing::info!(
+                    count = resolved.len(),
+                    "Contract address resolution successful"
+                );
+                resolved
+            }
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    "Failed to resolve contract addresses — falling back to unfiltered log fetching"
+                );
+                ResolvedAddresses::default()
+            }
+        },
+    )
+}
+
+/// The chain's FlareContractRegistry: the configured address or the well-known one.
+fn chain_registry(chain_config: &ChainConfig) -> anyhow::Result<FlareContractRegistry> {
+    Ok(match &chain_config.registry_address {
+        Some(address) => FlareContractRegistry::at(address.parse().map_err(|_| {
+            anyhow::anyhow!(
+                "{}_REGISTRY_ADDRESS is not a valid address",
+                chain_config.chain.to_string().to_uppercase()
+            )
+        })?),
+        None => FlareContractRegistry::for_chain(chain_config.chain),
+    })
+}
+
+/// Settings for `chain` from INDEXER_CHAINS, or read from its own environment
+/// variables so chains that aren't indexed live can still be inspected and backfilled.
+fn chain_config(config: &AppConfig, chain: Chain) -> anyhow::Result<ChainConfig> {
+    match config.indexer_chains.iter().find(|c| c.chain == chain) {
+        Some(chain_config) => Ok(chain_config.clone()),
+        None => ChainConfig::from_vars(
+            chain,
+            |name| std::env::var(name).ok(),
+            config.indexer_poll_interval_ms,
+            config.indexer_reorg_window,
+        ),
+    }
+}
+
+/// Print every contract registered in the chain's FlareContractRegistry, marking
+/// the ones the indexer watches with `*`.
+async fn dump_registry(
+    chain_config: &ChainConfig,
+    failover: &FailoverConfig,
+) -> anyhow::Result<()> {
+    let mut rpc_urls = vec![chain_config.rpc_url.clone()];
+    rpc_urls.extend(chain_config.rpc_fallback_urls.iter().cloned());
+    let provider =
+        FailoverTransport::new(chain_config.chain, rpc_urls, failover.clone())?.provider();
+    let registry = chain_registry(chain_config)?;
+    let all = registry.all_contracts(&provider, None).await?;
+    let watched = registry.contract_names();
+
+    println!(
+        "FlareContractRegistry {} on {}: {} contracts (* = watched)",
+        registry.registry_address(),
+        chain_config.chain,
+        all.len()
+    );
+    let width = all
+        .keys()
+        .chain(watched)
+        .map(String::len)
+        .max()
+        .unwrap_or(0);
+    for (name, address) in &all {
+        let marker = if watched.contains(name) { '*' } else { ' ' };
+        println!("{} {:<width$}  {}", marker, name, address);
+    }
+    for name in watched.iter().filter(|name| !all.contains_key(*name)) {
+        println!("* {:<width$}  (not registered)", name);
+    }
+    Ok(())
+}
@@ -1,19 +1,81 @@
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
-use alloy::primitives::Address;
-use alloy::providers::{Provider, ProviderBuilder};
-use alloy::rpc::types::Filter;
-use chrono::{TimeZone, Utc};
-use sqlx::PgPool;
+use alloy::eips::BlockNumberOrTag;
+use alloy::primitives::{Address, B256};
+use alloy::providers::Provider;
+use alloy::rpc::client::BatchRequest;
+use alloy::rpc::types::{Filter, Header, Log};
+use chrono::{DateTime, TimeZone, Utc};
+use sqlx::{PgConnection, PgPool};
 
 use flare_common::types::{Chain, DecodedEvent};
 use flare_decoders::DecoderRegistry;
 
-use crate::reorg::ReorgDetector;
+use crate::address_history;
+use crate::prices;
+use crate::registry::{self, ADDRESS_UPDATER, FAST_UPDATES_CONFIGURATION, FlareContractRegistry};
+use crate::reorg::{BlockRef, Reorg, ReorgDetector};
+use crate::rpc::{FailoverConfig, FailoverTransport};
+use crate::subscriptions;
+use crate::window::{LogWindow, is_range_error};
+
+/// Lag (in blocks) above which the poller switches to ranged catch-up by default.
+pub const DEFAULT_CATCH_UP_THRESHOLD: u64 = 50;
+
+/// Default maximum blocks per ranged `eth_getLogs` while catching up.
+pub const DEFAULT_CATCH_UP_WINDOW: u64 = 500;
+
+/// Headers requested per JSON-RPC batch.
+const HEADER_BATCH_SIZE: u64 = 100;
+
+/// How often the chain tip is re-read to measure lag.
+const TIP_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Stored blocks loaded per query while searching for the fork point of a deep reorg.
+const FORK_SEARCH_PAGE: i64 = 100;
+
+/// Result of indexing a block or block range.
+enum PollOutcome {
+    /// Decoded events and headers for the block or range up to `last_block`
+    Indexed {
+        events: Vec<DecodedEvent>,
+        blocks: Vec<BlockRef>,
+        /// Last block indexed; a range stops early at a registry update
+        last_block: u64,
+    },
+    /// A reorg was found; indexing must resume from its fork block
+    Reorged(Reorg),
+    /// The provider rejected the range; the window was shrunk, retry
+    Shrunk,
+}
 
 /// Block poller that continuously fetches new blocks and logs from the chain.
+///
+/// Near the tip it indexes one block at a time. When more than the catch-up
+/// threshold behind, it switches to ranged `eth_getLogs` over an adaptive
+/// `LogWindow`, with headers fetched in JSON-RPC batches for timestamps and reorg
+/// checks, and drops back to per-block mode once caught up.
+///
+/// With a registry configured, the log filter is resolved from it at startup and
+/// re-resolved every `registry_refresh_blocks` blocks, or right after a log from the
+/// registry or its `AddressUpdater` is indexed. Changed addresses are swapped into
+/// the filter without a restart and recorded in `contract_address_history`.
+///
+/// FTSO feed events are also written to `ftso_price_ticks`. The fast update feed IDs
+/// are re-read from `FastUpdatesConfiguration` with every registry resolution, and
+/// right after it emits a log (a feed was added, changed or removed).
+///
+/// With generic subscriptions enabled, addresses that have an active
+/// `generic_event` subscription are added to the filter and their unmatched logs
+/// are stored as generic events. The set is re-read from the database every
+/// refresh interval, so a new subscription is picked up from the block being
+/// indexed at that point (older logs need a backfill).
 pub struct BlockPoller {
     rpc_url: String,
+    /// Fallback RPC URLs in priority order, used when `rpc_url` fails or lags
+    fallback_rpc_urls: Vec<String>,
+    failover: FailoverConfig,
     poll_interval: Duration,
     chain: Chain,
     pool: PgPool,
@@ -23,6 +85,27 @@ pub struct BlockPoller {
     /// When set, only logs from these addresses are fetched.
     /// When empty, ALL logs for the block are fetched (unfiltered).
     contract_addresses: Vec<Address>,
+    /// Lag in blocks above which ranged catch-up is used
+    catch_up_threshold: u64,
+    log_window: LogWindow,
+    /// Registry the address filter is resolved from; `None` keeps the filter fixed
+    registry: Option<FlareContractRegistry>,
+    /// Blocks between registry re-resolutions (0 = only on registry updates)
+    registry_refresh_blocks: u64,
+    /// Addresses currently resolved from the registry, by contract name
+    resolved: HashMap<String, Address>,
+    /// FAsset symbol of each resolved AssetManager, tagged onto its events
+    fasset_symbols: HashMap<Address, String>,
+    /// Feed ID of each fast update feed index, for price ticks
+    fast_update_feed_ids: Vec<String>,
+    /// Block at which the registry is next re-resolved
+    next_registry_refresh: u64,
+    /// Set when an indexed log came from the registry or its `AddressUpdater`
+    registry_updated: bool,
+    /// Interval between re-reads of `generic_event` subscriptions; `None` disables them
+    generic_refresh: Option<Duration>,
+    /// When `generic_event` subscriptions were last read
+    generic_refreshed_at: Option<Instant>,
 }
 
 impl BlockPoller {
@@ -35,15 +118,43 @@ impl BlockPoller {
     ) -> Self {
         Self {
             rpc_url,
+            fallback_rpc_urls: Vec::new(),
+            failover: FailoverConfig::default(),
             poll_interval: Duration::from_millis(poll_interval_ms),
             chain,
             pool,
             decoders: DecoderRegistry::new(),
             reorg_detector: ReorgDetector::new(reorg_window as usize),
             contract_addresses: Vec::new(),
+            catch_up_threshold: DEFAULT_CATCH_UP_THRESHOLD,
+            log_window: LogWindow::new(DEFAULT_CATCH_UP_WINDOW),
+            registry: None,
+            registry_refresh_blocks: 0,
+            resolved: HashMap::new(),
+            fasset_symbols: HashMap::new(),
+            fast_update_feed_ids: Vec::new(),
+            next_registry_refresh: 0,
+            registry_updated: false,
+            generic_refresh: None,
+            generic_refreshed_at: None,
         }
     }
 
+    /// Add fallback RPC endpoints (highest priority first) and failover tuning.
+    pub fn with_rpc_failover(mut self, fallback_urls: Vec<String>, config: FailoverConfig) -> Self {
+        self.fallback_rpc_urls = fallback_urls;
+        self.failover = config;
+        self
+    }
+
+    /// Configure catch-up: ranged fetching starts when more than `threshold` blocks
+    /// behind the tip, with at most `max_window` blocks per `eth_getLogs`.
+    pub fn with_catch_up(mut self, threshold: u64, max_window: u64) -> Self {
+        self.catch_up_threshold = threshold;
+        self.log_window = LogWindow::new(max_window);
+        self
+    }
+
     /// Set the contract addresses to filter logs by.
     /// Only logs emitted by these contracts will be fetched and decoded.
     pub fn with_contract_addresses(mut self, addresses: Vec<Address>) -> Self {
@@ -57,9 +168,28 @@ impl BlockPoller {
         self
     }
 
+    /// Resolve the log filter from `registry` at startup and keep it current,
+    /// re-resolving every `refresh_blocks` blocks (0 = only on registry updates).
+    pub fn with_registry(mut self, registry: FlareContractRegistry, refresh_blocks: u64) -> Self {
+        self.registry = Some(registry);
+        self.registry_refresh_blocks = refresh_blocks;
+        self
+    }
+
+    /// Also fetch and store the logs of addresses with an active `generic_event`
+    /// subscription, re-reading the subscriptions every `refresh`.
+    pub fn with_generic_subscriptions(mut self, refresh: Duration) -> Self {
+        self.generic_refresh = Some(refresh);
+        self
+    }
+
     /// Start the polling loop. Runs indefinitely until the task is cancelled.
     pub async fn run(&mut self) -> anyhow::Result<()> {
-        let provider = ProviderBuilder::new().connect_http(self.rpc_url.parse()?);
+        let mut rpc_urls = vec![self.rpc_url.clone()];
+        rpc_urls.extend(self.fallback_rpc_urls.iter().cloned());
+        let transport = FailoverTransport::new(self.chain, rpc_urls, self.failover.clone())?;
+        let _health_checks = transport.spawn_health_checks();
+        let provider = transport.provider();
 
         // Determine starting block
         let mut current_block = self.get_last_indexed_block().await?.unwrap_or_else(|| {
@@ -71,8 +201,17 @@ impl BlockPoller {
             let latest = provider.get_block_number().await?;
             current_block = latest;
             tracing::info!(block = current_block, "Starting from latest block");
+        } else {
+            // Restore the reorg window so a reorg across the restart is still caught
+            self.load_reorg_window(current_block).await?;
+            if self.reorg_detector.last_block() == Some(current_block) {
+                current_block += 1;
+            }
         }
 
+        self.refresh_addresses(&provider, current_block).await;
+        self.refresh_generic_addresses(current_block).await;
+
         tracing::info!(
             chain = %self.chain,
             start_block = current_block,
@@ -80,21 +219,113 @@ impl BlockPoller {
             "Block poller started"
         );
 
+        let mut tip = provider.get_block_number().await?;
+        let mut tip_read_at = Instant::now();
+        let mut catching_up = false;
+
         loop {
+            if self.registry.is_some()
+                && (self.registry_updated || current_block >= self.next_registry_refresh)
+            {
+                self.refresh_addresses(&provider, current_block).await;
+            }
+            if let (Some(refresh), Some(refreshed_at)) =
+                (self.generic_refresh, self.generic_refreshed_at)
+                && refreshed_at.elapsed() >= refresh
+            {
+                self.refresh_generic_addresses(current_block).await;
+            }
+
+            if tip_read_at.elapsed() >= TIP_REFRESH_INTERVAL {
+                match provider.get_block_number().await {
+                    Ok(latest) => {
+                        tip = latest;
+                        tip_read_at = Instant::now();
+                    }
+                    Err(e) => tracing::debug!(error = %e, "Failed to refresh chain tip"),
+                }
+            }
+
+            let lag = tip.saturating_sub(current_block);
+            if lag > self.catch_up_threshold {
+                if !catching_up {
+                    tracing::info!(
+                        chain = %self.chain,
+                        block = current_block,
+                        tip,
+                        lag,
+                        "Poller behind chain tip, switching to ranged catch-up"
+                    );
+                    catching_up = true;
+                }
+
+                let to = self.log_window.end(current_block, tip);
+                match self.poll_range(&provider, current_block, to).await {
+                    Ok(PollOutcome::Indexed {
+                        events,
+                        blocks,
+                        last_block,
+                    }) => {
+                        self.commit_blocks(&blocks, &events, last_block).await?;
+                        tracing::debug!(
+                            from = current_block,
+                            to = last_block,
+                            events = events.len(),
+                            remaining = tip.saturating_sub(last_block),
+                            "Indexed block range"
+                        );
+                        current_block = last_block + 1;
+                    }
+                    Ok(PollOutcome::Reorged(reorg)) => {
+                        let reorg = self.locate_fork(&provider, reorg).await?;
+                        self.rewind(&reorg).await?;
+                        current_block = reorg.fork_block;
+                    }
+                    Ok(PollOutcome::Shrunk) => {}
+                    Err(e) => {
+                        tracing::warn!(
+                            from = current_block,
+                            to,
+                            error = %e,
+                            "Range fetch failed, retrying"
+                        );
+                        tokio::time::sleep(self.poll_interval).await;
+                    }
+                }
+                continue;
+            }
+
+            if catching_up {
+                tracing::info!(
+                    chain = %self.chain,
+                    block = current_block,
+                    "Caught up with chain tip, resuming per-block polling"
+                );
+                catching_up = false;
+            }
+
             match self.poll_block(&provider, current_block).await {
-                Ok(events) => {
+                Ok(PollOutcome::Indexed { events, blocks, .. }) => {
                     if !events.is_empty() {
                         tracing::info!(
                             block = current_block,
                             events = events.len(),
                             "Decoded events from block"
                         );
-                        self.persist_events(&events).await?;
                     }
-                    // Always update indexer state, even for blocks with no events
-                    self.update_indexer_state(current_block).await?;
+                    // Always advance the cursor, even for blocks with no events
+                    self.commit_blocks(&blocks, &events, current_block).await?;
                     current_block += 1;
+                    tip = tip.max(current_block);
+                }
+                Ok(PollOutcome::Reorged(reorg)) => {
+                    // Re-index the canonical replacement blocks from the fork point
+                    let reorg = self.locate_fork(&provider, reorg).await?;
+                    self.rewind(&reorg).await?;
+                    current_block = reorg.fork_block;
+                    continue;
                 }
+                Ok(PollOutcome::Shrunk) => continue,
                 Err(e) => {
                     // Block might not exist yet — wait and retry
                     tracing::debug!(
@@ -112,12 +343,115 @@ impl BlockPoller {
         }
     }
 
+    /// Index `from..=to` with one ranged `eth_getLogs` and batched header fetches.
+    ///
+    /// Logs are fetched first so a rejected range leaves the reorg detector untouched.
+    /// A range containing a registry update stops at the update's block: the rest
+    /// is fetched with the re-resolved filter.
+    async fn poll_range(
+        &mut self,
+        provider: &impl Provider,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<PollOutcome> {
+        let filter = self.filter_addresses(Filter::new().from_block(from).to_block(to));
+
+        let mut logs = match provider.get_logs(&filter).await {
+            Ok(logs) => {
+                self.log_window.grow();
+                logs
+            }
+            Err(e) if is_range_error(&e) && self.log_window.shrink() => {
+                tracing::debug!(
+                    from,
+                    to,
+                    error = %e,
+                    window = self.log_window.size(),
+                    "eth_getLogs range rejected, shrinking window"
+                );
+                return Ok(PollOutcome::Shrunk);
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let to = match self.note_registry_updates(&logs) {
+            Some(updated_at) if updated_at < to => {
+                logs.retain(|log| log.block_number.is_none_or(|n| n <= updated_at));
+                tracing::debug!(
+                    from,
+                    to,
+                    updated_at,
+                    "Registry update in range, stopping at its block"
+                );
+                updated_at
+            }
+            _ => to,
+        };
+
+        let headers = fetch_headers(provider, from, to).await?;
+        let hashes: HashMap<u64, _> = headers.iter().map(|h| (h.number, h.hash)).collect();
+        // Logs and headers come from separate requests; make sure both saw the same chain
+        let mismatched = logs
+            .iter()
+            .find(|log| match (log.block_number, log.block_hash) {
+                (Some(number), Some(hash)) => hashes.get(&number) != Some(&hash),
+                _ => false,
+            });
+        if let Some(log) = mismatched {
+            anyhow::bail!(
+                "Logs for block {:?} do not match the fetched header (chain moved mid-fetch)",
+                log.block_number
+            );
+        }
+
+        for header in &headers {
+            if let Some(reorg) = self
+                .reorg_detector
+                .check_and_record(header.number, header.hash, header.parent_hash, provider)
+                .await?
+            {
+                return Ok(PollOutcome::Reorged(reorg));
+            }
+        }
+
+        let timestamps: HashMap<u64, DateTime<Utc>> = headers
+            .iter()
+            .map(|h| {
+                let timestamp = Utc
+                    .timestamp_opt(h.timestamp as i64, 0)
+                    .single()
+                    .unwrap_or_else(Utc::now);
+                (h.number, timestamp)
+            })
+            .collect();
+
+        let events = decode_logs(
+            &self.decoders,
+            &logs,
+            self.chain,
+            &self.fasset_symbols,
+            |block| timestamps.get(&block).copied().unwrap_or_else(Utc::now),
+        );
+        let blocks = headers
+            .iter()
+            .map(|h| BlockRef {
+                number: h.number,
+                hash: h.hash,
+                parent_hash: h.parent_hash,
+            })
+            .collect();
+        Ok(PollOutcome::Indexed {
+            events,
+            blocks,
+            last_block: to,
+        })
+    }
+
     /// Poll a single block: fetch it, check for reorg, fetch logs, decode events.
     async fn poll_block(
         &mut self,
         provider: &impl Provider,
         block_number: u64,
-    ) -> anyhow::Result<Vec<DecodedEvent>> {
+    ) -> anyhow::Result<PollOutcome> {
         // Fetch block header for hash and timestamp
         let block = provider
             .get_block_by_number(block_number.into())
@@ -132,97 +466,303 @@ impl BlockPoller {
             .unwrap_or_else(Utc::now);
 
         // Check for reorg (pass parent_hash to avoid a second RPC call)
-        if let Some(reorg_block) = self
+        if let Some(reorg) = self
             .reorg_detector
             .check_and_record(block_number, block_hash, parent_hash, provider)
             .await?
         {
-            tracing::warn!(
-                reorg_at = reorg_block,
-                current = block_number,
-                "Reorg detected! Rolling back events."
-            );
-            self.rollback_events_from(reorg_block).await?;
-            return Ok(vec![]);
+            return Ok(PollOutcome::Reorged(reorg));
         }
 
         // Fetch logs for this block (filtered by contract addresses if available)
-        let mut filter = Filter::new()
-            .from_block(block_number)
-            .to_block(block_number);
+        let filter = self.filter_addresses(
+            Filter::new()
+                .from_block(block_number)
+                .to_block(block_number),
+        );
+
+        let logs = provider.get_logs(&filter).await?;
+        self.note_registry_updates(&logs);
+        let events = decode_logs(
+            &self.decoders,
+            &logs,
+            self.chain,
+            &self.fasset_symbols,
+            |_| block_timestamp,
+        );
+
+        Ok(PollOutcome::Indexed {
+            events,
+            blocks: vec![BlockRef {
+                number: block_number,
+                hash: block_hash,
+                parent_hash,
+            }],
+            last_block: block_number,
+        })
+    }
 
-        if !self.contract_addresses.is_empty() {
-            filter = filter.address(self.contract_addresses.clone());
+    /// Restrict `filter` to the watched contracts and generic-subscribed addresses.
+    /// Without contract addresses every log is fetched, so nothing needs adding.
+    fn filter_addresses(&self, filter: Filter) -> Filter {
+        if self.contract_addresses.is_empty() {
+            return filter;
         }
+        let mut addresses = self.contract_addresses.clone();
+        addresses.extend(self.decoders.generic_addresses().iter().copied());
+        filter.address(addresses)
+    }
 
-        let logs = provider.get_logs(&filter).await?;
+    /// Re-read the addresses with an active `generic_event` subscription and apply
+    /// the generic decoder to them, effective from `next_block`.
+    ///
+    /// On failure the current set is kept and retried after the next interval.
+    async fn refresh_generic_addresses(&mut self, next_block: u64) {
+        if self.generic_refresh.is_none() {
+            return;
+        }
+        self.generic_refreshed_at = Some(Instant::now());
 
-        if logs.is_empty() {
-            return Ok(vec![]);
+        let addresses = match subscriptions::generic_event_addresses(&self.pool, self.chain).await {
+            Ok(addresses) => addresses,
+            Err(e) => {
+                tracing::warn!(
+                    chain = %self.chain,
+                    error = %e,
+                    "Failed to load generic_event subscriptions, keeping the current addresses"
+                );
+                return;
+            }
+        };
+        if addresses.len() == self.decoders.generic_addresses().len()
+            && addresses
+                .iter()
+                .all(|a| self.decoders.generic_addresses().contains(a))
+        {
+            return;
         }
 
-        // Decode each log
-        let mut events = Vec::new();
-        for log in &logs {
-            let tx_hash = log
-                .transaction_hash
-                .map(|h| format!("{:#x}", h))
-                .unwrap_or_default();
+        metrics::gauge!("flare_indexer_generic_addresses", "chain" => self.chain.to_string())
+            .set(addresses.len() as f64);
+        tracing::info!(
+            chain = %self.chain,
+            count = addresses.len(),
+            from_block = next_block,
+            "Generic event addresses updated from subscriptions"
+        );
+        self.decoders.set_generic_addresses(addresses);
+    }
 
-            let log_index = log.log_index;
+    /// Flag a registry re-resolution if any log came from the registry, its
+    /// `AddressUpdater` or the `FastUpdatesConfiguration`, returning the first
+    /// block with such a log.
+    fn note_registry_updates(&mut self, logs: &[Log]) -> Option<u64> {
+        let registry = self.registry.as_ref()?;
+        let updater = self.resolved.get(ADDRESS_UPDATER);
+        let feed_configuration = self.resolved.get(FAST_UPDATES_CONFIGURATION);
+        let updated_at = logs
+            .iter()
+            .filter(|log| {
+                log.address() == registry.registry_address()
+                    || Some(&log.address()) == updater
+                    || Some(&log.address()) == feed_configuration
+            })
+            .filter_map(|log| log.block_number)
+            .min()?;
+        self.registry_updated = true;
+        Some(updated_at)
+    }
 
-            if let Some(mut event) =
-                self.decoders
-                    .decode(&log.inner, block_number, block_timestamp, self.chain)
-            {
-                event.tx_hash = tx_hash;
-                event.log_index = log_index;
-                events.push(event);
+    /// Re-resolve the registry as of the last indexed block and swap any changed
+    /// addresses into the log filter, effective from `next_block`.
+    ///
+    /// On failure the current filter is kept and retried at the next refresh. The
+    /// first resolution falls back to the latest state if the node no longer has
+    /// the last indexed block's state.
+    async fn refresh_addresses(&mut self, provider: &(impl Provider + Clone), next_block: u64) {
+        let Some(registry) = &self.registry else {
+            return;
+        };
+        self.registry_updated = false;
+        self.next_registry_refresh = match self.registry_refresh_blocks {
+            0 => u64::MAX,
+            blocks => next_block.saturating_add(blocks),
+        };
+
+        let mut result = registry
+            .resolve_at(provider, Some(next_block.saturating_sub(1)))
+            .await;
+        if self.resolved.is_empty() && !matches!(&result, Ok(r) if !r.is_empty()) {
+            result = registry.resolve_all(provider).await;
+        }
+        let resolved = match result {
+            Ok(resolved) if !resolved.is_empty() => resolved,
+            Ok(_) => {
+                tracing::warn!(
+                    chain = %self.chain,
+                    block = next_block,
+                    "Registry resolved no contracts, keeping the current address filter"
+                );
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(
+                    chain = %self.chain,
+                    block = next_block,
+                    error = %e,
+                    "Registry re-resolution failed, keeping the current address filter"
+                );
+                return;
             }
+        };
+        metrics::counter!("flare_indexer_registry_refreshes_total", "chain" => self.chain.to_string())
+            .increment(1);
+
+        // The filter is swapped even if recording fails: missing logs is worse than
+        // a gap in the history
+        match address_history::record_resolution(&self.pool, self.chain, next_block, &resolved)
+            .await
+        {
+            Ok(changes) => {
+                for change in changes.iter().filter(|c| c.previous.is_some()) {
+                    metrics::counter!(
+                        "flare_indexer_contract_address_changes_total",
+                        "chain" => self.chain.to_string(),
+                        "contract" => change.contract_name.clone()
+                    )
+                    .increment(1);
+                    tracing::info!(
+                        chain = %self.chain,
+                        contract = %change.contract_name,
+                        previous = ?change.previous,
+                        address = %change.address,
+                        from_block = next_block,
+                        "Contract address changed in registry, updating log filter"
+                    );
+                }
+            }
+            Err(e) => tracing::warn!(
+                chain = %self.chain,
+                error = %e,
+                "Failed to record contract address history"
+            ),
         }
 
-        Ok(events)
+        // Contracts that failed to resolve this time keep their previous address
+        let mut merged = self.resolved.clone();
+        merged.extend(resolved.addresses);
+        if let Some(configuration) = merged.get(FAST_UPDATES_CONFIGURATION) {
+            match registry::fast_update_feed_ids(
+                provider,
+                *configuration,
+                Some(next_block.saturating_sub(1)),
+            )
+            .await
+            {
+                Ok(feed_ids) => {
+                    if feed_ids != self.fast_update_feed_ids {
+                        tracing::info!(
+                            chain = %self.chain,
+                            feeds = feed_ids.iter().filter(|id| !id.is_empty()).count(),
+                            from_block = next_block,
+                            "Fast update feed IDs updated"
+                        );
+                    }
+                    self.fast_update_feed_ids = feed_ids;
+                }
+                Err(e) => tracing::warn!(
+                    chain = %self.chain,
+                    error = %e,
+                    "Failed to read fast update feed IDs, keeping the current ones"
+                ),
+            }
+        }
+        if merged == self.resolved {
+            return;
+        }
+        self.resolved = merged;
+        self.fasset_symbols = registry::fasset_symbols(&self.resolved);
+        let mut addresses: Vec<Address> = self
+            .resolved
+            .values()
+            .copied()
+            .chain(std::iter::once(registry.registry_address()))
+            .collect();
+        addresses.sort();
+        addresses.dedup();
+        self.contract_addresses = addresses;
+        tracing::info!(
+            chain = %self.chain,
+            count = self.contract_addresses.len(),
+            from_block = next_block,
+            "Log filter updated from registry"
+        );
     }
 
     /// Persist decoded events to the database.
     pub async fn persist_events(&self, events: &[DecodedEvent]) -> anyhow::Result<()> {
-        for event in events {
-            sqlx::query(
-                r#"
-                INSERT INTO indexed_events (tx_hash, log_index, block_number, block_timestamp, chain, address, event_type, decoded_data, is_reorged)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, false)
-                ON CONFLICT (tx_hash, log_index) DO NOTHING
-                "#,
-            )
-            .bind(&event.tx_hash)
-            .bind(event.log_index.map(|i| i as i64))
-            .bind(event.block_number as i64)
-            .bind(event.block_timestamp)
-            .bind(event.chain.to_string())
-            .bind(&event.address)
-            .bind(event.event_type.to_string())
-            .bind(&event.decoded_data)
-            .execute(&self.pool)
-            .await?;
-        }
-
+        let mut conn = self.pool.acquire().await?;
+        insert_events(&mut conn, events, false).await?;
         Ok(())
     }
 
     /// Update the indexer's last processed block number.
     pub async fn update_indexer_state(&self, block_number: u64) -> anyhow::Result<()> {
-        sqlx::query(
+        let mut conn = self.pool.acquire().await?;
+        set_indexer_state(&mut conn, self.chain, block_number).await
+    }
+
+    /// Store the headers, events and price ticks of every block up to `last_block` and
+    /// advance the cursor to it in one transaction, so the cursor never runs ahead of
+    /// (or behind) the stored events. A crash before commit leaves all three untouched, and the
+    /// blocks are simply indexed again.
+    pub async fn commit_blocks(
+        &self,
+        blocks: &[BlockRef],
+        events: &[DecodedEvent],
+        last_block: u64,
+    ) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        insert_blocks(&mut tx, self.chain, blocks).await?;
+        insert_events(&mut tx, events, false).await?;
+        prices::insert_price_ticks(
+            &mut tx,
+            &prices::price_ticks(events, &self.fast_update_feed_ids),
+        )
+        .await?;
+        set_indexer_state(&mut tx, self.chain, last_block).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Seed the reorg detector with the canonical blocks stored up to `last_block`.
+    async fn load_reorg_window(&mut self, last_block: u64) -> anyhow::Result<()> {
+        let rows: Vec<(i64, String)> = sqlx::query_as(
             r#"
-            INSERT INTO indexer_state (chain, last_block)
-            VALUES ($1, $2)
-            ON CONFLICT (chain) DO UPDATE SET last_block = $2, updated_at = NOW()
+            SELECT block_number, block_hash FROM indexed_blocks
+            WHERE chain = $1 AND is_canonical AND block_number <= $2
+            ORDER BY block_number DESC
+            LIMIT $3
             "#,
         )
         .bind(self.chain.to_string())
-        .bind(block_number as i64)
-        .execute(&self.pool)
+        .bind(last_block as i64)
+        .bind(self.reorg_detector.max_size() as i64)
+        .fetch_all(&self.pool)
         .await?;
 
+        let mut blocks = Vec::with_capacity(rows.len());
+        for (number, hash) in rows {
+            blocks.push((number as u64, hash.parse()?));
+        }
+        self.reorg_detector.seed(blocks);
+
+        tracing::info!(
+            chain = %self.chain,
+            blocks = self.reorg_detector.window_size(),
+            last = ?self.reorg_detector.last_block(),
+            "Restored reorg window from indexed blocks"
+        );
         Ok(())
     }
 
@@ -239,15 +779,383 @@ impl BlockPoller {
 
     /// Mark events from a reorged block as invalid.
     pub async fn rollback_events_from(&self, from_block: u64) -> anyhow::Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        let events = flag_reorged(&mut conn, self.chain, from_block).await?;
+
+        tracing::info!(from_block, events, "Rolled back events from reorged blocks");
+        Ok(())
+    }
+
+    /// Find the real fork point of a reorg deeper than the detector window.
+    ///
+    /// Walks the canonical blocks stored below the window, newest first, and compares
+    /// each with the provider's block at that height: the fork is right after the
+    /// newest one that still matches. Fails, halting the poller, when none of the
+    /// stored blocks match, since any rewind point would be a guess.
+    async fn locate_fork(&self, provider: &impl Provider, reorg: Reorg) -> anyhow::Result<Reorg> {
+        if !reorg.beyond_window {
+            return Ok(reorg);
+        }
+
+        let mut below = reorg.fork_block;
+        loop {
+            let rows: Vec<(i64, String)> = sqlx::query_as(
+                r#"
+                SELECT block_number, block_hash FROM indexed_blocks
+                WHERE chain = $1 AND is_canonical AND block_number < $2
+                ORDER BY block_number DESC
+                LIMIT $3
+                "#,
+            )
+            .bind(self.chain.to_string())
+            .bind(below as i64)
+            .bind(FORK_SEARCH_PAGE)
+            .fetch_all(&self.pool)
+            .await?;
+            let Some((oldest, _)) = rows.last() else {
+                break;
+            };
+            below = *oldest as u64;
+
+            for (number, hash) in rows {
+                let (number, hash) = (number as u64, hash.parse::<B256>()?);
+                let block = provider.get_block_by_number(number.into()).await?;
+                if block.is_some_and(|b| b.header.hash == hash) {
+                    tracing::warn!(
+                        chain = %self.chain,
+                        window_start = reorg.fork_block,
+                        fork_block = number + 1,
+                        "Reorg deeper than the reorg window, fork point found in stored blocks"
+                    );
+                    return Ok(Reorg {
+                        fork_block: number + 1,
+                        ..reorg
+                    });
+                }
+            }
+        }
+
+        metrics::counter!("flare_indexer_deep_reorgs_total", "chain" => self.chain.to_string())
+            .increment(1);
+        tracing::error!(
+            chain = %self.chain,
+            window_start = reorg.fork_block,
+            detected_at = reorg.detected_at,
+            "Reorg deeper than every stored block: the fork point is unknown, halting the \
+             poller. Re-index from a block before the fork (flare-indexer backfill)."
+        );
+        anyhow::bail!(
+            "reorg detected at block {} predates the stored blocks of {}",
+            reorg.detected_at,
+            self.chain
+        )
+    }
+
+    /// Roll back to the fork point of `reorg` so the canonical blocks get indexed.
+    ///
+    /// Events from the fork block on are flagged as reorged and their price ticks
+    /// deleted, the cursor moves back to the block before the fork and the reorg is
+    /// logged to `chain_reorgs`, all in one transaction. Events that reappear on the canonical chain are
+    /// stored again when their blocks are indexed again (see `insert_events`).
+    ///
+    /// A reorg deeper than the detector window is rolled back to the fork point
+    /// found by `locate_fork` and still logged as `beyond_window`.
+    pub async fn rewind(&self, reorg: &Reorg) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let events = flag_reorged(&mut tx, self.chain, reorg.fork_block).await?;
+        // Ticks are re-derived when the canonical blocks are indexed
+        prices::delete_price_ticks_from(&mut tx, self.chain, reorg.fork_block).await?;
+        // So are the engine's vault snapshots, from the canonical events
+        sqlx::query("DELETE FROM agent_vault_snapshots WHERE chain = $1 AND block_number >= $2")
+            .bind(self.chain.to_string())
+            .bind(reorg.fork_block as i64)
+            .execute(&mut *tx)
+            .await?;
         sqlx::query(
-            "UPDATE indexed_events SET is_reorged = true WHERE block_number >= $1 AND chain = $2",
+            r#"
+            UPDATE indexed_blocks SET is_canonical = false
+            WHERE chain = $1 AND block_number >= $2 AND is_canonical
+            "#,
         )
-        .bind(from_block as i64)
         .bind(self.chain.to_string())
-        .execute(&self.pool)
+        .bind(reorg.fork_block as i64)
+        .execute(&mut *tx)
         .await?;
+        set_indexer_state(&mut tx, self.chain, reorg.fork_block.saturating_sub(1)).await?;
+        sqlx::query(
+            r#"
+            INSERT INTO chain_reorgs (chain, fork_block, detected_at_block, beyond_window, events_reorged)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(self.chain.to_string())
+        .bind(reorg.fork_block as i64)
+        .bind(reorg.detected_at as i64)
+        .bind(reorg.beyond_window)
+        .bind(events as i64)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
 
-        tracing::info!(from_block, "Rolled back events from reorged blocks");
+        let chain = self.chain.to_string();
+        metrics::counter!("flare_indexer_reorgs_total", "chain" => chain.clone()).increment(1);
+        metrics::histogram!("flare_indexer_reorg_depth_blocks", "chain" => chain.clone())
+            .record((reorg.detected_at - reorg.fork_block) as f64);
+
+        if reorg.beyond_window {
+            metrics::counter!("flare_indexer_deep_reorgs_total", "chain" => chain).increment(1);
+            tracing::error!(
+                chain = %self.chain,
+                fork_block = reorg.fork_block,
+                detected_at = reorg.detected_at,
+                events_reorged = events,
+                "Reorg deeper than the reorg window, re-indexing from the stored fork point. \
+                 Consider raising INDEXER_REORG_WINDOW."
+            );
+        } else {
+            tracing::warn!(
+                chain = %self.chain,
+                fork_block = reorg.fork_block,
+                detected_at = reorg.detected_at,
+                events_reorged = events,
+                "Reorg detected, re-indexing canonical blocks from fork point"
+            );
+        }
         Ok(())
     }
 }
+
+/// Decode raw RPC logs, keeping only those a registered decoder recognises.
+///
+/// `block_timestamp` maps a log's block number to its block time. Events emitted
+/// by an AssetManager in `fasset_symbols` get its symbol as `decoded_data.fasset`.
+pub(crate) fn decode_logs(
+    decoders: &DecoderRegistry,
+    logs: &[Log],
+    chain: Chain,
+    fasset_symbols: &HashMap<Address, String>,
+    block_timestamp: impl Fn(u64) -> DateTime<Utc>,
+) -> Vec<DecodedEvent> {
+    let mut events = Vec::new();
+    for log in logs {
+        let Some(block_number) = log.block_number else {
+            continue;
+        };
+        let tx_hash = log
+            .transaction_hash
+            .map(|h| format!("{:#x}", h))
+            .unwrap_or_default();
+
+        if let Some(mut event) = decoders.decode(
+            &log.inner,
+            block_number,
+            block_timestamp(block_number),
+            chain,
+        ) {
+            event.tx_hash = tx_hash;
+            event.log_index = log.log_index;
+            if let Some(symbol) = fasset_symbols.get(&log.address())
+                && let Some(data) = event.decoded_data.as_object_mut()
+            {
+                data.insert("fasset".to_string(), symbol.clone().into());
+            }
+            events.push(event);
+        }
+    }
+    events
+}
+
+/// Insert decoded events in a single multi-row statement, ignoring any already
+/// stored on the canonical chain. Returns the number of rows inserted.
+///
+/// An event stored but flagged as reorged was re-included on the canonical chain:
+/// it gets a new row, and so a new id past the engine's cursor, so it is processed
+/// again. Alerts already raised for the flagged row and not yet retracted move to
+/// the new row, so they are neither retracted nor raised twice.
+/// `backfilled` marks historical events so the engine doesn't alert on them.
+pub(crate) async fn insert_events(
+    conn: &mut PgConnection,
+    events: &[DecodedEvent],
+    backfilled: bool,
+) -> anyhow::Result<u64> {
+    if events.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx_hashes = Vec::with_capacity(events.len());
+    let mut log_indexes = Vec::with_capacity(events.len());
+    let mut block_numbers = Vec::with_capacity(events.len());
+    let mut block_timestamps = Vec::with_capacity(events.len());
+    let mut chains = Vec::with_capacity(events.len());
+    let mut addresses = Vec::with_capacity(events.len());
+    let mut event_types = Vec::with_capacity(events.len());
+    let mut decoded_data = Vec::with_capacity(events.len());
+    // Drop in-batch duplicates so each event is inserted once
+    let mut seen = HashSet::with_capacity(events.len());
+    for event in events {
+        if event.log_index.is_some() && !seen.insert((&event.tx_hash, event.log_index)) {
+            continue;
+        }
+        tx_hashes.push(event.tx_hash.clone());
+        log_indexes.push(event.log_index.map(|i| i as i64));
+        block_numbers.push(event.block_number as i64);
+        block_timestamps.push(event.block_timestamp);
+        chains.push(event.chain.to_string());
+        addresses.push(event.address.clone());
+        event_types.push(event.event_type.to_string());
+        decoded_data.push(event.decoded_data.clone());
+    }
+
+    let (inserted,): (i64,) = sqlx::query_as(
+        r#"
+        WITH inserted AS (
+            INSERT INTO indexed_events (tx_hash, log_index, block_number, block_timestamp, chain, address, event_type, decoded_data, is_reorged, backfilled)
+            SELECT tx_hash, log_index, block_number, block_timestamp, chain, address, event_type, decoded_data, false, $9
+            FROM UNNEST($1::text[], $2::int8[], $3::int8[], $4::timestamptz[], $5::text[], $6::text[], $7::text[], $8::jsonb[])
+                AS t(tx_hash, log_index, block_number, block_timestamp, chain, address, event_type, decoded_data)
+            ON CONFLICT (tx_hash, log_index) WHERE NOT is_reorged DO NOTHING
+            RETURNING id, tx_hash, log_index
+        ), moved AS (
+            UPDATE alerts a SET event_id = i.id
+            FROM indexed_events old, inserted i
+            WHERE a.event_id = old.id
+              AND old.is_reorged
+              AND old.tx_hash = i.tx_hash AND old.log_index = i.log_index
+              AND a.retracted_at IS NULL
+        )
+        SELECT COUNT(*) FROM inserted
+        "#,
+    )
+    .bind(tx_hashes)
+    .bind(log_indexes)
+    .bind(block_numbers)
+    .bind(block_timestamps)
+    .bind(chains)
+    .bind(addresses)
+    .bind(event_types)
+    .bind(decoded_data)
+    .bind(backfilled)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(inserted as u64)
+}
+
+/// Record indexed block headers as canonical.
+///
+/// A hash seen before (the chain switched back to a block it had replaced) is
+/// marked canonical again.
+async fn insert_blocks(
+    conn: &mut PgConnection,
+    chain: Chain,
+    blocks: &[BlockRef],
+) -> anyhow::Result<()> {
+    if blocks.is_empty() {
+        return Ok(());
+    }
+
+    let numbers: Vec<i64> = blocks.iter().map(|b| b.number as i64).collect();
+    let hashes: Vec<String> = blocks.iter().map(|b| format!("{:#x}", b.hash)).collect();
+    let parents: Vec<String> = blocks
+        .iter()
+        .map(|b| format!("{:#x}", b.parent_hash))
+        .collect();
+
+    sqlx::query(
+        r#"
+        INSERT INTO indexed_blocks (chain, block_number, block_hash, parent_hash)
+        SELECT $1, block_number, block_hash, parent_hash
+        FROM UNNEST($2::int8[], $3::text[], $4::text[]) AS t(block_number, block_hash, parent_hash)
+        ON CONFLICT (chain, block_number, block_hash) DO UPDATE SET is_canonical = true
+        "#,
+    )
+    .bind(chain.to_string())
+    .bind(numbers)
+    .bind(hashes)
+    .bind(parents)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Flag every event of `chain` from `from_block` on as reorged. Returns the count.
+async fn flag_reorged(
+    conn: &mut PgConnection,
+    chain: Chain,
+    from_block: u64,
+) -> anyhow::Result<u64> {
+    let result = sqlx::query(
+        r#"
+        UPDATE indexed_events SET is_reorged = true
+        WHERE chain = $1 AND block_number >= $2 AND NOT is_reorged
+        "#,
+    )
+    .bind(chain.to_string())
+    .bind(from_block as i64)
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Set the live cursor for `chain` to `block_number`.
+async fn set_indexer_state(
+    conn: &mut PgConnection,
+    chain: Chain,
+    block_number: u64,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO indexer_state (chain, last_block)
+        VALUES ($1, $2)
+        ON CONFLICT (chain) DO UPDATE SET last_block = $2, updated_at = NOW()
+        "#,
+    )
+    .bind(chain.to_string())
+    .bind(block_number as i64)
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Fetch headers for `from..=to` in JSON-RPC batches, in block order.
+async fn fetch_headers(
+    provider: &impl Provider,
+    from: u64,
+    to: u64,
+) -> anyhow::Result<Vec<Header>> {
+    let numbers: Vec<u64> = (from..=to).collect();
+    fetch_block_headers(provider, &numbers).await
+}
+
+/// Fetch the headers of `numbers` in JSON-RPC batches, in the given order.
+pub(crate) async fn fetch_block_headers(
+    provider: &impl Provider,
+    numbers: &[u64],
+) -> anyhow::Result<Vec<Header>> {
+    let mut headers = Vec::with_capacity(numbers.len());
+    for chunk in numbers.chunks(HEADER_BATCH_SIZE as usize) {
+        let mut batch = BatchRequest::new(provider.client());
+        let mut waiters = Vec::new();
+        for &number in chunk {
+            waiters.push((
+                number,
+                batch.add_call::<_, Option<Header>>(
+                    "eth_getBlockByNumber",
+                    &(BlockNumberOrTag::Number(number), false),
+                )?,
+            ));
+        }
+        batch.send().await?;
+
+        for (number, waiter) in waiters {
+            let header = waiter
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Block {} not found", number))?;
+            headers.push(header);
+        }
+    }
+    Ok(headers)
+}
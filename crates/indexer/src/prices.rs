@@ -0,0 +1,211 @@
+//! FTSO price ticks derived from decoded feed events.
+//!
+//! Every `FtsoFeedPublished` event (anchor feeds, once per voting round) and every
+//! value in a `FastUpdateFeeds` event (block-latency feeds) becomes one row of
+//! `ftso_price_ticks`, written in the same transaction as the events themselves.
+//!
+//! Fast updates list values by feed index. Indices are mapped to feed IDs with the
+//! `FastUpdatesConfiguration.getFeedIds()` list the poller keeps current; values
+//! whose index has no known feed ID are skipped.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+
+use flare_common::types::{Chain, DecodedEvent, EventType};
+
+/// One feed value at one point in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceTick {
+    /// Hex-encoded 21-byte feed ID
+    pub feed_id: String,
+    /// Unscaled feed value; the price is `value * 10^-decimals`
+    pub value: String,
+    pub decimals: i16,
+    /// Voting round (anchor feeds) or voting epoch (fast updates)
+    pub epoch_id: Option<u64>,
+    pub block_number: u64,
+    pub block_timestamp: DateTime<Utc>,
+    pub chain: Chain,
+    pub tx_hash: String,
+    pub log_index: u64,
+}
+
+/// Price ticks carried by feed events among `events`.
+///
+/// `fast_update_feed_ids` lists the hex feed ID of each fast update feed index;
+/// empty slots (removed feeds) are empty strings.
+pub fn price_ticks(events: &[DecodedEvent], fast_update_feed_ids: &[String]) -> Vec<PriceTick> {
+    let mut ticks = Vec::new();
+    for event in events {
+        let data = &event.decoded_data;
+        let tick = |feed_id: &str, value: String, decimals: i64, epoch: &str| PriceTick {
+            feed_id: feed_id.to_string(),
+            value,
+            decimals: decimals as i16,
+            epoch_id: data.get(epoch).and_then(|v| v.as_u64()),
+            block_number: event.block_number,
+            block_timestamp: event.block_timestamp,
+            chain: event.chain,
+            tx_hash: event.tx_hash.clone(),
+            log_index: event.log_index.unwrap_or_default(),
+        };
+
+        match event.event_type {
+            EventType::FtsoFeedPublished => {
+                if let (Some(feed_id), Some(value), Some(decimals)) = (
+                    data.get("feed_id").and_then(|v| v.as_str()),
+                    data.get("value").and_then(|v| v.as_i64()),
+                    data.get("decimals").and_then(|v| v.as_i64()),
+                ) {
+                    ticks.push(tick(
+                        feed_id,
+                        value.to_string(),
+                        decimals,
+                        "voting_round_id",
+                    ));
+                }
+            }
+            EventType::FastUpdateFeeds => {
+                let (Some(values), Some(decimals)) = (
+                    data.get("values").and_then(|v| v.as_array()),
+                    data.get("decimals").and_then(|v| v.as_array()),
+                ) else {
+                    continue;
+                };
+                for (index, (value, decimals)) in values.iter().zip(decimals).enumerate() {
+                    let feed_id = match fast_update_feed_ids.get(index) {
+                        Some(feed_id) if !feed_id.is_empty() => feed_id,
+                        _ => continue,
+                    };
+                    if let (Some(value), Some(decimals)) = (value.as_str(), decimals.as_i64()) {
+                        ticks.push(tick(
+                            feed_id,
+                            value.to_string(),
+                            decimals,
+                            "voting_epoch_id",
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    ticks
+}
+
+/// Insert price ticks in one statement, ignoring any already stored. Returns the
+/// number of rows inserted.
+pub(crate) async fn insert_price_ticks(
+    conn: &mut PgConnection,
+    ticks: &[PriceTick],
+) -> anyhow::Result<u64> {
+    if ticks.is_empty() {
+        return Ok(0);
+    }
+
+    let feed_ids: Vec<&str> = ticks.iter().map(|t| t.feed_id.as_str()).collect();
+    let values: Vec<&str> = ticks.iter().map(|t| t.value.as_str()).collect();
+    let decimals: Vec<i16> = ticks.iter().map(|t| t.decimals).collect();
+    let epoch_ids: Vec<Option<i64>> = ticks.iter().map(|t| t.epoch_id.map(|e| e as i64)).collect();
+    let block_numbers: Vec<i64> = ticks.iter().map(|t| t.block_number as i64).collect();
+    let block_timestamps: Vec<DateTime<Utc>> = ticks.iter().map(|t| t.block_timestamp).collect();
+    let chains: Vec<String> = ticks.iter().map(|t| t.chain.to_string()).collect();
+    let tx_hashes: Vec<&str> = ticks.iter().map(|t| t.tx_hash.as_str()).collect();
+    let log_indexes: Vec<i64> = ticks.iter().map(|t| t.log_index as i64).collect();
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO ftso_price_ticks (feed_id, price, decimals, epoch_id, block_number, block_timestamp, chain, tx_hash, log_index)
+        SELECT feed_id, value * power(10::numeric, -decimals), decimals, epoch_id, block_number, block_timestamp, chain, tx_hash, log_index
+        FROM UNNEST($1::text[], $2::numeric[], $3::int2[], $4::int8[], $5::int8[], $6::timestamptz[], $7::text[], $8::text[], $9::int8[])
+            AS t(feed_id, value, decimals, epoch_id, block_number, block_timestamp, chain, tx_hash, log_index)
+        ON CONFLICT (chain, tx_hash, log_index, feed_id, block_timestamp) DO NOTHING
+        "#,
+    )
+    .bind(feed_ids)
+    .bind(values)
+    .bind(decimals)
+    .bind(epoch_ids)
+    .bind(block_numbers)
+    .bind(block_timestamps)
+    .bind(chains)
+    .bind(tx_hashes)
+    .bind(log_indexes)
+    .execute(conn)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Delete the ticks of `chain` from `from_block` on, after a reorg. Returns the count.
+pub(crate) async fn delete_price_ticks_from(
+    conn: &mut PgConnection,
+    chain: Chain,
+    from_block: u64,
+) -> anyhow::Result<u64> {
+    let result =
+        sqlx::query("DELETE FROM ftso_price_ticks WHERE chain = $1 AND block_number >= $2")
+            .bind(chain.to_string())
+            .bind(from_block as i64)
+            .execute(conn)
+            .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_event(event_type: EventType, decoded_data: serde_json::Value) -> DecodedEvent {
+        DecodedEvent {
+            tx_hash: "0xabc".to_string(),
+            log_index: Some(3),
+            block_number: 42,
+            block_timestamp: Utc::now(),
+            chain: Chain::Flare,
+            address: "0x1234".to_string(),
+            event_type,
+            decoded_data,
+        }
+    }
+
+    #[test]
+    fn test_price_ticks_from_feed_events() {
+        let events = vec![
+            make_event(
+                EventType::FtsoFeedPublished,
+                serde_json::json!({
+                    "voting_round_id": 881_000,
+                    "feed_id": "0x01464c522f55534400000000000000000000000000",
+                    "value": 1_834_567,
+                    "decimals": 7,
+                }),
+            ),
+            make_event(
+                EventType::FastUpdateFeeds,
+                serde_json::json!({
+                    "voting_epoch_id": 440_500,
+                    "values": ["1834600", "6512345678", "99"],
+                    "decimals": [7, 2, 0],
+                }),
+            ),
+            make_event(EventType::RoundFinalized, serde_json::json!({})),
+        ];
+        // Index 1 is a removed feed, index 2 was added after the IDs were read
+        let feed_ids = vec![
+            "0x01464c522f55534400000000000000000000000000".to_string(),
+            String::new(),
+        ];
+
+        let ticks = price_ticks(&events, &feed_ids);
+        assert_eq!(ticks.len(), 2);
+        assert_eq!(ticks[0].value, "1834567");
+        assert_eq!(ticks[0].decimals, 7);
+        assert_eq!(ticks[0].epoch_id, Some(881_000));
+        assert_eq!(ticks[1].feed_id, feed_ids[0]);
+        assert_eq!(ticks[1].value, "1834600");
+        assert_eq!(ticks[1].epoch_id, Some(440_500));
+        assert_eq!(ticks[1].log_index, 3);
+    }
+}
@@ -5,6 +5,14 @@
 //! current address of any enshrined protocol contract. This makes the indexer
 //! resilient to protocol upgrades that deploy new contract addresses.
 //!
+//! Resolution reads the whole registry with a single `getAllContracts()` call and
+//! picks out the configured names, falling back to one `getContractAddressByName`
+//! call per name for registries that don't implement it.
+//!
+//! FAssets deploy one AssetManager per underlying asset. They are enumerated from
+//! the `AssetManagerController` and resolved as `AssetManager:<symbol>` (e.g.
+//! `AssetManager:FXRP`), named after their FAsset token's symbol.
+//!
 //! # Usage
 //!
 //! ```rust,no_run
@@ -23,17 +31,45 @@
 use alloy::primitives::Address;
 use alloy::providers::Provider;
 use alloy::sol;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 
+use flare_common::types::Chain;
+
 // Solidity interface for the FlareContractRegistry.
-// Only the function we need is defined.
+// Only the functions we need are defined.
 sol! {
     #[sol(rpc)]
     interface IFlareContractRegistry {
         /// Returns the address of the contract with the given name.
         /// Returns address(0) if the contract is not registered.
         function getContractAddressByName(string memory _name) external view returns (address);
+
+        /// Returns the names and addresses of every registered contract.
+        function getAllContracts() external view returns (string[] memory _names, address[] memory _addresses);
+    }
+
+    #[sol(rpc)]
+    interface IAssetManagerController {
+        /// Returns every AssetManager the controller manages, one per FAsset.
+        function getAssetManagers() external view returns (address[] memory);
+    }
+
+    #[sol(rpc)]
+    interface IAssetManager {
+        /// Returns the FAsset token this AssetManager mints.
+        function fAsset() external view returns (address);
+    }
+
+    #[sol(rpc)]
+    interface IFastUpdatesConfiguration {
+        /// Returns the feed ID of every fast update feed index; removed feeds are zero.
+        function getFeedIds() external view returns (bytes21[] memory);
+    }
+
+    #[sol(rpc)]
+    interface IERC20Metadata {
+        function symbol() external view returns (string memory);
     }
 }
 
@@ -45,6 +81,41 @@ pub const ASSET_MANAGER: &str = "AssetManager";
 pub const VOTER_WHITELISTER: &str = "VoterWhitelister";
 pub const FTSO_REGISTRY: &str = "FtsoRegistry";
 pub const WNAT: &str = "WNat";
+/// Updates the addresses the registry returns; its logs signal a registry change.
+pub const ADDRESS_UPDATER: &str = "AddressUpdater";
+/// Enumerates the per-FAsset AssetManagers.
+pub const ASSET_MANAGER_CONTROLLER: &str = "AssetManagerController";
+/// Emits FTSO v2 block-latency feed values (`FastUpdateFeeds`).
+pub const FAST_UPDATER: &str = "FastUpdater";
+/// Maps fast update feed indices to feed IDs.
+pub const FAST_UPDATES_CONFIGURATION: &str = "FastUpdatesConfiguration";
+/// Emits FTSO v2 anchor feed values (`FtsoFeedPublished`).
+pub const FTSO_FEED_PUBLISHER: &str = "FtsoFeedPublisher";
+/// Initializes signing policies and relays protocol results (`SigningPolicyInitialized`).
+pub const RELAY: &str = "Relay";
+/// Registers FTSO v2 voters for reward epochs (`VoterRegistered`, `VoterRemoved`).
+pub const VOTER_REGISTRY: &str = "VoterRegistry";
+/// Runs reward epochs and collects voters' signatures (`SigningPolicySigned`, ...).
+pub const FLARE_SYSTEMS_MANAGER: &str = "FlareSystemsManager";
+
+/// Resolved name of the AssetManager for the FAsset `symbol`, e.g. `AssetManager:FXRP`.
+pub fn asset_manager_name(symbol: &str) -> String {
+    format!("{}:{}", ASSET_MANAGER, symbol)
+}
+
+/// FAsset symbol of every AssetManager among `contracts` (name → address pairs).
+pub fn fasset_symbols<'a>(
+    contracts: impl IntoIterator<Item = (&'a String, &'a Address)>,
+) -> HashMap<Address, String> {
+    let prefix = asset_manager_name("");
+    contracts
+        .into_iter()
+        .filter_map(|(name, address)| {
+            let symbol = name.strip_prefix(&prefix)?;
+            Some((*address, symbol.to_string()))
+        })
+        .collect()
+}
 
 /// Well-known address of the FlareContractRegistry on Flare mainnet.
 pub const FLARE_REGISTRY_ADDRESS: &str = "0xaD67FE66660Fb8dFE9d6b1b4240d8650e30F6019";
@@ -52,8 +123,14 @@ pub const FLARE_REGISTRY_ADDRESS: &str = "0xaD67FE66660Fb8dFE9d6b1b4240d8650e30F
 /// Well-known address of the FlareContractRegistry on Songbird.
 pub const SONGBIRD_REGISTRY_ADDRESS: &str = "0xaD67FE66660Fb8dFE9d6b1b4240d8650e30F6019";
 
+/// Well-known address of the FlareContractRegistry on Coston.
+pub const COSTON_REGISTRY_ADDRESS: &str = "0xaD67FE66660Fb8dFE9d6b1b4240d8650e30F6019";
+
+/// Well-known address of the FlareContractRegistry on Coston2.
+pub const COSTON2_REGISTRY_ADDRESS: &str = "0xaD67FE66660Fb8dFE9d6b1b4240d8650e30F6019";
+
 /// Set of resolved contract addresses from the FlareContractRegistry.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ResolvedAddresses {
     /// Map of contract name → resolved address.
     pub addresses: HashMap<String, Address>,
@@ -79,6 +156,11 @@ impl ResolvedAddresses {
     pub fn len(&self) -> usize {
         self.addresses.len()
     }
+
+    /// FAsset symbol of each resolved AssetManager, by address.
+    pub fn fasset_symbols(&self) -> HashMap<Address, String> {
+        fasset_symbols(&self.addresses)
+    }
 }
 
 /// Client for the FlareContractRegistry on-chain contract.
@@ -108,6 +190,23 @@ impl FlareContractRegistry {
         }
     }
 
+    /// Create a registry client for `chain`'s well-known registry with default contract names.
+    pub fn for_chain(chain: Chain) -> Self {
+        let address = match chain {
+            Chain::Flare => FLARE_REGISTRY_ADDRESS,
+            Chain::Songbird => SONGBIRD_REGISTRY_ADDRESS,
+            Chain::Coston => COSTON_REGISTRY_ADDRESS,
+            Chain::Coston2 => COSTON2_REGISTRY_ADDRESS,
+        };
+        Self::at(Address::from_str(address).expect("valid registry address"))
+    }
+
+    /// Create a registry client for a registry deployed at `registry_address`
+    /// with default contract names.
+    pub fn at(registry_address: Address) -> Self {
+        Self::custom(registry_address, default_contract_names())
+    }
+
     /// Create a registry client with a custom registry address and contract names.
     pub fn custom(registry_address: Address, contract_names: Vec<String>) -> Self {
         Self {
@@ -124,11 +223,96 @@ impl FlareContractRegistry {
         &self,
         provider: &(impl Provider + Clone),
     ) -> anyhow::Result<ResolvedAddresses> {
+        self.resolve_at(provider, None).await
+    }
+
+    /// Resolve all configured contract names as of `block` (the latest block when `None`).
+    ///
+    /// Resolving at an older block needs a node that still has that block's state.
+    pub async fn resolve_at(
+        &self,
+        provider: &(impl Provider + Clone),
+        block: Option<u64>,
+    ) -> anyhow::Result<ResolvedAddresses> {
+        let mut addresses = match self.all_contracts(provider, block).await {
+            Ok(all) => select_contracts(&self.contract_names, &all),
+            Err(e) => {
+                tracing::warn!(
+                    error = %e,
+                    "getAllContracts failed, resolving contract names one by one"
+                );
+                self.resolve_by_name(provider, block).await
+            }
+        };
+
+        if let Some(controller) = addresses.get(ASSET_MANAGER_CONTROLLER).copied() {
+            match asset_managers(provider, controller, block).await {
+                Ok(managers) => {
+                    for (symbol, address) in managers {
+                        addresses.insert(asset_manager_name(&symbol), address);
+                    }
+                }
+                Err(e) => tracing::warn!(
+                    controller = %controller,
+                    error = %e,
+                    "Failed to enumerate FAsset AssetManagers"
+                ),
+            }
+        }
+
+        if addresses.is_empty() {
+            tracing::warn!(
+                "No contract addresses resolved — indexer will fetch ALL logs (unfiltered)"
+            );
+        } else {
+            tracing::info!(
+                resolved_count = addresses.len(),
+                total_requested = self.contract_names.len(),
+                "Contract address resolution complete"
+            );
+        }
+
+        Ok(ResolvedAddresses { addresses })
+    }
+
+    /// Every contract registered as of `block` (the latest block when `None`), by name,
+    /// read with a single `getAllContracts()` call.
+    pub async fn all_contracts(
+        &self,
+        provider: &(impl Provider + Clone),
+        block: Option<u64>,
+    ) -> anyhow::Result<BTreeMap<String, Address>> {
+        let contract = IFlareContractRegistry::new(self.registry_address, provider.clone());
+        let mut call = contract.getAllContracts();
+        if let Some(block) = block {
+            call = call.block(block.into());
+        }
+        let all = call.call().await?;
+        if all._names.len() != all._addresses.len() {
+            anyhow::bail!(
+                "getAllContracts returned {} names but {} addresses",
+                all._names.len(),
+                all._addresses.len()
+            );
+        }
+        Ok(all._names.into_iter().zip(all._addresses).collect())
+    }
+
+    /// Resolve each configured name with its own `getContractAddressByName` call.
+    async fn resolve_by_name(
+        &self,
+        provider: &(impl Provider + Clone),
+        block: Option<u64>,
+    ) -> HashMap<String, Address> {
         let contract = IFlareContractRegistry::new(self.registry_address, provider.clone());
         let mut addresses = HashMap::new();
 
         for name in &self.contract_names {
-            match contract.getContractAddressByName(name.clone()).call().await {
+            let mut call = contract.getContractAddressByName(name.clone());
+            if let Some(block) = block {
+                call = call.block(block.into());
+            }
+            match call.call().await {
                 Ok(addr) => {
                     if addr == Address::ZERO {
                         tracing::warn!(
@@ -153,26 +337,101 @@ impl FlareContractRegistry {
                 }
             }
         }
-
-        if addresses.is_empty() {
-            tracing::warn!(
-                "No contract addresses resolved — indexer will fetch ALL logs (unfiltered)"
-            );
-        } else {
-            tracing::info!(
-                resolved_count = addresses.len(),
-                total_requested = self.contract_names.len(),
-                "Contract address resolution complete"
-            );
-        }
-
-        Ok(ResolvedAddresses { addresses })
+        addresses
     }
 
     /// Get the registry contract address.
     pub fn registry_address(&self) -> Address {
         self.registry_address
     }
+
+    /// Names of the contracts this client resolves.
+    pub fn contract_names(&self) -> &[String] {
+        &self.contract_names
+    }
+}
+
+/// Every AssetManager of `controller` with its FAsset symbol, as of `block`.
+///
+/// AssetManagers whose FAsset symbol can't be read are skipped.
+pub async fn asset_managers(
+    provider: &(impl Provider + Clone),
+    controller: Address,
+    block: Option<u64>,
+) -> anyhow::Result<Vec<(String, Address)>> {
+    let block_id = block.map(Into::into).unwrap_or_default();
+    let managers = IAssetManagerController::new(controller, provider.clone())
+        .getAssetManagers()
+        .block(block_id)
+        .call()
+        .await?;
+
+    let mut resolved = Vec::with_capacity(managers.len());
+    for manager in managers {
+        let symbol = async {
+            let fasset = IAssetManager::new(manager, provider.clone())
+                .fAsset()
+                .block(block_id)
+                .call()
+                .await?;
+            IERC20Metadata::new(fasset, provider.clone())
+                .symbol()
+                .block(block_id)
+                .call()
+                .await
+        };
+        match symbol.await {
+            Ok(symbol) => {
+                tracing::debug!(asset_manager = %manager, fasset = %symbol, "Resolved FAsset AssetManager");
+                resolved.push((symbol, manager));
+            }
+            Err(e) => tracing::warn!(
+                asset_manager = %manager,
+                error = %e,
+                "Failed to read FAsset symbol, skipping AssetManager"
+            ),
+        }
+    }
+    Ok(resolved)
+}
+
+/// Hex feed ID of every fast update feed index as of `block`, with an empty string
+/// for removed feeds.
+pub async fn fast_update_feed_ids(
+    provider: &(impl Provider + Clone),
+    configuration: Address,
+    block: Option<u64>,
+) -> anyhow::Result<Vec<String>> {
+    let ids = IFastUpdatesConfiguration::new(configuration, provider.clone())
+        .getFeedIds()
+        .block(block.map(Into::into).unwrap_or_default())
+        .call()
+        .await?;
+    Ok(ids
+        .into_iter()
+        .map(|id| {
+            if id.is_zero() {
+                String::new()
+            } else {
+                flare_decoders::ftso::feed_id_hex(id.as_slice())
+            }
+        })
+        .collect())
+}
+
+/// Pick the non-zero addresses of `names` out of the full registry contents.
+fn select_contracts(names: &[String], all: &BTreeMap<String, Address>) -> HashMap<String, Address> {
+    let mut addresses = HashMap::new();
+    for name in names {
+        match all.get(name) {
+            Some(addr) if *addr != Address::ZERO => {
+                tracing::debug!(contract_name = %name, address = %addr, "Resolved contract address");
+                addresses.insert(name.clone(), *addr);
+            }
+            _ => tracing::warn!(contract_name = %name, "Contract not registered"),
+        }
+    }
+    addresses
 }
 
 /// Default contract names to resolve for the FlareEmissary indexer.
@@ -185,6 +444,14 @@ fn default_contract_names() -> Vec<String> {
         VOTER_WHITELISTER.to_string(),
         FTSO_REGISTRY.to_string(),
         WNAT.to_string(),
+        ADDRESS_UPDATER.to_string(),
+        ASSET_MANAGER_CONTROLLER.to_string(),
+        FAST_UPDATER.to_string(),
+        FAST_UPDATES_CONFIGURATION.to_string(),
+        FTSO_FEED_PUBLISHER.to_string(),
+        RELAY.to_string(),
+        VOTER_REGISTRY.to_string(),
+        FLARE_SYSTEMS_MANAGER.to_string(),
     ]
 }
 
@@ -206,10 +473,27 @@ mod tests {
         assert_eq!(registry.registry_address(), expected);
     }
 
+    #[test]
+    fn test_registry_for_every_chain() {
+        for (chain, address) in [
+            (Chain::Flare, FLARE_REGISTRY_ADDRESS),
+            (Chain::Songbird, SONGBIRD_REGISTRY_ADDRESS),
+            (Chain::Coston, COSTON_REGISTRY_ADDRESS),
+            (Chain::Coston2, COSTON2_REGISTRY_ADDRESS),
+        ] {
+            let registry = FlareContractRegistry::for_chain(chain);
+            assert_eq!(
+                registry.registry_address(),
+                Address::from_str(address).unwrap()
+            );
+            assert_eq!(registry.contract_names, default_contract_names());
+        }
+    }
+
     #[test]
     fn test_default_contract_names_count() {
         let names = default_contract_names();
-        assert_eq!(names.len(), 7);
+        assert_eq!(names.len(), 15);
         assert!(names.contains(&FTSO_MANAGER.to_string()));
         assert!(names.contains(&STATE_CONNECTOR.to_string()));
         assert!(names.contains(&ASSET_MANAGER.to_string()));
@@ -224,6 +508,50 @@ mod tests {
         assert_eq!(registry.contract_names.len(), 1);
     }
 
+    #[test]
+    fn test_select_contracts_skips_unregistered_names() {
+        let all = BTreeMap::from([
+            (FTSO_MANAGER.to_string(), Address::repeat_byte(0xAA)),
+            (WNAT.to_string(), Address::ZERO),
+            ("Unwatched".to_string(), Address::repeat_byte(0xCC)),
+        ]);
+        let names = vec![
+            FTSO_MANAGER.to_string(),
+            WNAT.to_string(),
+            ASSET_MANAGER.to_string(),
+        ];
+
+        let selected = select_contracts(&names, &all);
+        assert_eq!(
+            selected,
+            HashMap::from([(FTSO_MANAGER.to_string(), Address::repeat_byte(0xAA))])
+        );
+    }
+
+    #[test]
+    fn test_fasset_symbols_from_asset_manager_names() {
+        let resolved = ResolvedAddresses {
+            addresses: HashMap::from([
+                (asset_manager_name("FXRP"), Address::repeat_byte(0x01)),
+                (asset_manager_name("FBTC"), Address::repeat_byte(0x02)),
+                (ASSET_MANAGER.to_string(), Address::repeat_byte(0x03)),
+                (
+                    ASSET_MANAGER_CONTROLLER.to_string(),
+                    Address::repeat_byte(0x04),
+                ),
+            ]),
+        };
+
+        assert_eq!(asset_manager_name("FXRP"), "AssetManager:FXRP");
+        assert_eq!(
+            resolved.fasset_symbols(),
+            HashMap::from([
+                (Address::repeat_byte(0x01), "FXRP".to_string()),
+                (Address::repeat_byte(0x02), "FBTC".to_string()),
+            ])
+        );
+    }
+
     #[test]
     fn test_resolved_addresses_operations() {
         let mut map = HashMap::new();
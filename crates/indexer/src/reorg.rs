@@ -3,11 +3,32 @@ use std::collections::VecDeque;
 use alloy::primitives::B256;
 use alloy::providers::Provider;
 
+/// Identity of an indexed block, as stored in `indexed_blocks`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockRef {
+    pub number: u64,
+    pub hash: B256,
+    pub parent_hash: B256,
+}
+
+/// A detected chain reorganization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reorg {
+    /// First block that was replaced; indexing must resume here
+    pub fork_block: u64,
+    /// Block whose parent hash revealed the reorg
+    pub detected_at: u64,
+    /// Every block in the window was replaced, so the true fork point is older than
+    /// `fork_block`. The poller locates it in `indexed_blocks` before rewinding
+    /// (see `BlockPoller::locate_fork`).
+    pub beyond_window: bool,
+}
+
 /// Sliding-window reorg detector.
 ///
 /// Maintains a window of recent `(block_number, block_hash)` pairs.
 /// On each new block, checks whether the parent hash matches the expected chain.
-/// If a mismatch is found, returns the block number where the reorg occurred.
+/// If a mismatch is found, returns the `Reorg` describing where the chains diverged.
 pub struct ReorgDetector {
     /// Recent block hashes: (block_number, block_hash)
     window: VecDeque<(u64, B256)>,
@@ -23,9 +44,23 @@ impl ReorgDetector {
         }
     }
 
+    /// Replace the window with `blocks` (e.g. loaded from `indexed_blocks` on
+    /// startup). Only the most recent `max_size` blocks are kept.
+    pub fn seed(&mut self, blocks: impl IntoIterator<Item = (u64, B256)>) {
+        let mut blocks: Vec<_> = blocks.into_iter().collect();
+        blocks.sort_by_key(|(number, _)| *number);
+        let skip = blocks.len().saturating_sub(self.max_size);
+        self.window = blocks.into_iter().skip(skip).collect();
+    }
+
+    /// Newest block in the window.
+    pub fn last_block(&self) -> Option<u64> {
+        self.window.back().map(|(number, _)| *number)
+    }
+
     /// Check if the new block's parent hash matches our recorded hash for the previous block.
     /// If OK, record the new block hash and return `None`.
-    /// If a reorg is detected, return `Some(reorg_block_number)` — the earliest block that diverged.
+    /// If a reorg is detected, return the `Reorg` starting at the earliest block that diverged.
     ///
     /// `parent_hash` is passed in from the already-fetched block to avoid a redundant RPC call.
     pub async fn check_and_record(
@@ -34,7 +69,7 @@ impl ReorgDetector {
         block_hash: B256,
         parent_hash: B256,
         provider: &impl Provider,
-    ) -> anyhow::Result<Option<u64>> {
+    ) -> anyhow::Result<Option<Reorg>> {
         // If we have the parent in our window, verify the hash chain
         if block_number > 0
             && let Some(pos) = self
@@ -53,12 +88,23 @@ impl ReorgDetector {
                 );
 
                 // Find the divergence point: walk back through our window
-                let reorg_start = self.find_divergence_point(provider).await?;
+                let reorg = match self.find_divergence_point(provider).await? {
+                    Some(fork_block) => Reorg {
+                        fork_block,
+                        detected_at: block_number,
+                        beyond_window: false,
+                    },
+                    None => Reorg {
+                        fork_block: self.window.front().map(|(num, _)| *num).unwrap_or(0),
+                        detected_at: block_number,
+                        beyond_window: true,
+                    },
+                };
 
                 // Clear the window from the reorg point onwards
-                self.window.retain(|(num, _)| *num < reorg_start);
+                self.window.retain(|(num, _)| *num < reorg.fork_block);
 
-                return Ok(Some(reorg_start));
+                return Ok(Some(reorg));
             }
         }
 
@@ -72,14 +118,16 @@ impl ReorgDetector {
     }
 
     /// Walk back through the window to find the earliest block where the hash diverges.
-    async fn find_divergence_point(&self, provider: &impl Provider) -> anyhow::Result<u64> {
+    ///
+    /// Returns `None` if every block in the window was replaced.
+    async fn find_divergence_point(&self, provider: &impl Provider) -> anyhow::Result<Option<u64>> {
         for (block_number, expected_hash) in self.window.iter().rev() {
             let block = provider.get_block_by_number((*block_number).into()).await?;
 
             match block {
                 Some(b) if b.header.hash == *expected_hash => {
                     // This block is still canonical; reorg starts after it
-                    return Ok(*block_number + 1);
+                    return Ok(Some(*block_number + 1));
                 }
                 _ => {
                     // This block was also reorged, continue walking back
@@ -88,9 +136,13 @@ impl ReorgDetector {
             }
         }
 
-        // If we walked through the entire window, the reorg is deeper than our window.
-        // Return the oldest block in our window as the reorg point.
-        Ok(self.window.front().map(|(num, _)| *num).unwrap_or(0))
+        // We walked through the entire window: the reorg is deeper than it
+        Ok(None)
+    }
+
+    /// Maximum number of blocks tracked.
+    pub fn max_size(&self) -> usize {
+        self.max_size
     }
 
     /// Current window size (number of tracked blocks).
@@ -109,4 +161,37 @@ mod tests {
         assert_eq!(detector.window_size(), 0);
         assert_eq!(detector.max_size, 10);
     }
+
+    #[test]
+    fn test_seed_keeps_most_recent_blocks_in_order() {
+        let mut detector = ReorgDetector::new(3);
+        detector.seed((100..105).rev().map(|n| (n, B256::with_last_byte(n as u8))));
+
+        assert_eq!(detector.window_size(), 3);
+        assert_eq!(detector.last_block(), Some(104));
+        let numbers: Vec<u64> = detector.window.iter().map(|(n, _)| *n).collect();
+        assert_eq!(numbers, vec![102, 103, 104]);
+    }
+
+    #[tokio::test]
+    async fn test_seeded_window_accepts_continuing_block() {
+        use alloy::providers::ProviderBuilder;
+
+        let mut detector = ReorgDetector::new(10);
+        detector.seed((1..=5).map(|n| (n, B256::with_last_byte(n as u8))));
+
+        // Continues the stored chain: recorded without touching the provider
+        let provider = ProviderBuilder::new().connect_http("http://127.0.0.1:1".parse().unwrap());
+        let reorg = detector
+            .check_and_record(
+                6,
+                B256::with_last_byte(6),
+                B256::with_last_byte(5),
+                &provider,
+            )
+            .await
+            .unwrap();
+        assert_eq!(reorg, None);
+        assert_eq!(detector.last_block(), Some(6));
+    }
 }
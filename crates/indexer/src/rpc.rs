@@ -0,0 +1,506 @@
+//! Multi-endpoint RPC transport with health-checked failover.
+//!
+//! `FailoverTransport` wraps a primary RPC endpoint and any number of fallbacks
+//! (in priority order) behind a single alloy transport, so pollers and backfills
+//! use it like any other provider. It keeps per-endpoint latency, error-rate and
+//! block-height statistics and switches the active endpoint when:
+//!
+//! - a request to the active endpoint fails at the transport level (connection
+//!   error, timeout, HTTP error) and another endpoint answers it, or
+//! - a health check finds the active endpoint down or lagging more than
+//!   `max_lag_blocks` behind the best height seen.
+//!
+//! Health checks fail back to a higher-priority endpoint once it has passed
+//! `recovery_checks` consecutive checks. JSON-RPC error responses (e.g. a rejected
+//! `eth_getLogs` range) are answers, not endpoint failures, and never trigger a switch.
+//!
+//! Every switch is logged and counted in `flare_indexer_rpc_failovers_total`.
+//! Endpoint URLs often embed API keys, so logs and metric labels identify
+//! endpoints by chain, role (`primary`, `fallback_1`, ...) and redacted host only.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::http::Http;
+use alloy::transports::{TransportError, TransportFut};
+use tokio::task::{JoinHandle, JoinSet};
+use tower::Service;
+
+use flare_common::types::Chain;
+
+/// Weight of the newest sample in the latency and error-rate moving averages.
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Failover tuning.
+#[derive(Debug, Clone)]
+pub struct FailoverConfig {
+    /// Interval between health checks of every endpoint
+    pub health_check_interval: Duration,
+    /// Blocks an endpoint may trail the best known height before it's considered lagging
+    pub max_lag_blocks: u64,
+    /// Consecutive healthy checks before failing back to a higher-priority endpoint
+    pub recovery_checks: u32,
+    /// Timeout for a single RPC request
+    pub request_timeout: Duration,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            health_check_interval: Duration::from_secs(10),
+            max_lag_blocks: 5,
+            recovery_checks: 2,
+            request_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Point-in-time statistics for one endpoint.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EndpointStats {
+    /// Moving average of request latency in milliseconds
+    pub latency_ms: f64,
+    /// Moving average of the failure rate, 0.0 – 1.0
+    pub error_rate: f64,
+    pub requests: u64,
+    pub errors: u64,
+    /// Block height reported by the last successful health check
+    pub block_height: Option<u64>,
+    /// Consecutive health checks that found the endpoint up and not lagging
+    pub healthy_streak: u32,
+    /// Whether the last health check succeeded
+    pub up: bool,
+}
+
+impl EndpointStats {
+    fn record(&mut self, latency: Duration, ok: bool) {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        let failure = if ok { 0.0 } else { 1.0 };
+        if self.requests == 0 {
+            self.latency_ms = latency_ms;
+            self.error_rate = failure;
+        } else {
+            self.latency_ms += EWMA_ALPHA * (latency_ms - self.latency_ms);
+            self.error_rate += EWMA_ALPHA * (failure - self.error_rate);
+        }
+        self.requests += 1;
+        if !ok {
+            self.errors += 1;
+        }
+    }
+}
+
+struct Endpoint {
+    /// Role label: `primary`, `fallback_1`, ...
+    label: String,
+    /// Scheme and host only, safe to log
+    redacted: String,
+    url: String,
+    http: Http<reqwest::Client>,
+    stats: Mutex<EndpointStats>,
+}
+
+struct Inner {
+    chain: Chain,
+    endpoints: Vec<Endpoint>,
+    active: AtomicUsize,
+    config: FailoverConfig,
+}
+
+/// Alloy transport that routes requests to the healthiest configured endpoint.
+#[derive(Clone)]
+pub struct FailoverTransport {
+    inner: Arc<Inner>,
+}
+
+impl FailoverTransport {
+    /// Build a transport over `chain`'s RPC `urls`, highest priority first.
+    pub fn new(chain: Chain, urls: Vec<String>, config: FailoverConfig) -> anyhow::Result<Self> {
+        if urls.is_empty() {
+            anyhow::bail!("At least one RPC URL is required");
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            .build()?;
+        let endpoints = urls
+            .into_iter()
+            .enumerate()
+            .map(|(i, url)| {
+                let parsed: reqwest::Url = url.parse()?;
+                Ok(Endpoint {
+                    label: if i == 0 {
+                        "primary".to_string()
+                    } else {
+                        format!("fallback_{}", i)
+                    },
+                    redacted: format!("{}://{}", parsed.scheme(), parsed.host_str().unwrap_or("?")),
+                    http: Http::with_client(client.clone(), parsed),
+                    url,
+                    stats: Mutex::new(EndpointStats::default()),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let transport = Self {
+            inner: Arc::new(Inner {
+                chain,
+                endpoints,
+                active: AtomicUsize::new(0),
+                config,
+            }),
+        };
+        transport.publish_active();
+        Ok(transport)
+    }
+
+    /// A provider that sends every request through this transport.
+    pub fn provider(&self) -> impl Provider + Clone + use<> {
+        ProviderBuilder::new().connect_client(RpcClient::new(self.clone(), false))
+    }
+
+    /// Index of the endpoint currently serving requests (0 = primary).
+    pub fn active(&self) -> usize {
+        self.inner.active.load(Ordering::Relaxed)
+    }
+
+    /// Statistics for every endpoint, in priority order.
+    pub fn stats(&self) -> Vec<EndpointStats> {
+        self.inner
+            .endpoints
+            .iter()
+            .map(|e| e.stats.lock().unwrap().clone())
+            .collect()
+    }
+
+    /// Run health checks in the background until the returned guard is dropped.
+    pub fn spawn_health_checks(&self) -> HealthCheckGuard {
+        let transport = self.clone();
+        HealthCheckGuard(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(transport.inner.config.health_check_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                transport.check_health().await;
+            }
+        }))
+    }
+
+    /// Probe every endpoint's block height once and fail over or back if needed.
+    pub async fn check_health(&self) {
+        let endpoints = &self.inner.endpoints;
+        if endpoints.len() < 2 {
+            return;
+        }
+
+        let mut probes = JoinSet::new();
+        for i in 0..endpoints.len() {
+            let transport = self.clone();
+            probes.spawn(async move { (i, transport.probe(i).await) });
+        }
+        let mut heights = vec![None; endpoints.len()];
+        while let Some(Ok((i, height))) = probes.join_next().await {
+            heights[i] = height;
+        }
+        let best = heights.iter().flatten().copied().max();
+        let max_lag = self.inner.config.max_lag_blocks;
+        let is_healthy =
+            |i: usize| matches!((heights[i], best), (Some(h), Some(best)) if best - h <= max_lag);
+        for (i, endpoint) in endpoints.iter().enumerate() {
+            let mut stats = endpoint.stats.lock().unwrap();
+            stats.healthy_streak = if is_healthy(i) {
+                stats.healthy_streak + 1
+            } else {
+                0
+            };
+        }
+        self.publish_stats();
+        let active = self.active();
+
+        if is_healthy(active) {
+            // Fail back once a higher-priority endpoint has stayed healthy for a while
+            let recovered = (0..active).find(|&i| {
+                is_healthy(i)
+                    && endpoints[i].stats.lock().unwrap().healthy_streak
+                        >= self.inner.config.recovery_checks
+            });
+            if let Some(recovered) = recovered {
+                self.switch(active, recovered, "recovered", best);
+            }
+            return;
+        }
+
+        let reason = if heights[active].is_none() {
+            "unreachable"
+        } else {
+            "lagging"
+        };
+        match (0..endpoints.len()).find(|&i| i != active && is_healthy(i)) {
+            Some(next) => self.switch(active, next, reason, best),
+            None => tracing::warn!(
+                chain = %self.inner.chain,
+                active = %endpoints[active].label,
+                reason,
+                "Active RPC endpoint unhealthy and no healthy alternative, staying on it"
+            ),
+        }
+    }
+
+    /// Read one endpoint's block height and record the outcome.
+    async fn probe(&self, i: usize) -> Option<u64> {
+        let endpoint = &self.inner.endpoints[i];
+        let provider = ProviderBuilder::new().connect_http(endpoint.url.parse().ok()?);
+        let started = Instant::now();
+        let height = match tokio::time::timeout(
+            self.inner.config.request_timeout,
+            provider.get_block_number(),
+        )
+        .await
+        {
+            Ok(Ok(height)) => Some(height),
+            Ok(Err(e)) => {
+                tracing::debug!(endpoint = %endpoint.label, error = %e, "RPC health check failed");
+                None
+            }
+            Err(_) => {
+                tracing::debug!(endpoint = %endpoint.label, "RPC health check timed out");
+                None
+            }
+        };
+
+        let mut stats = endpoint.stats.lock().unwrap();
+        stats.record(started.elapsed(), height.is_some());
+        stats.up = height.is_some();
+        if height.is_some() {
+            stats.block_height = height;
+        }
+        height
+    }
+
+    fn switch(&self, from: usize, to: usize, reason: &'static str, best_height: Option<u64>) {
+        if self
+            .inner
+            .active
+            .compare_exchange(from, to, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+        {
+            return;
+        }
+
+        let (from_ep, to_ep) = (&self.inner.endpoints[from], &self.inner.endpoints[to]);
+        let from_height = from_ep.stats.lock().unwrap().block_height;
+        let to_height = to_ep.stats.lock().unwrap().block_height;
+        if to < from {
+            tracing::info!(
+                chain = %self.inner.chain,
+                from = %from_ep.label,
+                to = %to_ep.label,
+                to_url = %to_ep.redacted,
+                reason,
+                "RPC failing back to higher-priority endpoint"
+            );
+        } else {
+            tracing::warn!(
+                chain = %self.inner.chain,
+                from = %from_ep.label,
+                from_url = %from_ep.redacted,
+                from_height,
+                to = %to_ep.label,
+                to_url = %to_ep.redacted,
+                to_height,
+                best_height,
+                reason,
+                "RPC failing over"
+            );
+        }
+
+        metrics::counter!(
+            "flare_indexer_rpc_failovers_total",
+            "chain" => self.inner.chain.to_string(),
+            "from" => from_ep.label.clone(),
+            "to" => to_ep.label.clone(),
+            "reason" => reason,
+        )
+        .increment(1);
+        self.publish_active();
+    }
+
+    /// Send a request to the active endpoint, trying the others in priority order
+    /// if it fails at the transport level.
+    async fn send(&self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let active = self.active();
+        let order =
+            std::iter::once(active).chain((0..self.inner.endpoints.len()).filter(|&i| i != active));
+
+        let mut first_error = None;
+        for i in order {
+            let endpoint = &self.inner.endpoints[i];
+            let started = Instant::now();
+            let result = endpoint.http.clone().call(request.clone()).await;
+            endpoint
+                .stats
+                .lock()
+                .unwrap()
+                .record(started.elapsed(), result.is_ok());
+
+            match result {
+                Ok(response) => {
+                    if i != active {
+                        self.switch(active, i, "request_error", None);
+                    }
+                    return Ok(response);
+                }
+                Err(e) => {
+                    tracing::debug!(endpoint = %endpoint.label, error = %e, "RPC request failed");
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        Err(first_error.expect("at least one endpoint"))
+    }
+
+    fn publish_active(&self) {
+        let active = self.active();
+        let chain = self.inner.chain.to_string();
+        for (i, endpoint) in self.inner.endpoints.iter().enumerate() {
+            metrics::gauge!(
+                "flare_indexer_rpc_active",
+                "chain" => chain.clone(),
+                "endpoint" => endpoint.label.clone()
+            )
+            .set(if i == active { 1.0 } else { 0.0 });
+        }
+    }
+
+    fn publish_stats(&self) {
+        let chain = self.inner.chain.to_string();
+        for endpoint in &self.inner.endpoints {
+            let stats = endpoint.stats.lock().unwrap().clone();
+            let labels = [
+                ("chain", chain.clone()),
+                ("endpoint", endpoint.label.clone()),
+            ];
+            metrics::gauge!("flare_indexer_rpc_latency_ms", &labels).set(stats.latency_ms);
+            metrics::gauge!("flare_indexer_rpc_error_rate", &labels).set(stats.error_rate);
+            if let Some(height) = stats.block_height {
+                metrics::gauge!("flare_indexer_rpc_block_height", &labels).set(height as f64);
+            }
+        }
+    }
+}
+
+impl Service<RequestPacket> for FailoverTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let transport = self.clone();
+        Box::pin(async move { transport.send(request).await })
+    }
+}
+
+/// Stops the background health checks when dropped.
+pub struct HealthCheckGuard(JoinHandle<()>);
+
+impl Drop for HealthCheckGuard {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU64;
+
+    /// Serve a JSON-RPC stub answering `eth_blockNumber` with the shared height.
+    async fn stub_node(height: Arc<AtomicU64>) -> String {
+        let router = axum::Router::new().route(
+            "/",
+            axum::routing::post(
+                move |axum::Json(request): axum::Json<serde_json::Value>| async move {
+                    axum::Json(serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "result": format!("0x{:x}", height.load(Ordering::Relaxed)),
+                    }))
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{}", addr)
+    }
+
+    fn config() -> FailoverConfig {
+        FailoverConfig {
+            request_timeout: Duration::from_secs(2),
+            ..FailoverConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_error_fails_over_to_fallback() {
+        let fallback = stub_node(Arc::new(AtomicU64::new(42))).await;
+        // Nothing listens on port 9 — every request to the primary fails to connect
+        let transport = FailoverTransport::new(
+            Chain::Flare,
+            vec!["http://127.0.0.1:9".to_string(), fallback],
+            config(),
+        )
+        .unwrap();
+
+        let height = transport.provider().get_block_number().await.unwrap();
+        assert_eq!(height, 42);
+        assert_eq!(transport.active(), 1);
+
+        let stats = transport.stats();
+        assert_eq!(stats[0].errors, 1);
+        assert_eq!(stats[1].errors, 0);
+    }
+
+    #[tokio::test]
+    async fn test_lagging_primary_fails_over_and_back() {
+        let primary_height = Arc::new(AtomicU64::new(100));
+        let primary = stub_node(primary_height.clone()).await;
+        let fallback = stub_node(Arc::new(AtomicU64::new(200))).await;
+        let transport =
+            FailoverTransport::new(Chain::Flare, vec![primary, fallback], config()).unwrap();
+
+        transport.check_health().await;
+        assert_eq!(transport.active(), 1, "primary trails by 100 blocks");
+        assert_eq!(transport.stats()[0].block_height, Some(100));
+
+        // Primary catches up but must stay healthy for `recovery_checks` checks
+        primary_height.store(200, Ordering::Relaxed);
+        transport.check_health().await;
+        assert_eq!(transport.active(), 1);
+        transport.check_health().await;
+        assert_eq!(transport.active(), 0);
+    }
+
+    #[test]
+    fn test_stats_moving_averages() {
+        let mut stats = EndpointStats::default();
+        stats.record(Duration::from_millis(100), true);
+        assert_eq!(stats.latency_ms, 100.0);
+        assert_eq!(stats.error_rate, 0.0);
+
+        stats.record(Duration::from_millis(200), false);
+        assert!((stats.latency_ms - 120.0).abs() < 1e-9);
+        assert!((stats.error_rate - EWMA_ALPHA).abs() < 1e-9);
+        assert_eq!((stats.requests, stats.errors), (2, 1));
+    }
+}
@@ -0,0 +1,48 @@
+//! Addresses the indexer watches on behalf of `generic_event` subscriptions.
+//!
+//! Protocol contracts come from the registry, but a user can subscribe to raw
+//! events of any contract. With generic subscriptions enabled, the poller adds
+//! every monitored address with an active `generic_event` subscription to its log
+//! filter and captures that address's unmatched logs with the generic decoder.
+
+use alloy::primitives::Address;
+use sqlx::PgPool;
+
+use flare_common::types::{Chain, EventType};
+
+/// Monitored addresses on `chain` with at least one active `generic_event`
+/// subscription, in address order.
+///
+/// Stored addresses that don't parse are skipped with a warning.
+pub async fn generic_event_addresses(pool: &PgPool, chain: Chain) -> anyhow::Result<Vec<Address>> {
+    let rows: Vec<(String,)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT ma.address FROM subscriptions s
+        JOIN monitored_addresses ma ON ma.id = s.address_id
+        WHERE s.active AND s.event_type = $1 AND ma.chain = $2
+        "#,
+    )
+    .bind(EventType::GenericEvent.to_string())
+    .bind(chain.to_string())
+    .fetch_all(pool)
+    .await?;
+
+    let mut addresses: Vec<Address> = rows
+        .into_iter()
+        .filter_map(|(address,)| match address.parse() {
+            Ok(address) => Some(address),
+            Err(e) => {
+                tracing::warn!(
+                    chain = %chain,
+                    address = %address,
+                    error = %e,
+                    "Skipping unparseable monitored address"
+                );
+                None
+            }
+        })
+        .collect();
+    addresses.sort();
+    addresses.dedup();
+    Ok(addresses)
+}
@@ -0,0 +1,163 @@
+//! Adaptive block-range sizing for `eth_getLogs`.
+//!
+//! RPC providers cap a single `eth_getLogs` by block range, result count or
+//! response size, and the caps differ per provider. Rather than configure the exact
+//! limit, range fetches start at a maximum window, halve it whenever the provider
+//! rejects the range, and grow it back after successful fetches.
+
+/// Block window that shrinks on rejected ranges and grows on success.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogWindow {
+    size: u64,
+    max: u64,
+}
+
+impl LogWindow {
+    /// Start at `max` blocks (at least one).
+    pub fn new(max: u64) -> Self {
+        let max = max.max(1);
+        Self { size: max, max }
+    }
+
+    /// Current number of blocks per request.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Last block of a window starting at `from`, capped at `limit`.
+    pub fn end(&self, from: u64, limit: u64) -> u64 {
+        limit.min(from.saturating_add(self.size - 1))
+    }
+
+    /// Halve the window after a rejected range. Returns `false` if already at one block.
+    pub fn shrink(&mut self) -> bool {
+        if self.size == 1 {
+            return false;
+        }
+        self.size = (self.size / 2).max(1);
+        true
+    }
+
+    /// Double the window after a successful fetch, up to the maximum.
+    pub fn grow(&mut self) {
+        self.size = self.size.saturating_mul(2).min(self.max);
+    }
+}
+
+/// Provider messages for a log query that spans too many blocks or returns too much.
+const RANGE_NEEDLES: [&str; 6] = [
+    "block range",              // "block range too large", "exceed maximum block range: 30"
+    "query returned more than", // Infura, Geth: "query returned more than 10000 results"
+    "response size exceeded",   // Alchemy: "Log response size exceeded."
+    "response size should not", // "response size should not greater than 10000000 bytes"
+    "too many blocks",          // Coreth (Flare nodes): "requested too many blocks from 1 to 50000"
+    "limited to a",             // QuickNode: "eth_getLogs is limited to a 10,000 range"
+];
+
+/// Messages of throttling errors, which must be retried rather than split.
+const RATE_LIMIT_NEEDLES: [&str; 5] = [
+    "rate limit",
+    "ratelimit",
+    "rate exceeded",
+    "too many requests",
+    "request count exceeded",
+];
+
+/// Whether an RPC error means the requested log range was too large.
+///
+/// Matches the range messages of known providers, plus Infura-style `-32005`
+/// ("limit exceeded") errors that mention the range or result count. Rate-limit and
+/// HTTP 429 errors are excluded even when they say "limit exceeded": shrinking the
+/// window would only multiply requests. Anything else is treated as transient.
+pub fn is_range_error(error: &dyn std::fmt::Display) -> bool {
+    let message = error.to_string().to_lowercase();
+    let http_429 = message
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|token| token == "429");
+    if http_429
+        || RATE_LIMIT_NEEDLES
+            .iter()
+            .any(|needle| message.contains(needle))
+    {
+        return false;
+    }
+
+    RANGE_NEEDLES.iter().any(|needle| message.contains(needle))
+        || (message.contains("-32005")
+            && ["range", "results", "more than"]
+                .iter()
+                .any(|needle| message.contains(needle)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_shrinks_and_grows_within_bounds() {
+        let mut window = LogWindow::new(8);
+        assert_eq!(window.size(), 8);
+
+        assert!(window.shrink());
+        assert!(window.shrink());
+        assert!(window.shrink());
+        assert_eq!(window.size(), 1);
+        assert!(!window.shrink());
+
+        window.grow();
+        window.grow();
+        assert_eq!(window.size(), 4);
+        window.grow();
+        window.grow();
+        assert_eq!(window.size(), 8);
+    }
+
+    #[test]
+    fn test_window_end_is_capped() {
+        let window = LogWindow::new(100);
+        assert_eq!(window.end(1_000, u64::MAX), 1_099);
+        assert_eq!(window.end(1_000, 1_010), 1_010);
+        assert_eq!(LogWindow::new(0).end(5, 100), 5);
+    }
+
+    #[test]
+    fn test_is_range_error() {
+        assert!(is_range_error(&"query returned more than 10000 results"));
+        assert!(is_range_error(&"Block range too large"));
+        assert!(is_range_error(&"exceed maximum block range: 30"));
+        assert!(is_range_error(&"Log response size exceeded."));
+        assert!(is_range_error(
+            &"requested too many blocks from 14290000 to 14340000, maximum is set to 30"
+        ));
+        assert!(is_range_error(
+            &"server returned an error response: error code -32005: limit exceeded, max range 2048"
+        ));
+        assert!(!is_range_error(&"connection reset by peer"));
+        assert!(!is_range_error(&"header not found"));
+        assert!(!is_range_error(&"error code -32005: limit exceeded"));
+    }
+
+    #[test]
+    fn test_rate_limit_is_not_range_error() {
+        assert!(!is_range_error(&"rate limit exceeded"));
+        assert!(!is_range_error(
+            &"error code -32005: project ID request rate exceeded"
+        ));
+        assert!(!is_range_error(
+            &"daily request count exceeded, request rate limited"
+        ));
+        assert!(!is_range_error(
+            &"compute units per second capacity exceeded (ratelimit)"
+        ));
+    }
+
+    #[test]
+    fn test_http_429_is_not_range_error() {
+        assert!(!is_range_error(
+            &"HTTP error 429 with body: Too Many Requests"
+        ));
+        assert!(!is_range_error(
+            &"HTTP error 429 with body: query returned more than 10000 results"
+        ));
+    }
+}
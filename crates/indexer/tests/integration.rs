@@ -7,11 +7,21 @@
 //! DATABASE_URL="postgresql://..." cargo test -p flare-indexer --test integration -- --ignored --nocapture
 //! ```
 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use alloy::primitives::{Address, B256};
 use chrono::Utc;
 use sqlx::PgPool;
 
 use flare_common::types::{Chain, DecodedEvent, EventType};
+use flare_indexer::address_history;
+use flare_indexer::backfill::{BackfillStats, Backfiller};
 use flare_indexer::poller::BlockPoller;
+use flare_indexer::registry::{FlareContractRegistry, ResolvedAddresses};
+use flare_indexer::reorg::{BlockRef, Reorg};
 
 /// Create a BlockPoller connected to the test database.
 async fn setup(pool: &PgPool) -> BlockPoller {
@@ -105,6 +115,117 @@ async fn test_persist_events_deduplication(pool: PgPool) {
     assert_eq!(count.0, 1, "Duplicate insert should be ignored");
 }
 
+#[sqlx::test]
+#[ignore] // Also requires Redis on REDIS_URL (default redis://localhost:6379)
+async fn test_reincluded_event_is_processed_again(pool: PgPool) {
+    let poller = setup(&pool).await;
+
+    // A user subscribed to liquidations and redemption requests of the emitter
+    sqlx::query(
+        r#"
+        WITH u AS (
+            INSERT INTO users (id, wallet_address) VALUES (uuid_generate_v4(), '0xreincluded')
+            RETURNING id
+        ), a AS (
+            INSERT INTO monitored_addresses (id, address, chain)
+            VALUES (uuid_generate_v4(), '0x0000000000000000000000000000000000001234', 'flare')
+            RETURNING id
+        ), c AS (
+            INSERT INTO notification_channels (id, user_id, channel_type, config, verified)
+            SELECT uuid_generate_v4(), u.id, 'telegram', '{}', true FROM u
+            RETURNING id, user_id
+        )
+        INSERT INTO subscriptions (id, user_id, address_id, channel_id, event_type)
+        SELECT uuid_generate_v4(), c.user_id, a.id, c.id, t.event_type
+        FROM a, c, (VALUES ('liquidation_started'), ('redemption_requested')) AS t(event_type)
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // The event is indexed, then reorged out before the engine reaches it
+    let event = make_event(500, 0, EventType::LiquidationStarted);
+    poller
+        .persist_events(std::slice::from_ref(&event))
+        .await
+        .unwrap();
+    poller.rollback_events_from(500).await.unwrap();
+
+    let redis_url =
+        std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+    let redis = flare_common::redis_pool::create_redis_pool(&redis_url)
+        .await
+        .unwrap();
+    let mut worker = flare_engine::worker::EventWorker::new(pool.clone(), redis, 100, 50)
+        .with_name("test_reincluded");
+    worker.update_cursor(0).await.unwrap();
+    assert_eq!(worker.process_batch().await.unwrap(), 1);
+
+    let alerts = || async {
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM alerts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        count
+    };
+    assert_eq!(alerts().await, 0, "a reorged event never alerts");
+
+    // The same log is re-mined in the next block: the engine sees it again
+    let remined = DecodedEvent {
+        block_number: 501,
+        ..event
+    };
+    let tx_hash = remined.tx_hash.clone();
+    poller.persist_events(&[remined]).await.unwrap();
+    assert_eq!(worker.process_batch().await.unwrap(), 1);
+    assert_eq!(alerts().await, 1);
+
+    let rows: Vec<(i64, bool)> = sqlx::query_as(
+        "SELECT block_number, is_reorged FROM indexed_events WHERE tx_hash = $1 ORDER BY id",
+    )
+    .bind(tx_hash)
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(rows, vec![(500, true), (501, false)]);
+
+    // An event that already alerted and is re-included keeps its one alert, now on
+    // the canonical row, so it is neither retracted nor raised again
+    // (a different subscription, so its cooldown doesn't interfere)
+    let alerted = make_event(600, 0, EventType::RedemptionRequested);
+    poller
+        .persist_events(std::slice::from_ref(&alerted))
+        .await
+        .unwrap();
+    assert_eq!(worker.process_batch().await.unwrap(), 1);
+    assert_eq!(alerts().await, 2);
+
+    poller.rollback_events_from(600).await.unwrap();
+    poller
+        .persist_events(&[DecodedEvent {
+            block_number: 601,
+            ..alerted.clone()
+        }])
+        .await
+        .unwrap();
+    assert_eq!(worker.process_batch().await.unwrap(), 1);
+    assert_eq!(alerts().await, 2);
+
+    let (block_number, is_reorged): (i64, bool) = sqlx::query_as(
+        r#"
+        SELECT e.block_number, e.is_reorged FROM alerts a
+        JOIN indexed_events e ON e.id = a.event_id
+        WHERE e.tx_hash = $1
+        "#,
+    )
+    .bind(&alerted.tx_hash)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!((block_number, is_reorged), (601, false));
+}
+
 #[sqlx::test]
 #[ignore]
 async fn test_update_and_get_indexer_state(pool: PgPool) {
@@ -125,6 +246,79 @@ async fn test_update_and_get_indexer_state(pool: PgPool) {
     assert_eq!(block, Some(1000));
 }
 
+#[sqlx::test]
+#[ignore]
+async fn test_commit_blocks_is_atomic_across_crash(pool: PgPool) {
+    let poller = setup(&pool).await;
+    poller.update_indexer_state(399).await.unwrap();
+
+    let events = vec![
+        make_event(400, 0, EventType::MintingExecuted),
+        make_event(400, 1, EventType::RedemptionRequested),
+        make_event(400, 2, EventType::CollateralDeposited),
+        // Same log delivered twice within one batch
+        make_event(400, 2, EventType::CollateralDeposited),
+    ];
+    let blocks = [BlockRef {
+        number: 400,
+        hash: B256::with_last_byte(1),
+        parent_hash: B256::with_last_byte(2),
+    }];
+
+    let stored = || async {
+        let (events,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM indexed_events WHERE block_number = 400")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let (blocks,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM indexed_blocks WHERE block_number = 400")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let cursor = poller.get_last_indexed_block().await.unwrap();
+        (events, blocks, cursor)
+    };
+
+    // Simulate the process dying after the events are written but before the cursor
+    // moves: the cursor write fails inside the same transaction
+    sqlx::query(
+        r#"
+        CREATE FUNCTION crash_on_cursor() RETURNS trigger AS $$
+        BEGIN RAISE EXCEPTION 'simulated crash'; END;
+        $$ LANGUAGE plpgsql
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "CREATE TRIGGER crash BEFORE UPDATE ON indexer_state FOR EACH ROW EXECUTE FUNCTION crash_on_cursor()",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    assert!(poller.commit_blocks(&blocks, &events, 400).await.is_err());
+    assert_eq!(
+        stored().await,
+        (0, 0, Some(399)),
+        "nothing from the crashed block is kept"
+    );
+
+    // On restart the block is indexed again from the unchanged cursor
+    sqlx::query("DROP TRIGGER crash ON indexer_state")
+        .execute(&pool)
+        .await
+        .unwrap();
+    poller.commit_blocks(&blocks, &events, 400).await.unwrap();
+    assert_eq!(stored().await, (3, 1, Some(400)));
+
+    // A crash after commit replays the block; nothing is duplicated
+    poller.commit_blocks(&blocks, &events, 400).await.unwrap();
+    assert_eq!(stored().await, (3, 1, Some(400)));
+}
+
 #[sqlx::test]
 #[ignore]
 async fn test_rollback_marks_events_reorged(pool: PgPool) {
@@ -165,3 +359,1093 @@ async fn test_rollback_marks_events_reorged(pool: PgPool) {
         assert!(is_reorged, "Block {} should be marked as reorged", block);
     }
 }
+
+/// JSON-RPC methods received by a stub node, with their params.
+type RpcCalls = Arc<Mutex<Vec<(String, serde_json::Value)>>>;
+
+/// Chain served by `stub_rpc`. Block `n` has hash `n` and every even block holds one
+/// FDC `RoundFinalized` log. `eth_getLogs` rejects ranges wider than `max_range`.
+///
+/// Setting `fork_at` switches to a competing chain from that block on: its blocks get
+/// different hashes, the transaction of every block `b` with `b % 4 == 2` is
+/// re-included in block `b + 1`, and the remaining transactions are dropped.
+///
+/// The registry holds `FtsoManager`, `AssetManager`, `AddressUpdater`,
+/// `AssetManagerController` and `Unwatched`. `AddressUpdater` is at `updater` and
+/// every other contract at the log emitter `0x…1234`, or at `0x…5678` as of
+/// `registry_switch_at`. The controller lists the `AssetManager` as the FXRP manager.
+/// Only `getAllContracts` is served, so resolution that falls back to per-name calls
+/// finds nothing.
+///
+/// Each address in `unknown_emitters` also emits an undecodable `Ping(uint256)` log
+/// in every block, returned only if the `eth_getLogs` address filter includes it.
+///
+/// With `fast_updates` set, the emitter also logs `FastUpdateFeeds` in every block `b`
+/// with values `[b, 2b]` and decimals `[2, 3]`. The `FastUpdatesConfiguration` at
+/// `0x…feed` maps those indices to FLR/USD and BTC/USD.
+struct StubChain {
+    tip: AtomicU64,
+    max_range: u64,
+    fork_at: AtomicU64,
+    registry_switch_at: AtomicU64,
+    updater: AtomicU64,
+    unknown_emitters: Mutex<Vec<Address>>,
+    fast_updates: std::sync::atomic::AtomicBool,
+}
+
+impl StubChain {
+    fn new(tip: u64, max_range: u64) -> Arc<Self> {
+        Arc::new(Self {
+            tip: AtomicU64::new(tip),
+            max_range,
+            fork_at: AtomicU64::new(u64::MAX),
+            registry_switch_at: AtomicU64::new(u64::MAX),
+            updater: AtomicU64::new(0x9999),
+            unknown_emitters: Mutex::new(Vec::new()),
+            fast_updates: std::sync::atomic::AtomicBool::new(false),
+        })
+    }
+
+    fn tip(&self) -> u64 {
+        self.tip.load(Ordering::SeqCst)
+    }
+
+    fn hash(&self, n: u64) -> String {
+        if n >= self.fork_at.load(Ordering::SeqCst) {
+            format!("0x{:064x}", n | 1 << 60)
+        } else {
+            format!("0x{:064x}", n)
+        }
+    }
+
+    /// `(block, tx_hash seed)` of every log in `from..=to`.
+    fn logs(&self, from: u64, to: u64) -> Vec<(u64, u64)> {
+        let fork_at = self.fork_at.load(Ordering::SeqCst);
+        (from..=to.min(self.tip()))
+            .filter_map(|b| {
+                if b < fork_at {
+                    (b % 2 == 0).then_some((b, b * 1000))
+                } else {
+                    (b % 4 == 3).then_some((b, (b - 1) * 1000))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Serve `chain` as a fake JSON-RPC node. Batched requests are supported.
+async fn stub_rpc(chain: Arc<StubChain>) -> (String, RpcCalls) {
+    let calls = RpcCalls::default();
+    let recorded = calls.clone();
+    let router = axum::Router::new().route(
+        "/",
+        axum::routing::post(
+            move |axum::Json(request): axum::Json<serde_json::Value>| async move {
+                let chain = chain.clone();
+                let respond = |request: &serde_json::Value| {
+                    let method = request["method"].as_str().unwrap().to_string();
+                    let params = request["params"].clone();
+                    recorded
+                        .lock()
+                        .unwrap()
+                        .push((method.clone(), params.clone()));
+                    let result = rpc_result(&method, &params, &chain);
+                    let mut response = serde_json::json!({ "jsonrpc": "2.0", "id": request["id"] });
+                    match result {
+                        Ok(result) => response["result"] = result,
+                        Err(message) => {
+                            response["error"] =
+                                serde_json::json!({ "code": -32005, "message": message })
+                        }
+                    }
+                    response
+                };
+                axum::Json(match request.as_array() {
+                    Some(batch) => serde_json::Value::Array(batch.iter().map(respond).collect()),
+                    None => respond(&request),
+                })
+            },
+        ),
+    );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    (format!("http://{}", addr), calls)
+}
+
+fn rpc_result(
+    method: &str,
+    params: &serde_json::Value,
+    chain: &StubChain,
+) -> Result<serde_json::Value, &'static str> {
+    use alloy::primitives::keccak256;
+    use alloy::sol_types::SolValue;
+
+    let parse = |v: &serde_json::Value| {
+        u64::from_str_radix(v.as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+    };
+    let hash = |n: u64| format!("0x{:064x}", n);
+
+    match method {
+        "eth_blockNumber" => Ok(serde_json::json!(format!("0x{:x}", chain.tip()))),
+        "eth_getBlockByNumber" => {
+            let n = parse(&params[0]);
+            if n > chain.tip() {
+                return Ok(serde_json::Value::Null);
+            }
+            Ok(serde_json::json!({
+                "hash": chain.hash(n),
+                "parentHash": chain.hash(n.saturating_sub(1)),
+                "sha3Uncles": hash(0),
+                "miner": "0x0000000000000000000000000000000000000000",
+                "stateRoot": hash(0),
+                "transactionsRoot": hash(0),
+                "receiptsRoot": hash(0),
+                "logsBloom": format!("0x{}", "00".repeat(256)),
+                "difficulty": "0x0",
+                "number": format!("0x{:x}", n),
+                "gasLimit": "0x0",
+                "gasUsed": "0x0",
+                "timestamp": format!("0x{:x}", 1_700_000_000 + n),
+                "extraData": "0x",
+                "mixHash": hash(0),
+                "nonce": "0x0000000000000000",
+                "baseFeePerGas": "0x0",
+                "transactions": [],
+                "uncles": []
+            }))
+        }
+        "eth_getLogs" => {
+            let (from, to) = (parse(&params[0]["fromBlock"]), parse(&params[0]["toBlock"]));
+            if to - from + 1 > chain.max_range {
+                return Err("block range too large");
+            }
+            let log = |address: String, topics: Vec<String>, b: u64, tx: u64, index: usize| {
+                serde_json::json!({
+                    "address": address,
+                    "topics": topics,
+                    "data": "0x",
+                    "blockHash": chain.hash(b),
+                    "blockNumber": format!("0x{:x}", b),
+                    "blockTimestamp": format!("0x{:x}", 1_700_000_000 + b),
+                    "transactionHash": hash(tx),
+                    "transactionIndex": "0x0",
+                    "logIndex": format!("0x{:x}", index),
+                    "removed": false
+                })
+            };
+            let topic0 = format!("{:#x}", keccak256("RoundFinalized(uint256,bytes32)"));
+            let mut logs: Vec<serde_json::Value> = chain
+                .logs(from, to)
+                .into_iter()
+                .map(|(b, tx)| {
+                    log(
+                        "0x0000000000000000000000000000000000001234".to_string(),
+                        vec![topic0.clone(), hash(b)],
+                        b,
+                        tx,
+                        0,
+                    )
+                })
+                .collect();
+
+            let filtered: Vec<Address> = match &params[0]["address"] {
+                serde_json::Value::Array(addresses) => addresses
+                    .iter()
+                    .map(|a| a.as_str().unwrap().parse().unwrap())
+                    .collect(),
+                serde_json::Value::String(address) => vec![address.parse().unwrap()],
+                _ => Vec::new(),
+            };
+            let ping = format!("{:#x}", keccak256("Ping(uint256)"));
+            for (i, emitter) in chain.unknown_emitters.lock().unwrap().iter().enumerate() {
+                if !filtered.contains(emitter) {
+                    continue;
+                }
+                for b in from..=to.min(chain.tip()) {
+                    let tx = b * 1000 + 1 + i as u64;
+                    logs.push(log(
+                        format!("{:#x}", emitter),
+                        vec![ping.clone(), hash(b)],
+                        b,
+                        tx,
+                        i + 1,
+                    ));
+                }
+            }
+            if chain.fast_updates.load(Ordering::SeqCst) {
+                let topic0 = format!(
+                    "{:#x}",
+                    keccak256("FastUpdateFeeds(uint256,uint256[],int8[])")
+                );
+                for b in from..=to.min(chain.tip()) {
+                    let mut entry = log(
+                        "0x0000000000000000000000000000000000001234".to_string(),
+                        vec![topic0.clone(), hash(b)],
+                        b,
+                        b * 1000 + 500,
+                        5,
+                    );
+                    let data = (
+                        vec![
+                            alloy::primitives::U256::from(b),
+                            alloy::primitives::U256::from(2 * b),
+                        ],
+                        vec![2i8, 3i8],
+                    )
+                        .abi_encode_params();
+                    entry["data"] = alloy::hex::encode_prefixed(data).into();
+                    logs.push(entry);
+                }
+            }
+            Ok(serde_json::json!(logs))
+        }
+        "eth_call" => {
+            let block = match params[1].as_str() {
+                Some("latest") | None => chain.tip(),
+                Some(_) => parse(&params[1]),
+            };
+            let calldata = params[0]["input"]
+                .as_str()
+                .or(params[0]["data"].as_str())
+                .unwrap_or_default();
+            let address = |name: &str| {
+                let n = if name == "AddressUpdater" {
+                    chain.updater.load(Ordering::SeqCst)
+                } else if name == "FastUpdatesConfiguration" {
+                    0xfeed
+                } else if block >= chain.registry_switch_at.load(Ordering::SeqCst) {
+                    0x5678
+                } else {
+                    0x1234
+                };
+                Address::left_padding_from(&n.to_be_bytes())
+            };
+            let calls = |signature: &str| {
+                calldata
+                    .trim_start_matches("0x")
+                    .starts_with(&alloy::hex::encode(&keccak256(signature)[..4]))
+            };
+            let result = if calls("getAllContracts()") {
+                let names: Vec<String> = [
+                    "FtsoManager",
+                    "AssetManager",
+                    "AddressUpdater",
+                    "AssetManagerController",
+                    "FastUpdatesConfiguration",
+                    "Unwatched",
+                ]
+                .map(String::from)
+                .to_vec();
+                let addresses: Vec<Address> = names.iter().map(|name| address(name)).collect();
+                (names, addresses).abi_encode_params()
+            } else if calls("getAssetManagers()") {
+                (vec![address("AssetManager")],).abi_encode_params()
+            } else if calls("fAsset()") {
+                (Address::repeat_byte(0xf0),).abi_encode_params()
+            } else if calls("symbol()") {
+                ("FXRP".to_string(),).abi_encode_params()
+            } else if calls("getFeedIds()") {
+                let ids: Vec<alloy::primitives::FixedBytes<21>> = ["FLR/USD", "BTC/USD"]
+                    .iter()
+                    .map(|name| flare_decoders::ftso::feed_id(1, name).unwrap().into())
+                    .collect();
+                (ids,).abi_encode_params()
+            } else {
+                return Err("only the registry, AssetManager, FAsset and feed calls are supported");
+            };
+            Ok(serde_json::json!(alloy::hex::encode_prefixed(result)))
+        }
+        _ => Err("method not supported"),
+    }
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_backfill_chunks_range_and_resumes(pool: PgPool) {
+    setup(&pool).await;
+    let (rpc_url, _) = stub_rpc(StubChain::new(1_000, 4)).await;
+
+    // A live cursor must be left alone by the backfill
+    sqlx::query("INSERT INTO indexer_state (chain, last_block) VALUES ('flare', 5000)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Chunks of 16 are rejected and shrink to 4
+    let backfiller = Backfiller::new(rpc_url, Chain::Flare, pool.clone(), 16);
+    let stats = backfiller.run(100, 109).await.unwrap();
+    assert_eq!(
+        stats,
+        BackfillStats {
+            blocks: 10,
+            events: 5
+        }
+    );
+
+    let rows: Vec<(i64, bool, String)> = sqlx::query_as(
+        "SELECT block_number, backfilled, event_type FROM indexed_events ORDER BY block_number",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    let blocks: Vec<i64> = rows.iter().map(|(b, _, _)| *b).collect();
+    assert_eq!(blocks, vec![100, 102, 104, 106, 108]);
+    assert!(
+        rows.iter()
+            .all(|(_, backfilled, event_type)| *backfilled && event_type == "round_finalized")
+    );
+
+    let (next_block, completed): (i64, bool) = sqlx::query_as(
+        "SELECT next_block, completed_at IS NOT NULL FROM backfill_progress WHERE chain = 'flare' AND from_block = 100 AND to_block = 109",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(next_block, 110);
+    assert!(completed);
+
+    let (live,): (i64,) =
+        sqlx::query_as("SELECT last_block FROM indexer_state WHERE chain = 'flare'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(live, 5000);
+
+    // Re-running a finished range is a no-op
+    let stats = backfiller.run(100, 109).await.unwrap();
+    assert_eq!(stats.blocks, 0);
+
+    // An interrupted range resumes from its recorded position
+    backfiller.start_block(200, 209).await.unwrap();
+    backfiller.record_progress(200, 209, 206).await.unwrap();
+    let stats = backfiller.run(200, 209).await.unwrap();
+    assert_eq!(
+        stats,
+        BackfillStats {
+            blocks: 4,
+            events: 2
+        }
+    );
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_backfill_stops_at_live_cursor(pool: PgPool) {
+    setup(&pool).await;
+    let (rpc_url, _) = stub_rpc(StubChain::new(110, 1_000)).await;
+    sqlx::query("INSERT INTO indexer_state (chain, last_block) VALUES ('flare', 104)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // The range reaches past the live cursor: only blocks up to it are backfilled
+    let backfiller = Backfiller::new(rpc_url.clone(), Chain::Flare, pool.clone(), 4);
+    let stats = backfiller.run(100, 109).await.unwrap();
+    assert_eq!(stats.blocks, 5);
+
+    // The live poller stores the later blocks' events as live ones
+    let mut poller =
+        BlockPoller::new(rpc_url, 20, Chain::Flare, pool.clone(), 10).with_catch_up(1_000, 64);
+    tokio::select! {
+        result = poller.run() => panic!("poller exited: {:?}", result),
+        _ = wait_for_cursor(&pool, 110) => {}
+    }
+
+    let rows: Vec<(i64, bool)> = sqlx::query_as(
+        "SELECT block_number, backfilled FROM indexed_events WHERE block_number <> 104 ORDER BY block_number",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            (100, true),
+            (102, true),
+            (106, false),
+            (108, false),
+            (110, false)
+        ]
+    );
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_poller_catches_up_with_ranged_fetches(pool: PgPool) {
+    setup(&pool).await;
+    let (rpc_url, calls) = stub_rpc(StubChain::new(300, 32)).await;
+
+    sqlx::query("INSERT INTO indexer_state (chain, last_block) VALUES ('flare', 100)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // Windows of 64 are rejected and shrink to 32
+    let mut poller =
+        BlockPoller::new(rpc_url, 50, Chain::Flare, pool.clone(), 10).with_catch_up(5, 64);
+
+    let caught_up = async {
+        loop {
+            let (last,): (i64,) =
+                sqlx::query_as("SELECT last_block FROM indexer_state WHERE chain = 'flare'")
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            if last >= 300 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    };
+    tokio::select! {
+        result = poller.run() => panic!("poller exited: {:?}", result),
+        result = tokio::time::timeout(Duration::from_secs(20), caught_up) => {
+            result.expect("poller did not catch up to the tip")
+        }
+    }
+
+    let (events, distinct_blocks): (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*), COUNT(DISTINCT block_number) FROM indexed_events WHERE block_number BETWEEN 100 AND 300",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        events, 101,
+        "every even block from 100 to 300 has one event"
+    );
+    assert_eq!(distinct_blocks, 101);
+
+    let calls = calls.lock().unwrap();
+    let log_calls = calls.iter().filter(|(m, _)| m == "eth_getLogs").count();
+    let header_calls = calls
+        .iter()
+        .filter(|(m, _)| m == "eth_getBlockByNumber")
+        .count();
+    assert!(
+        log_calls < 30,
+        "expected ranged log fetches while behind, got {} calls",
+        log_calls
+    );
+    assert!(
+        header_calls >= 200,
+        "headers are still fetched for every block"
+    );
+}
+
+/// Block until the live cursor for flare reaches `block`.
+async fn wait_for_cursor(pool: &PgPool, block: i64) {
+    loop {
+        let row: Option<(i64,)> =
+            sqlx::query_as("SELECT last_block FROM indexer_state WHERE chain = 'flare'")
+                .fetch_optional(pool)
+                .await
+                .unwrap();
+        if row.is_some_and(|(last,)| last >= block) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+/// Index blocks 100..=110 per block, fork the stub chain at `fork_at`, mine block 111
+/// and wait until the poller has re-indexed through it.
+async fn run_through_reorg(pool: &PgPool, reorg_window: u64, fork_at: u64) {
+    let chain = StubChain::new(110, 1);
+    let (rpc_url, _) = stub_rpc(chain.clone()).await;
+
+    sqlx::query("INSERT INTO indexer_state (chain, last_block) VALUES ('flare', 100)")
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let mut poller = BlockPoller::new(rpc_url, 20, Chain::Flare, pool.clone(), reorg_window)
+        .with_catch_up(1_000, 1);
+
+    let reorged = async {
+        wait_for_cursor(pool, 110).await;
+        chain.fork_at.store(fork_at, Ordering::SeqCst);
+        chain.tip.store(111, Ordering::SeqCst);
+        loop {
+            let (reorgs,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM chain_reorgs")
+                .fetch_one(pool)
+                .await
+                .unwrap();
+            if reorgs > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        wait_for_cursor(pool, 111).await;
+    };
+    tokio::select! {
+        result = poller.run() => panic!("poller exited: {:?}", result),
+        result = tokio::time::timeout(Duration::from_secs(20), reorged) => {
+            result.expect("poller did not re-index through the reorg")
+        }
+    }
+}
+
+/// `(block_number, is_reorged)` of the event with tx hash seed `tx`, preferring its
+/// canonical row over rows left flagged by a reorg.
+async fn event_state(pool: &PgPool, tx: u64) -> (i64, bool) {
+    sqlx::query_as(
+        "SELECT block_number, is_reorged FROM indexed_events WHERE tx_hash = $1 ORDER BY is_reorged LIMIT 1",
+    )
+    .bind(format!("0x{:064x}", tx))
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_poller_reindexes_canonical_blocks_after_reorg(pool: PgPool) {
+    setup(&pool).await;
+    run_through_reorg(&pool, 10, 105).await;
+
+    // Blocks before the fork are untouched
+    assert_eq!(event_state(&pool, 104_000).await, (104, false));
+    // Re-included transactions get a canonical row in their new block
+    assert_eq!(event_state(&pool, 106_000).await, (107, false));
+    assert_eq!(event_state(&pool, 110_000).await, (111, false));
+    // A dropped transaction stays flagged
+    assert_eq!(event_state(&pool, 108_000).await, (108, true));
+
+    let (fork_block, detected_at, beyond_window, events_reorged): (i64, i64, bool, i64) =
+        sqlx::query_as(
+            "SELECT fork_block, detected_at_block, beyond_window, events_reorged FROM chain_reorgs WHERE chain = 'flare'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!((fork_block, detected_at), (105, 111));
+    assert!(!beyond_window);
+    assert_eq!(events_reorged, 3);
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_poller_flags_reorg_deeper_than_window(pool: PgPool) {
+    setup(&pool).await;
+    // The fork at 103 is older than the 3-block window (108..=110)
+    run_through_reorg(&pool, 3, 103).await;
+
+    let (fork_block, beyond_window): (i64, bool) =
+        sqlx::query_as("SELECT fork_block, beyond_window FROM chain_reorgs WHERE chain = 'flare'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        fork_block, 103,
+        "the fork point is found in the stored blocks"
+    );
+    assert!(beyond_window, "a deep reorg is flagged for the operator");
+
+    assert_eq!(event_state(&pool, 102_000).await, (102, false));
+    // Events below the window are rolled back too
+    assert_eq!(event_state(&pool, 104_000).await, (104, true));
+    assert_eq!(event_state(&pool, 106_000).await, (107, false));
+    assert_eq!(event_state(&pool, 108_000).await, (108, true));
+    assert_eq!(event_state(&pool, 110_000).await, (111, false));
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_poller_halts_on_reorg_older_than_stored_blocks(pool: PgPool) {
+    setup(&pool).await;
+    let chain = StubChain::new(110, 1);
+    let (rpc_url, _) = stub_rpc(chain.clone()).await;
+    sqlx::query("INSERT INTO indexer_state (chain, last_block) VALUES ('flare', 100)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let mut poller =
+        BlockPoller::new(rpc_url, 20, Chain::Flare, pool.clone(), 3).with_catch_up(1_000, 1);
+    let run = async {
+        tokio::select! {
+            result = poller.run() => result,
+            _ = async {
+                wait_for_cursor(&pool, 110).await;
+                // Every stored block (100..=110) is replaced
+                chain.fork_at.store(100, Ordering::SeqCst);
+                chain.tip.store(111, Ordering::SeqCst);
+                std::future::pending::<()>().await
+            } => unreachable!(),
+        }
+    };
+    let result = tokio::time::timeout(Duration::from_secs(20), run)
+        .await
+        .expect("poller kept running past an unlocatable fork");
+    assert!(result.is_err());
+
+    // Nothing is rolled back to a guessed fork point
+    let (reorgs,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM chain_reorgs")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(reorgs, 0);
+    assert_eq!(event_state(&pool, 108_000).await, (108, false));
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_reorg_across_restart_is_detected_from_stored_blocks(pool: PgPool) {
+    setup(&pool).await;
+    let chain = StubChain::new(110, 1);
+    let (rpc_url, _) = stub_rpc(chain.clone()).await;
+    sqlx::query("INSERT INTO indexer_state (chain, last_block) VALUES ('flare', 100)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let poller = || {
+        BlockPoller::new(rpc_url.clone(), 20, Chain::Flare, pool.clone(), 10)
+            .with_catch_up(1_000, 1)
+    };
+
+    let mut first = poller();
+    tokio::select! {
+        result = first.run() => panic!("poller exited: {:?}", result),
+        result = tokio::time::timeout(Duration::from_secs(20), wait_for_cursor(&pool, 110)) => {
+            result.expect("poller did not index up to 110")
+        }
+    }
+    drop(first);
+
+    // While the indexer is down, blocks from 108 on are replaced
+    chain.fork_at.store(108, Ordering::SeqCst);
+    chain.tip.store(111, Ordering::SeqCst);
+
+    let mut restarted = poller();
+    let reorged = async {
+        loop {
+            let (reorgs,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM chain_reorgs")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            if reorgs > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        wait_for_cursor(&pool, 111).await;
+    };
+    tokio::select! {
+        result = restarted.run() => panic!("poller exited: {:?}", result),
+        result = tokio::time::timeout(Duration::from_secs(20), reorged) => {
+            result.expect("reorg across the restart was not detected")
+        }
+    }
+
+    let (fork_block, beyond_window): (i64, bool) =
+        sqlx::query_as("SELECT fork_block, beyond_window FROM chain_reorgs WHERE chain = 'flare'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(fork_block, 108);
+    assert!(!beyond_window);
+    assert_eq!(event_state(&pool, 108_000).await, (108, true));
+
+    // The replaced blocks stay in the audit trail, no longer canonical
+    let blocks: Vec<(i64, String, bool)> = sqlx::query_as(
+        "SELECT block_number, block_hash, is_canonical FROM indexed_blocks WHERE chain = 'flare' AND block_number >= 108 ORDER BY block_number, is_canonical",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    let expected: Vec<(i64, String, bool)> = vec![
+        (108, format!("0x{:064x}", 108), false),
+        (108, chain.hash(108), true),
+        (109, format!("0x{:064x}", 109), false),
+        (109, chain.hash(109), true),
+        (110, format!("0x{:064x}", 110), false),
+        (110, chain.hash(110), true),
+        (111, chain.hash(111), true),
+    ];
+    assert_eq!(blocks, expected);
+
+    // The canonical chain links up through the fork
+    let (canonical,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM indexed_blocks WHERE chain = 'flare' AND is_canonical AND block_number BETWEEN 100 AND 111",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(canonical, 12);
+}
+
+/// Run a registry-backed poller from block 100 to 160 while the registry switches
+/// every contract to a new address at block 130, and check that the filter and the
+/// history switch over at `switched_from`. With `ranged`, the poller catches up
+/// with ranged fetches instead of polling block by block.
+async fn run_through_registry_switch(
+    pool: &PgPool,
+    refresh_blocks: u64,
+    updater_emits_logs: bool,
+    ranged: bool,
+    switched_from: u64,
+) {
+    let chain = StubChain::new(160, 1_000);
+    chain.registry_switch_at.store(130, Ordering::SeqCst);
+    if updater_emits_logs {
+        chain.updater.store(0x1234, Ordering::SeqCst);
+    }
+    let (rpc_url, calls) = stub_rpc(chain).await;
+
+    sqlx::query("INSERT INTO indexer_state (chain, last_block) VALUES ('flare', 100)")
+        .execute(pool)
+        .await
+        .unwrap();
+
+    let mut poller = BlockPoller::new(rpc_url, 20, Chain::Flare, pool.clone(), 10)
+        .with_catch_up(if ranged { 1 } else { 1_000 }, 64)
+        .with_registry(
+            FlareContractRegistry::for_chain(Chain::Flare),
+            refresh_blocks,
+        );
+    tokio::select! {
+        result = poller.run() => panic!("poller exited: {:?}", result),
+        _ = wait_for_cursor(pool, 160) => {}
+    }
+
+    let history: Vec<(String, i64, Option<i64>)> = sqlx::query_as(
+        r#"
+        SELECT address, from_block, to_block FROM contract_address_history
+        WHERE chain = 'flare' AND contract_name = 'FtsoManager'
+        ORDER BY from_block
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap();
+    let address = |suffix: &str| format!("0x{:0>40}", suffix);
+    assert_eq!(
+        history,
+        vec![
+            (address("1234"), 100, Some(switched_from as i64 - 1)),
+            (address("5678"), switched_from as i64, None),
+        ]
+    );
+
+    // Blocks from `switched_from` on are fetched with the new address in the filter
+    let calls = calls.lock().unwrap();
+    let log_filters: Vec<(u64, String)> = calls
+        .iter()
+        .filter(|(m, _)| m == "eth_getLogs")
+        .map(|(_, params)| {
+            let from = params[0]["fromBlock"]
+                .as_str()
+                .unwrap()
+                .trim_start_matches("0x");
+            (
+                u64::from_str_radix(from, 16).unwrap(),
+                params[0]["address"].to_string(),
+            )
+        })
+        .collect();
+    for (from, addresses) in &log_filters {
+        assert_eq!(
+            addresses.contains("5678"),
+            *from >= switched_from,
+            "filter for block {}: {}",
+            from,
+            addresses
+        );
+    }
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_poller_re_resolves_registry_periodically(pool: PgPool) {
+    setup(&pool).await;
+    // Refreshed at 100, 110, ..., 130 (state of block 129) and 140
+    run_through_registry_switch(&pool, 10, false, false, 140).await;
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_poller_re_resolves_registry_on_address_updater_logs(pool: PgPool) {
+    setup(&pool).await;
+    // With periodic refreshes off, the AddressUpdater log in block 130 triggers
+    // re-resolution before block 131
+    run_through_registry_switch(&pool, 0, true, false, 131).await;
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_poller_stops_ranged_fetch_at_registry_update(pool: PgPool) {
+    setup(&pool).await;
+    // A range holding the AddressUpdater log of block 130 is committed up to 130
+    // only; 131..=160 is fetched again with the re-resolved filter
+    run_through_registry_switch(&pool, 0, true, true, 131).await;
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_addresses_in_use_since_covers_replaced_contracts(pool: PgPool) {
+    setup(&pool).await;
+    let resolve = |byte: u8| ResolvedAddresses {
+        addresses: HashMap::from([("AssetManager".to_string(), Address::repeat_byte(byte))]),
+    };
+
+    let changes = address_history::record_resolution(&pool, Chain::Flare, 100, &resolve(0xaa))
+        .await
+        .unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].previous, None);
+
+    // Unchanged addresses are not re-recorded
+    let changes = address_history::record_resolution(&pool, Chain::Flare, 150, &resolve(0xaa))
+        .await
+        .unwrap();
+    assert!(changes.is_empty());
+
+    let changes = address_history::record_resolution(&pool, Chain::Flare, 200, &resolve(0xbb))
+        .await
+        .unwrap();
+    assert_eq!(changes[0].previous, Some(Address::repeat_byte(0xaa)));
+
+    let since = |block| address_history::contracts_in_use_since(&pool, Chain::Flare, block);
+    let name = "AssetManager".to_string();
+    assert_eq!(
+        since(50).await.unwrap(),
+        vec![
+            (name.clone(), Address::repeat_byte(0xaa)),
+            (name.clone(), Address::repeat_byte(0xbb))
+        ]
+    );
+    assert_eq!(
+        since(250).await.unwrap(),
+        vec![(name, Address::repeat_byte(0xbb))]
+    );
+    assert!(
+        address_history::contracts_in_use_since(&pool, Chain::Coston2, 0)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_poller_tags_events_from_asset_managers_with_fasset_symbol(pool: PgPool) {
+    setup(&pool).await;
+    let (rpc_url, _) = stub_rpc(StubChain::new(110, 1_000)).await;
+    sqlx::query("INSERT INTO indexer_state (chain, last_block) VALUES ('flare', 100)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let mut poller = BlockPoller::new(rpc_url, 20, Chain::Flare, pool.clone(), 10)
+        .with_catch_up(1_000, 64)
+        .with_registry(FlareContractRegistry::for_chain(Chain::Flare), 1_000);
+    tokio::select! {
+        result = poller.run() => panic!("poller exited: {:?}", result),
+        _ = wait_for_cursor(&pool, 110) => {}
+    }
+
+    // The stub's log emitter is the FXRP AssetManager
+    let tags: Vec<(Option<String>,)> = sqlx::query_as(
+        "SELECT decoded_data->>'fasset' FROM indexed_events WHERE block_number BETWEEN 100 AND 110",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(tags.len(), 6);
+    assert!(tags.iter().all(|(tag,)| tag.as_deref() == Some("FXRP")));
+
+    let (address,): (String,) = sqlx::query_as(
+        "SELECT address FROM contract_address_history WHERE contract_name = 'AssetManager:FXRP' AND to_block IS NULL",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(address, format!("0x{:0>40}", "1234"));
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_poller_indexes_addresses_with_generic_event_subscriptions(pool: PgPool) {
+    setup(&pool).await;
+    let subscribed = Address::repeat_byte(0xab);
+    let unsubscribed = Address::repeat_byte(0xde);
+    let chain = StubChain::new(110, 1_000);
+    *chain.unknown_emitters.lock().unwrap() = vec![subscribed, unsubscribed];
+    let (rpc_url, _) = stub_rpc(chain.clone()).await;
+    sqlx::query("INSERT INTO indexer_state (chain, last_block) VALUES ('flare', 100)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let mut poller = BlockPoller::new(rpc_url, 20, Chain::Flare, pool.clone(), 10)
+        .with_catch_up(1_000, 64)
+        .with_contract_addresses(vec![format!("0x{:0>40}", "1234").parse().unwrap()])
+        .with_generic_subscriptions(Duration::ZERO);
+
+    let subscribe = async {
+        wait_for_cursor(&pool, 110).await;
+        // Subscribed mid-run: picked up from the next block on
+        sqlx::query(
+            r#"
+            WITH u AS (
+                INSERT INTO users (wallet_address) VALUES ('0xgeneric') RETURNING id
+            ), a AS (
+                INSERT INTO monitored_addresses (address, chain, address_type)
+                VALUES ($1, 'flare', 'generic_contract') RETURNING id
+            ), c AS (
+                INSERT INTO notification_channels (user_id, channel_type)
+                SELECT id, 'webhook' FROM u RETURNING id, user_id
+            )
+            INSERT INTO subscriptions (user_id, address_id, channel_id, event_type)
+            SELECT c.user_id, a.id, c.id, 'generic_event' FROM a, c
+            "#,
+        )
+        .bind(format!("{:#x}", subscribed))
+        .execute(&pool)
+        .await
+        .unwrap();
+        chain.tip.store(120, Ordering::SeqCst);
+        wait_for_cursor(&pool, 120).await;
+    };
+    tokio::select! {
+        result = poller.run() => panic!("poller exited: {:?}", result),
+        _ = subscribe => {}
+    }
+
+    let generic: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT address, block_number FROM indexed_events WHERE event_type = 'generic_event' ORDER BY block_number",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert!(!generic.is_empty());
+    assert!(
+        generic
+            .iter()
+            .all(|(address, block)| *address == format!("{:#x}", subscribed) && *block > 110)
+    );
+    assert_eq!(generic.last().unwrap().1, 120);
+
+    // Protocol events are still indexed alongside
+    let (protocol,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM indexed_events WHERE event_type <> 'generic_event' AND block_number BETWEEN 100 AND 120",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(protocol, 11);
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_poller_writes_price_ticks_from_fast_updates(pool: PgPool) {
+    setup(&pool).await;
+    let chain = StubChain::new(110, 1_000);
+    chain.fast_updates.store(true, Ordering::SeqCst);
+    let (rpc_url, _) = stub_rpc(chain).await;
+    sqlx::query("INSERT INTO indexer_state (chain, last_block) VALUES ('flare', 100)")
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let mut poller = BlockPoller::new(rpc_url, 20, Chain::Flare, pool.clone(), 10)
+        .with_catch_up(1_000, 64)
+        .with_registry(FlareContractRegistry::for_chain(Chain::Flare), 1_000);
+    tokio::select! {
+        result = poller.run() => panic!("poller exited: {:?}", result),
+        _ = wait_for_cursor(&pool, 110) => {}
+    }
+
+    let flr_usd =
+        flare_decoders::ftso::feed_id_hex(&flare_decoders::ftso::feed_id(1, "FLR/USD").unwrap());
+    let ticks: Vec<(String, String, i16, i64)> = sqlx::query_as(
+        r#"
+        SELECT feed_id, price::text, decimals, block_number FROM ftso_price_ticks
+        WHERE chain = 'flare' AND block_number = 104
+        ORDER BY feed_id DESC
+        "#,
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(ticks.len(), 2);
+    assert_eq!(ticks[0].0, flr_usd);
+    assert_eq!(ticks[0].1.parse::<f64>().unwrap(), 1.04);
+    assert_eq!(ticks[0].2, 2);
+    assert_eq!(ticks[1].1.parse::<f64>().unwrap(), 0.208);
+
+    let count = || async {
+        let (count,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM ftso_price_ticks WHERE chain = 'flare'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        count
+    };
+    assert_eq!(count().await, 22);
+
+    // Ticks from reorged blocks are dropped with the rollback
+    poller
+        .rewind(&Reorg {
+            fork_block: 108,
+            detected_at: 110,
+            beyond_window: false,
+        })
+        .await
+        .unwrap();
+    assert_eq!(count().await, 16);
+}
+
+#[sqlx::test]
+#[ignore]
+async fn test_rewind_drops_vault_snapshots_of_reorged_blocks(pool: PgPool) {
+    setup(&pool).await;
+    let (rpc_url, _) = stub_rpc(StubChain::new(110, 1_000)).await;
+    for (chain, block) in [
+        ("flare", 105i64),
+        ("flare", 108),
+        ("flare", 109),
+        ("songbird", 108),
+    ] {
+        sqlx::query(
+            r#"
+            INSERT INTO agent_vault_snapshots (
+                chain, agent, cr, vault_collateral, pool_collateral,
+                minted_lots, reserved_lots, redeeming_lots, block_number, recorded_at
+            )
+            VALUES ($1, '0xagent', 2.0, 100, 0, 10, 0, 0, $2, NOW() + $2::float8 * INTERVAL '1 second')
+            "#,
+        )
+        .bind(chain)
+        .bind(block)
+        .execute(&pool)
+        .await
+        .unwrap();
+    }
+
+    let poller = BlockPoller::new(rpc_url, 20, Chain::Flare, pool.clone(), 10);
+    poller
+        .rewind(&Reorg {
+            fork_block: 108,
+            detected_at: 110,
+            beyond_window: false,
+        })
+        .await
+        .unwrap();
+
+    // Only the chain's snapshots from the fork block on are dropped
+    let remaining: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT chain, block_number FROM agent_vault_snapshots ORDER BY chain, block_number",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+        remaining,
+        vec![("flare".to_string(), 105), ("songbird".to_string(), 108)]
+    );
+}
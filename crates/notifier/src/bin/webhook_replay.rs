@@ -0,0 +1,102 @@
+//! FlareEmissary webhook replay tool
+//!
+//! Re-sends stored alerts to a webhook, signed like live deliveries but marked
+//! `"replay": true`.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! # Replay to the webhook channel of each alert's own subscription
+//! cargo run --bin flare-webhook-replay -- --alert <ALERT_ID> [--alert <ALERT_ID> ...]
+//!
+//! # Replay to a specific webhook channel
+//! cargo run --bin flare-webhook-replay -- --alert <ALERT_ID> --channel <CHANNEL_ID>
+//!
+//! # Replay to an arbitrary endpoint
+//! cargo run --bin flare-webhook-replay -- --alert <ALERT_ID> --url https://... --secret <SECRET>
+//! ```
+//!
+//! Reads `DATABASE_URL` and `WEBHOOK_ALLOW_PRIVATE_TARGETS` from the environment.
+
+use uuid::Uuid;
+
+use flare_common::config::AppConfig;
+use flare_common::db;
+use flare_notifier::replay::{ReplayTarget, replay_alert};
+use flare_notifier::webhook::WebhookSender;
+
+const USAGE: &str = "usage: flare-webhook-replay --alert <ID> [--alert <ID> ...] \
+                     [--channel <ID> | --url <URL> --secret <SECRET>]";
+
+/// Parsed command-line arguments.
+struct Args {
+    alerts: Vec<Uuid>,
+    target: ReplayTarget,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> anyhow::Result<Args> {
+    let mut alerts = Vec::new();
+    let mut channel = None;
+    let mut url = None;
+    let mut secret = None;
+
+    let mut args = args.peekable();
+    while let Some(flag) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("{} requires a value\n{}", flag, USAGE))
+        };
+        match flag.as_str() {
+            "--alert" => alerts.push(value()?.parse()?),
+            "--channel" => channel = Some(value()?.parse()?),
+            "--url" => url = Some(value()?),
+            "--secret" => secret = Some(value()?),
+            "-h" | "--help" => anyhow::bail!(USAGE),
+            other => anyhow::bail!("unknown argument '{}'\n{}", other, USAGE),
+        }
+    }
+
+    if alerts.is_empty() {
+        anyhow::bail!("at least one --alert is required\n{}", USAGE);
+    }
+
+    let target = match (channel, url, secret) {
+        (None, None, None) => ReplayTarget::Subscription,
+        (Some(id), None, None) => ReplayTarget::Channel(id),
+        (None, Some(url), Some(secret)) => ReplayTarget::Url { url, secret },
+        _ => anyhow::bail!("use either --channel, or --url with --secret\n{}", USAGE),
+    };
+
+    Ok(Args { alerts, target })
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "flare_notifier=info".into()),
+        )
+        .init();
+
+    let args = parse_args(std::env::args().skip(1))?;
+    let config = AppConfig::from_env()?;
+    let pool = db::create_pool(&config.database_url, 2).await?;
+    let sender = WebhookSender::new(config.webhook_allow_private_targets);
+
+    let mut failures = 0;
+    for alert_id in &args.alerts {
+        match replay_alert(&pool, &sender, *alert_id, &args.target).await {
+            Ok(delivery_id) => println!("{} replayed (delivery {})", alert_id, delivery_id),
+            Err(e) => {
+                failures += 1;
+                eprintln!("{} failed: {:#}", alert_id, e);
+            }
+        }
+    }
+
+    if failures > 0 {
+        anyhow::bail!("{} of {} replays failed", failures, args.alerts.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,528 @@
+//! Discord delivery via webhooks or bot DMs.
+//!
+//! Channel config is one of:
+//! - `{ "webhook_url": "https://discord.com/api/webhooks/<id>/<token>" }` — posts to a
+//!   server channel; needs no bot token.
+//! - `{ "user_id": "<snowflake>" }` — DMs the user from the bot; requires
+//!   `DISCORD_BOT_TOKEN` and the user must share a server with the bot.
+//!
+//! Channels are verified by posting a one-time code to the destination with
+//! `send_verification`; the owner submits it back to the API.
+//!
+//! Alerts are rendered as a single embed coloured by severity. Discord 429 responses
+//! are honoured: short `retry_after` waits are slept through in place, longer ones
+//! surface as `DeliveryError::RateLimited`.
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+
+use flare_common::types::{ChannelType, Severity};
+
+use crate::sender::{Delivery, DeliveryError, NotificationSender, SendFuture, error_for_status};
+
+/// Timeout for a single Discord API call.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many 429 responses are waited out before giving up on an attempt.
+const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Longest `retry_after` slept through in place; longer waits fail the attempt.
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(10);
+
+/// Ceiling on a reported `retry_after`, so a bogus value can't overflow the delay.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Maximum number of decoded-event metadata fields added to an embed.
+const MAX_METADATA_FIELDS: usize = 6;
+
+/// Discord's limit on an embed field value.
+const MAX_FIELD_VALUE_LEN: usize = 1024;
+
+/// Sends alerts to Discord as embeds.
+pub struct DiscordSender {
+    client: reqwest::Client,
+    api_base_url: String,
+    bot_token: Option<String>,
+}
+
+impl DiscordSender {
+    /// Create a sender. `api_base_url` is normally `https://discord.com/api/v10`.
+    /// Without a bot token only webhook channels can be delivered to.
+    pub fn new(api_base_url: impl Into<String>, bot_token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            api_base_url: api_base_url.into().trim_end_matches('/').to_string(),
+            bot_token,
+        }
+    }
+
+    /// Embed colour for a severity.
+    pub fn severity_color(severity: Severity) -> u32 {
+        match severity {
+            Severity::Info => 0x3498DB,
+            Severity::Warning => 0xF1C40F,
+            Severity::Critical => 0xE74C3C,
+        }
+    }
+
+    /// Render a delivery as a Discord embed.
+    pub fn build_embed(delivery: &Delivery) -> Value {
+        let event = &delivery.event;
+        let payload = &delivery.payload;
+        let explorer = event.chain.explorer_url();
+        let tx_url = format!("{}/tx/{}", explorer, event.tx_hash);
+        let block_url = format!("{}/block/{}", explorer, event.block_number);
+        let address_url = format!("{}/address/{}", explorer, event.address);
+
+        let mut fields = vec![
+            json!({
+                "name": "Tx Hash",
+                "value": format!("[`{}`]({})", event.tx_hash, tx_url),
+                "inline": false,
+            }),
+            json!({
+                "name": "Block",
+                "value": format!("[{}]({})", event.block_number, block_url),
+                "inline": true,
+            }),
+            json!({
+                "name": "Address",
+                "value": format!("[`{}`]({})", event.address, address_url),
+                "inline": true,
+            }),
+        ];
+
+        if let Some(metadata) = payload.metadata.as_object() {
+            fields.extend(
+                metadata
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        let value = match value {
+                            Value::String(s) => s.clone(),
+                            Value::Number(n) => n.to_string(),
+                            Value::Bool(b) => b.to_string(),
+                            _ => return None,
+                        };
+                        Some(json!({
+                            "name": key,
+                            "value": truncate(&value, MAX_FIELD_VALUE_LEN),
+                            "inline": true,
+                        }))
+                    })
+                    .take(MAX_METADATA_FIELDS),
+            );
+        }
+
+        json!({
+            "title": payload.title,
+            "description": payload.body,
+            "url": tx_url,
+            "color": Self::severity_color(payload.severity),
+            "fields": fields,
+            "footer": { "text": format!("FlareEmissary · {}", event.chain) },
+            "timestamp": event.block_timestamp.to_rfc3339(),
+        })
+    }
+
+    /// Post a channel's verification code to its Discord destination.
+    pub async fn send_verification(&self, config: &Value, code: &str) -> Result<(), DeliveryError> {
+        let message = json!({
+            "content": format!(
+                "Your FlareEmissary verification code is **{}**. Enter it in FlareEmissary to start receiving alerts here.",
+                code
+            ),
+        });
+        self.post(config, &message).await
+    }
+
+    /// Post a message to the webhook or DM named in the channel config.
+    async fn post(&self, config: &Value, message: &Value) -> Result<(), DeliveryError> {
+        if let Some(webhook_url) = config.get("webhook_url").and_then(Value::as_str) {
+            self.send_webhook(webhook_url, message).await
+        } else if let Some(user_id) = config.get("user_id").and_then(Value::as_str) {
+            self.send_dm(user_id, message).await
+        } else {
+            Err(DeliveryError::InvalidConfig(
+                "Discord channel needs webhook_url or user_id".to_string(),
+            ))
+        }
+    }
+
+    /// Post a message through a webhook.
+    async fn send_webhook(&self, webhook_url: &str, message: &Value) -> Result<(), DeliveryError> {
+        let (id, token) = parse_webhook_url(webhook_url).ok_or_else(|| {
+            DeliveryError::InvalidConfig(format!("invalid Discord webhook URL: {}", webhook_url))
+        })?;
+        let url = format!("{}/webhooks/{}/{}?wait=true", self.api_base_url, id, token);
+
+        self.execute(|| self.client.post(&url).json(message))
+            .await?;
+        Ok(())
+    }
+
+    /// Open (or reuse) the DM channel with a user and post the message there.
+    async fn send_dm(&self, user_id: &str, message: &Value) -> Result<(), DeliveryError> {
+        let bot_token = self.bot_token.as_deref().ok_or_else(|| {
+            DeliveryError::InvalidConfig(
+                "Discord DM delivery requires DISCORD_BOT_TOKEN".to_string(),
+            )
+        })?;
+        let auth = format!("Bot {}", bot_token);
+
+        let url = format!("{}/users/@me/channels", self.api_base_url);
+        let body = json!({ "recipient_id": user_id });
+        let dm_channel: Value = self
+            .execute(|| {
+                self.client
+                    .post(&url)
+                    .header("Authorization", &auth)
+                    .json(&body)
+            })
+            .await?
+            .json()
+            .await?;
+
+        let dm_channel_id = dm_channel["id"]
+            .as_str()
+            .ok_or_else(|| DeliveryError::Rejected {
+                status: 200,
+                body: format!("DM channel response missing id: {}", dm_channel),
+            })?;
+
+        let url = format!("{}/channels/{}/messages", self.api_base_url, dm_channel_id);
+        self.execute(|| {
+            self.client
+                .post(&url)
+                .header("Authorization", &auth)
+                .json(message)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Send a request, waiting out short 429 rate limits.
+    ///
+    /// `build` is called once per try since a sent request cannot be reused.
+    async fn execute(
+        &self,
+        build: impl Fn() -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, DeliveryError> {
+        let mut retries = 0;
+        loop {
+            let response = build().send().await?;
+            if response.status() != StatusCode::TOO_MANY_REQUESTS {
+                return error_for_status(response).await;
+            }
+
+            let retry_after = retry_after(response).await;
+            if retries >= MAX_RATE_LIMIT_RETRIES || retry_after > MAX_RATE_LIMIT_WAIT {
+                return Err(DeliveryError::RateLimited { retry_after });
+            }
+
+            retries += 1;
+            tracing::warn!(
+                retry_after_ms = retry_after.as_millis() as u64,
+                retries,
+                "Discord rate limit hit, waiting"
+            );
+            tokio::time::sleep(retry_after).await;
+        }
+    }
+}
+
+impl NotificationSender for DiscordSender {
+    fn channel_type(&self) -> ChannelType {
+        ChannelType::Discord
+    }
+
+    fn send<'a>(&'a self, delivery: &'a Delivery) -> SendFuture<'a> {
+        Box::pin(async move {
+            let message = json!({ "embeds": [Self::build_embed(delivery)] });
+            self.post(&delivery.channel.config, &message).await
+        })
+    }
+}
+
+/// Extract `(id, token)` from a Discord webhook URL.
+///
+/// Only the ID and token are taken from user-supplied config; requests always go to
+/// the configured API base URL, so a channel cannot point the notifier at other hosts.
+fn parse_webhook_url(url: &str) -> Option<(&str, &str)> {
+    let (_, rest) = url.split_once("/webhooks/")?;
+    let mut parts = rest.trim_end_matches('/').split('/');
+    let id = parts.next()?;
+    let token = parts.next()?.split(['?', '#']).next()?;
+
+    let valid = !id.is_empty()
+        && id.chars().all(|c| c.is_ascii_digit())
+        && !token.is_empty()
+        && token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        && parts.next().is_none();
+    valid.then_some((id, token))
+}
+
+/// Read the wait duration from a 429 response.
+///
+/// Discord reports `retry_after` (seconds, fractional) in the JSON body; the
+/// `Retry-After` header is used as a fallback. Waits are capped at `MAX_RETRY_AFTER`.
+async fn retry_after(response: reqwest::Response) -> Duration {
+    let header = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<f64>().ok());
+    let body = response
+        .json::<Value>()
+        .await
+        .ok()
+        .and_then(|v| v["retry_after"].as_f64());
+
+    body.or(header)
+        .filter(|secs| !secs.is_nan() && *secs >= 0.0)
+        .map(|secs| {
+            Duration::try_from_secs_f64(secs)
+                .unwrap_or(MAX_RETRY_AFTER)
+                .min(MAX_RETRY_AFTER)
+        })
+        .unwrap_or(Duration::from_secs(1))
+}
+
+/// Truncate to at most `max` characters.
+fn truncate(s: &str, max: usize) -> String {
+    match s.char_indices().nth(max) {
+        Some((idx, _)) => s[..idx].to_string(),
+        None => s.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{Captured, delivery, spawn_stub};
+    use axum::extract::{OriginalUri, State};
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Stub Discord API: the first `rate_limited` calls return 429 with `retry_after`,
+    /// then every call succeeds. Records (uri, body) of successful calls.
+    async fn stub_discord(rate_limited: usize, retry_after: f64) -> (String, Captured) {
+        #[derive(Clone)]
+        struct Stub {
+            captured: Captured,
+            calls: Arc<AtomicUsize>,
+            rate_limited: usize,
+            retry_after: f64,
+        }
+
+        async fn handle(
+            State(stub): State<Stub>,
+            OriginalUri(uri): OriginalUri,
+            headers: HeaderMap,
+            Json(body): Json<Value>,
+        ) -> (axum::http::StatusCode, Json<Value>) {
+            if stub.calls.fetch_add(1, Ordering::SeqCst) < stub.rate_limited {
+                return (
+                    axum::http::StatusCode::TOO_MANY_REQUESTS,
+                    Json(json!({ "retry_after": stub.retry_after, "global": false })),
+                );
+            }
+            let auth = headers
+                .get("authorization")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default();
+            stub.captured
+                .lock()
+                .unwrap()
+                .push((format!("{} {}", uri, auth).trim().to_string(), body));
+            (axum::http::StatusCode::OK, Json(json!({ "id": "555" })))
+        }
+
+        let captured = Captured::default();
+        let stub = Stub {
+            captured: captured.clone(),
+            calls: Arc::default(),
+            rate_limited,
+            retry_after,
+        };
+        let router = Router::new()
+            .route("/webhooks/{id}/{token}", post(handle))
+            .route("/users/@me/channels", post(handle))
+            .route("/channels/{id}/messages", post(handle))
+            .with_state(stub);
+        (spawn_stub(router).await, captured)
+    }
+
+    const WEBHOOK_URL: &str = "https://discord.com/api/webhooks/1234567890/tok-EN_abc";
+
+    #[tokio::test]
+    async fn test_webhook_posts_embed() {
+        let (base_url, captured) = stub_discord(0, 0.0).await;
+        let sender = DiscordSender::new(base_url, None);
+
+        let delivery = delivery(ChannelType::Discord, json!({ "webhook_url": WEBHOOK_URL }));
+        sender.send(&delivery).await.unwrap();
+
+        let captured = captured.lock().unwrap();
+        assert_eq!(captured.len(), 1);
+        let (uri, body) = &captured[0];
+        assert_eq!(uri, "/webhooks/1234567890/tok-EN_abc?wait=true");
+
+        let embed = &body["embeds"][0];
+        assert_eq!(embed["color"], 0xE74C3C);
+        assert_eq!(
+            embed["url"],
+            "https://flare-explorer.flare.network/tx/0xfeedbeef"
+        );
+        let names: Vec<&str> = embed["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(&names[..3], ["Tx Hash", "Block", "Address"]);
+        assert!(names.contains(&"agent"), "metadata should become fields");
+    }
+
+    #[tokio::test]
+    async fn test_dm_opens_channel_then_posts() {
+        let (base_url, captured) = stub_discord(0, 0.0).await;
+        let sender = DiscordSender::new(base_url, Some("bot-token".to_string()));
+
+        let delivery = delivery(ChannelType::Discord, json!({ "user_id": "42" }));
+        sender.send(&delivery).await.unwrap();
+
+        let captured = captured.lock().unwrap();
+        assert_eq!(captured.len(), 2);
+        assert_eq!(captured[0].0, "/users/@me/channels Bot bot-token");
+        assert_eq!(captured[0].1["recipient_id"], "42");
+        assert_eq!(captured[1].0, "/channels/555/messages Bot bot-token");
+        assert!(captured[1].1["embeds"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_verification_code_posted_to_destination() {
+        let (base_url, captured) = stub_discord(0, 0.0).await;
+        let sender = DiscordSender::new(base_url, Some("bot-token".to_string()));
+
+        sender
+            .send_verification(&json!({ "webhook_url": WEBHOOK_URL }), "AB12CD34")
+            .await
+            .unwrap();
+        sender
+            .send_verification(&json!({ "user_id": "42" }), "EF56AB78")
+            .await
+            .unwrap();
+
+        let captured = captured.lock().unwrap();
+        assert_eq!(captured.len(), 3);
+        assert_eq!(captured[0].0, "/webhooks/1234567890/tok-EN_abc?wait=true");
+        assert!(
+            captured[0].1["content"]
+                .as_str()
+                .unwrap()
+                .contains("AB12CD34")
+        );
+        assert_eq!(captured[2].0, "/channels/555/messages Bot bot-token");
+        assert!(
+            captured[2].1["content"]
+                .as_str()
+                .unwrap()
+                .contains("EF56AB78")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_is_waited_out() {
+        let (base_url, captured) = stub_discord(2, 0.01).await;
+        let sender = DiscordSender::new(base_url, None);
+
+        let delivery = delivery(ChannelType::Discord, json!({ "webhook_url": WEBHOOK_URL }));
+        sender.send(&delivery).await.unwrap();
+        assert_eq!(captured.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_long_rate_limit_surfaces_retry_after() {
+        let (base_url, captured) = stub_discord(1, 60.0).await;
+        let sender = DiscordSender::new(base_url, None);
+
+        let delivery = delivery(ChannelType::Discord, json!({ "webhook_url": WEBHOOK_URL }));
+        let err = sender.send(&delivery).await.unwrap_err();
+        assert!(matches!(
+            err,
+            DeliveryError::RateLimited { retry_after } if retry_after == Duration::from_secs(60)
+        ));
+        assert!(captured.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_huge_retry_after_is_capped() {
+        let (base_url, _) = stub_discord(1, 1e20).await;
+        let sender = DiscordSender::new(base_url, None);
+
+        let delivery = delivery(ChannelType::Discord, json!({ "webhook_url": WEBHOOK_URL }));
+        let err = sender.send(&delivery).await.unwrap_err();
+        assert!(matches!(
+            err,
+            DeliveryError::RateLimited { retry_after } if retry_after == MAX_RETRY_AFTER
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config() {
+        let sender = DiscordSender::new("http://127.0.0.1:9", None);
+
+        for config in [
+            json!({}),
+            json!({ "user_id": "42" }),
+            json!({ "webhook_url": "https://evil.example/hook" }),
+        ] {
+            let delivery = delivery(ChannelType::Discord, config);
+            let err = sender.send(&delivery).await.unwrap_err();
+            assert!(matches!(err, DeliveryError::InvalidConfig(_)));
+        }
+    }
+
+    #[test]
+    fn test_parse_webhook_url() {
+        assert_eq!(
+            parse_webhook_url("https://discord.com/api/webhooks/123/abc-DEF_9"),
+            Some(("123", "abc-DEF_9"))
+        );
+        assert_eq!(
+            parse_webhook_url("https://discordapp.com/api/webhooks/123/abc?thread_id=1"),
+            Some(("123", "abc"))
+        );
+        assert_eq!(
+            parse_webhook_url("https://discord.com/api/webhooks/123"),
+            None
+        );
+        assert_eq!(
+            parse_webhook_url("https://discord.com/api/webhooks/abc/tok"),
+            None
+        );
+        assert_eq!(
+            parse_webhook_url("https://discord.com/api/webhooks/123/tok/../../x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_severity_colors_are_distinct() {
+        let colors = [Severity::Info, Severity::Warning, Severity::Critical]
+            .map(DiscordSender::severity_color);
+        assert_ne!(colors[0], colors[1]);
+        assert_ne!(colors[1], colors[2]);
+        assert_ne!(colors[0], colors[2]);
+    }
+}
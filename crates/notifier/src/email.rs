@@ -0,0 +1,361 @@
+//! Email delivery via the Resend API.
+//!
+//! Channel config: `{ "email": "user@example.com" }`. Email channels are verified by
+//! a confirm-your-address email (see `verification_email`), so the address must
+//! belong to the channel owner before any alert is sent to it.
+//!
+//! Every alert email carries a signed unsubscribe link for the originating
+//! subscription, both in the body and as RFC 8058 `List-Unsubscribe` headers.
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde_json::json;
+
+use flare_common::types::{ChannelType, Severity};
+use flare_engine::subscription::SubscriptionService;
+
+use crate::sender::{Delivery, DeliveryError, NotificationSender, SendFuture, error_for_status};
+
+/// Timeout for a single Resend API call.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A rendered email ready to hand to the provider.
+#[derive(Debug, Clone)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+    /// Extra MIME headers (e.g., `List-Unsubscribe`)
+    pub headers: Vec<(String, String)>,
+}
+
+/// Thin client for Resend's `POST /emails` endpoint.
+pub struct ResendClient {
+    client: reqwest::Client,
+    api_base_url: String,
+    api_key: String,
+    from: String,
+}
+
+impl ResendClient {
+    /// Create a client. `api_base_url` is normally `https://api.resend.com`.
+    pub fn new(
+        api_base_url: impl Into<String>,
+        api_key: impl Into<String>,
+        from: impl Into<String>,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            api_base_url: api_base_url.into().trim_end_matches('/').to_string(),
+            api_key: api_key.into(),
+            from: from.into(),
+        }
+    }
+
+    /// Send one email. Resend builds the multipart/alternative body from `html` + `text`.
+    pub async fn send(&self, message: &EmailMessage) -> Result<(), DeliveryError> {
+        let headers: serde_json::Map<String, serde_json::Value> = message
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), json!(value)))
+            .collect();
+
+        let response = self
+            .client
+            .post(format!("{}/emails", self.api_base_url))
+            .bearer_auth(&self.api_key)
+            .json(&json!({
+                "from": self.from,
+                "to": [message.to],
+                "subject": message.subject,
+                "html": message.html,
+                "text": message.text,
+                "headers": headers,
+            }))
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(Duration::from_secs(1));
+            return Err(DeliveryError::RateLimited { retry_after });
+        }
+
+        error_for_status(response).await?;
+        Ok(())
+    }
+}
+
+/// Sends alert emails with an unsubscribe link per subscription.
+pub struct EmailSender {
+    client: ResendClient,
+    public_api_url: String,
+    signing_key: [u8; 32],
+}
+
+impl EmailSender {
+    /// `public_api_url` is where recipients reach the API (for unsubscribe links);
+    /// `signing_key` signs those links and must match the API's
+    /// `AppConfig::unsubscribe_key`.
+    pub fn new(
+        client: ResendClient,
+        public_api_url: impl Into<String>,
+        signing_key: [u8; 32],
+    ) -> Self {
+        Self {
+            client,
+            public_api_url: public_api_url.into().trim_end_matches('/').to_string(),
+            signing_key,
+        }
+    }
+
+    /// Signed one-click unsubscribe URL for the delivery's subscription.
+    pub fn unsubscribe_url(&self, delivery: &Delivery) -> String {
+        let token = SubscriptionService::unsubscribe_token(
+            delivery.alert.subscription_id,
+            &self.signing_key,
+        );
+        format!("{}/api/unsubscribe?token={}", self.public_api_url, token)
+    }
+
+    /// Render a delivery into subject, HTML and plain-text bodies.
+    pub fn render(&self, delivery: &Delivery, to: &str) -> EmailMessage {
+        let unsubscribe_url = self.unsubscribe_url(delivery);
+        EmailMessage {
+            to: to.to_string(),
+            subject: format!("[FlareEmissary] {}", delivery.payload.title),
+            html: render_html(delivery, &unsubscribe_url),
+            text: render_text(delivery, &unsubscribe_url),
+            headers: vec![
+                (
+                    "List-Unsubscribe".to_string(),
+                    format!("<{}>", unsubscribe_url),
+                ),
+                (
+                    "List-Unsubscribe-Post".to_string(),
+                    "List-Unsubscribe=One-Click".to_string(),
+                ),
+            ],
+        }
+    }
+}
+
+impl NotificationSender for EmailSender {
+    fn channel_type(&self) -> ChannelType {
+        ChannelType::Email
+    }
+
+    fn send<'a>(&'a self, delivery: &'a Delivery) -> SendFuture<'a> {
+        Box::pin(async move {
+            let to = delivery
+                .channel
+                .config
+                .get("email")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| DeliveryError::InvalidConfig("missing email".to_string()))?;
+
+            self.client.send(&self.render(delivery, to)).await
+        })
+    }
+}
+
+/// Build the confirm-your-address email sent when an email channel is verified.
+pub fn verification_email(to: &str, confirm_url: &str) -> EmailMessage {
+    let url = escape_html(confirm_url);
+    EmailMessage {
+        to: to.to_string(),
+        subject: "Confirm your FlareEmissary alert address".to_string(),
+        html: format!(
+            r#"<!DOCTYPE html>
+<html><body style="font-family:sans-serif;color:#1f2933;max-width:560px;margin:0 auto;padding:24px">
+<h2>Confirm your email address</h2>
+<p>Someone asked FlareEmissary to send on-chain alerts to this address. If that was you, confirm below.</p>
+<p><a href="{url}" style="display:inline-block;padding:10px 18px;background:#e62058;color:#fff;text-decoration:none;border-radius:4px">Confirm address</a></p>
+<p style="color:#7b8794;font-size:12px">If you did not request this, ignore this email and no alerts will be sent.</p>
+</body></html>"#
+        ),
+        text: format!(
+            "Confirm your email address\n\n\
+             Someone asked FlareEmissary to send on-chain alerts to this address. \
+             If that was you, confirm here:\n\n{}\n\n\
+             If you did not request this, ignore this email and no alerts will be sent.\n",
+            confirm_url
+        ),
+        headers: Vec::new(),
+    }
+}
+
+fn severity_color(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "#3498db",
+        Severity::Warning => "#f1c40f",
+        Severity::Critical => "#e74c3c",
+    }
+}
+
+fn render_html(delivery: &Delivery, unsubscribe_url: &str) -> String {
+    let event = &delivery.event;
+    let payload = &delivery.payload;
+    let explorer = event.chain.explorer_url();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html><body style="font-family:sans-serif;color:#1f2933;max-width:560px;margin:0 auto;padding:24px">
+<div style="border-left:4px solid {color};padding-left:12px">
+<h2 style="margin:0 0 8px">{title}</h2>
+<p style="margin:0">{body}</p>
+</div>
+<table style="margin-top:16px;font-size:14px;border-collapse:collapse">
+<tr><td style="padding:2px 12px 2px 0;color:#7b8794">Chain</td><td>{chain}</td></tr>
+<tr><td style="padding:2px 12px 2px 0;color:#7b8794">Block</td><td><a href="{explorer}/block/{block}">{block}</a></td></tr>
+<tr><td style="padding:2px 12px 2px 0;color:#7b8794">Tx</td><td><a href="{explorer}/tx/{tx}"><code>{tx}</code></a></td></tr>
+<tr><td style="padding:2px 12px 2px 0;color:#7b8794">Address</td><td><a href="{explorer}/address/{address}"><code>{address}</code></a></td></tr>
+</table>
+<p style="margin-top:24px;color:#7b8794;font-size:12px">You are receiving this because of a FlareEmissary subscription. <a href="{unsubscribe}">Unsubscribe</a></p>
+</body></html>"#,
+        color = severity_color(payload.severity),
+        title = escape_html(&payload.title),
+        body = escape_html(&payload.body),
+        chain = event.chain,
+        block = event.block_number,
+        tx = escape_html(&event.tx_hash),
+        address = escape_html(&event.address),
+        unsubscribe = escape_html(unsubscribe_url),
+    )
+}
+
+fn render_text(delivery: &Delivery, unsubscribe_url: &str) -> String {
+    let event = &delivery.event;
+    let payload = &delivery.payload;
+    let explorer = event.chain.explorer_url();
+
+    format!(
+        "{}\n\n{}\n\nChain:   {}\nBlock:   {}\nTx:      {}/tx/{}\nAddress: {}\n\n--\nUnsubscribe: {}\n",
+        payload.title,
+        payload.body,
+        event.chain,
+        event.block_number,
+        explorer,
+        event.tx_hash,
+        event.address,
+        unsubscribe_url,
+    )
+}
+
+/// Escape text for HTML element content and quoted attributes.
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{Captured, delivery, spawn_stub};
+    use axum::extract::State;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    async fn stub_resend(status: StatusCode) -> (String, Captured) {
+        let captured = Captured::default();
+        let router = Router::new()
+            .route(
+                "/emails",
+                post(
+                    move |State(captured): State<Captured>,
+                          headers: HeaderMap,
+                          Json(body): Json<serde_json::Value>| async move {
+                        let auth = headers["authorization"].to_str().unwrap().to_string();
+                        captured.lock().unwrap().push((auth, body));
+                        (status, Json(json!({ "id": "email-1" })))
+                    },
+                ),
+            )
+            .with_state(captured.clone());
+        (spawn_stub(router).await, captured)
+    }
+
+    fn sender(base_url: &str) -> EmailSender {
+        EmailSender::new(
+            ResendClient::new(base_url, "re_test", "alerts@example.com"),
+            "https://api.example.com/",
+            [7; 32],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_html_and_text_with_unsubscribe_headers() {
+        let (base_url, captured) = stub_resend(StatusCode::OK).await;
+        let delivery = delivery(ChannelType::Email, json!({ "email": "alice@example.com" }));
+        sender(&base_url).send(&delivery).await.unwrap();
+
+        let captured = captured.lock().unwrap();
+        assert_eq!(captured.len(), 1);
+        let (auth, body) = &captured[0];
+        assert_eq!(auth, "Bearer re_test");
+        assert_eq!(body["from"], "alerts@example.com");
+        assert_eq!(body["to"], json!(["alice@example.com"]));
+        assert!(body["subject"].as_str().unwrap().contains("Liquidation"));
+
+        let expected_url = format!(
+            "https://api.example.com/api/unsubscribe?token={}",
+            SubscriptionService::unsubscribe_token(delivery.alert.subscription_id, &[7; 32])
+        );
+        assert!(body["html"].as_str().unwrap().contains(&expected_url));
+        assert!(body["text"].as_str().unwrap().contains(&expected_url));
+        assert_eq!(
+            body["headers"]["List-Unsubscribe"],
+            format!("<{}>", expected_url)
+        );
+        assert_eq!(
+            body["headers"]["List-Unsubscribe-Post"],
+            "List-Unsubscribe=One-Click"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_and_rejection() {
+        let delivery = delivery(ChannelType::Email, json!({ "email": "alice@example.com" }));
+
+        let (base_url, _) = stub_resend(StatusCode::TOO_MANY_REQUESTS).await;
+        let err = sender(&base_url).send(&delivery).await.unwrap_err();
+        assert!(matches!(err, DeliveryError::RateLimited { .. }));
+
+        let (base_url, _) = stub_resend(StatusCode::UNPROCESSABLE_ENTITY).await;
+        let err = sender(&base_url).send(&delivery).await.unwrap_err();
+        assert!(matches!(err, DeliveryError::Rejected { status: 422, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_send_without_email_is_invalid_config() {
+        let delivery = delivery(ChannelType::Email, json!({}));
+        let err = sender("http://127.0.0.1:9")
+            .send(&delivery)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DeliveryError::InvalidConfig(_)));
+    }
+
+    #[test]
+    fn test_html_escapes_payload() {
+        let mut delivery = delivery(ChannelType::Email, json!({}));
+        delivery.payload.body = "<script>alert(1)</script>".to_string();
+        let html = render_html(&delivery, "https://x/unsub?token=a.b");
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+}
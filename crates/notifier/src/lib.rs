@@ -1,11 +1,18 @@
-// TODO: Milestone 5 — Notification workers
-//
-// Workers that consume the Redis notification queue and deliver via:
-// - Telegram (teloxide crate)
-// - Discord (webhook + bot)
-// - Email (Resend HTTP API)
-//
-// Features:
-// - Retry with exponential backoff
-// - Delivery status tracking (pending → sent → failed)
-// - Dead letter queue for permanently failed notifications
+//! Notification delivery (Milestone 5).
+//!
+//! Workers consume the Redis notification queue filled by the engine and deliver
+//! each pending notification through the `NotificationSender` registered for its
+//! channel type, tracking status on the `notifications` row
+//! (pending → sent, or failed → retried with backoff → sent / dead_letter).
+
+pub mod discord;
+pub mod email;
+pub mod replay;
+pub mod retry;
+pub mod sender;
+pub mod telegram;
+pub mod webhook;
+pub mod worker;
+
+#[cfg(test)]
+mod test_support;
@@ -0,0 +1,145 @@
+//! FlareEmissary notifier binary entrypoint.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use flare_common::config::AppConfig;
+use flare_common::db;
+use flare_common::redis_pool::create_redis_pool;
+use flare_notifier::discord::DiscordSender;
+use flare_notifier::email::{EmailSender, ResendClient};
+use flare_notifier::retry::RetryPolicy;
+use flare_notifier::sender::SenderRegistry;
+use flare_notifier::telegram::{TelegramBotListener, TelegramSender};
+use flare_notifier::webhook::WebhookSender;
+use flare_notifier::worker::{NotificationWorker, requeue_due, requeue_stale};
+
+/// How often pending notifications are swept back onto the queue.
+const STALE_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often failed notifications are checked for due retries.
+const RETRY_SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long an enqueued retry waits before it is considered lost and re-enqueued.
+const RETRY_LEASE: Duration = Duration::from_secs(300);
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // Initialize tracing
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "flare_notifier=info".into()),
+        )
+        .json()
+        .init();
+
+    tracing::info!("FlareEmissary Notifier starting...");
+
+    // Load configuration
+    let config = AppConfig::from_env()?;
+
+    // Connect to database
+    let pool = db::create_pool(&config.database_url, config.db_max_connections).await?;
+
+    // Run migrations
+    sqlx::migrate!("../../migrations").run(&pool).await?;
+    tracing::info!("Database migrations applied");
+
+    let mut senders = SenderRegistry::new();
+    if let Some(token) = &config.telegram_bot_token {
+        senders.register(Box::new(TelegramSender::new(
+            &config.telegram_api_base_url,
+            token,
+        )));
+    }
+    if let (Some(api_key), Some(from)) = (&config.resend_api_key, &config.email_from) {
+        senders.register(Box::new(EmailSender::new(
+            ResendClient::new(&config.resend_api_base_url, api_key, from),
+            &config.public_api_url,
+            config.unsubscribe_key(),
+        )));
+    }
+    // Webhook delivery needs no credentials, so Discord is always available
+    senders.register(Box::new(DiscordSender::new(
+        &config.discord_api_base_url,
+        config.discord_bot_token.clone(),
+    )));
+    senders.register(Box::new(WebhookSender::new(
+        config.webhook_allow_private_targets,
+    )));
+    let senders = Arc::new(senders);
+
+    let retry_policy = RetryPolicy {
+        max_attempts: config.notifier_max_attempts.max(1),
+        base_delay: Duration::from_secs(config.notifier_retry_base_secs),
+        ..RetryPolicy::default()
+    };
+
+    // Spawn queue-consuming workers, each with its own Redis connection
+    let mut workers = tokio::task::JoinSet::new();
+    for id in 0..config.notifier_workers.max(1) {
+        let redis = create_redis_pool(&config.redis_url).await?;
+        let worker = NotificationWorker::new(id, pool.clone(), senders.clone())
+            .with_retry_policy(retry_policy);
+        workers.spawn(async move { worker.run(redis).await });
+    }
+
+    // Periodically re-enqueue notifications whose queue entry was lost
+    let mut sweep_redis = create_redis_pool(&config.redis_url).await?;
+    let sweep_pool = pool.clone();
+    workers.spawn(async move {
+        let mut interval = tokio::time::interval(STALE_SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = requeue_stale(&sweep_pool, &mut sweep_redis, STALE_SWEEP_INTERVAL).await
+            {
+                tracing::warn!(error = %e, "Stale notification sweep failed");
+            }
+        }
+    });
+
+    // Re-enqueue failed notifications once their backoff has elapsed
+    let mut retry_redis = create_redis_pool(&config.redis_url).await?;
+    let retry_pool = pool.clone();
+    workers.spawn(async move {
+        let mut interval = tokio::time::interval(RETRY_SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = requeue_due(&retry_pool, &mut retry_redis, RETRY_LEASE).await {
+                tracing::warn!(error = %e, "Retry sweep failed");
+            }
+        }
+    });
+
+    // Complete Telegram chat verification handshakes
+    if let Some(token) = &config.telegram_bot_token {
+        let mut listener = TelegramBotListener::new(&config.telegram_api_base_url, token);
+        let listener_pool = pool.clone();
+        workers.spawn(async move { listener.run(&listener_pool).await });
+    }
+
+    tracing::info!(
+        workers = config.notifier_workers.max(1),
+        "Notification workers started"
+    );
+
+    // Run with graceful shutdown on Ctrl+C
+    tokio::select! {
+        Some(result) = workers.join_next() => {
+            let e = match result {
+                Ok(Ok(())) => anyhow::anyhow!("Notification worker exited unexpectedly"),
+                Ok(Err(e)) => e,
+                Err(e) => e.into(),
+            };
+            tracing::error!(error = %e, "Notification worker exited with error");
+            return Err(e);
+        }
+        _ = tokio::signal::ctrl_c() => {
+            tracing::info!("Received shutdown signal, stopping gracefully...");
+        }
+    }
+
+    tracing::info!("FlareEmissary Notifier stopped.");
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! Re-send stored alerts to webhook endpoints.
+//!
+//! Used by the `flare-webhook-replay` tool to backfill a receiver after an outage or
+//! to exercise a new integration with real data. Replays carry `"replay": true`, a
+//! fresh `delivery_id` and a fresh signature timestamp, and do not touch the
+//! `notifications` table.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use flare_common::types::{ChannelType, NotificationChannel};
+
+use crate::webhook::{WebhookBody, WebhookSender, webhook_config};
+use crate::worker::load_alert;
+
+/// Where a replayed alert is sent.
+#[derive(Debug, Clone)]
+pub enum ReplayTarget {
+    /// The webhook channel of the alert's own subscription
+    Subscription,
+    /// A specific webhook channel
+    Channel(Uuid),
+    /// An ad-hoc URL and signing secret
+    Url { url: String, secret: String },
+}
+
+/// Re-send one stored alert. Returns the delivery ID used for the replay.
+pub async fn replay_alert(
+    pool: &PgPool,
+    sender: &WebhookSender,
+    alert_id: Uuid,
+    target: &ReplayTarget,
+) -> anyhow::Result<Uuid> {
+    let mut conn = pool.acquire().await?;
+    let (alert, event) = load_alert(&mut conn, alert_id).await?;
+
+    let (url, secret) = match target {
+        ReplayTarget::Url { url, secret } => (url.clone(), secret.clone()),
+        ReplayTarget::Subscription | ReplayTarget::Channel(_) => {
+            let channel: NotificationChannel = match target {
+                ReplayTarget::Channel(id) => {
+                    sqlx::query_as("SELECT * FROM notification_channels WHERE id = $1")
+                        .bind(id)
+                        .fetch_one(&mut *conn)
+                        .await?
+                }
+                _ => {
+                    sqlx::query_as(
+                        r#"
+                        SELECT c.* FROM notification_channels c
+                        JOIN subscriptions s ON s.channel_id = c.id
+                        WHERE s.id = $1
+                        "#,
+                    )
+                    .bind(alert.subscription_id)
+                    .fetch_one(&mut *conn)
+                    .await?
+                }
+            };
+            if channel.channel_type != ChannelType::Webhook {
+                anyhow::bail!(
+                    "Channel {} is a {} channel, not a webhook",
+                    channel.id,
+                    channel.channel_type
+                );
+            }
+            let (url, secret) = webhook_config(&channel.config)?;
+            (url.to_string(), secret.to_string())
+        }
+    };
+
+    let body = WebhookBody::alert(Uuid::new_v4(), &alert, &event, true);
+    sender
+        .post(&url, &secret, "alert", body.delivery_id, &body)
+        .await?;
+
+    tracing::info!(
+        alert_id = %alert_id,
+        delivery_id = %body.delivery_id,
+        "Alert replayed to webhook"
+    );
+
+    Ok(body.delivery_id)
+}
@@ -0,0 +1,105 @@
+//! Retry scheduling for failed deliveries.
+//!
+//! Failed attempts are retried with exponential backoff and "equal jitter": the
+//! delay for attempt `n` is drawn uniformly from `[d/2, d]` where
+//! `d = min(base * 2^(n-1), max)`. Jitter spreads out retries from a burst of
+//! failures (e.g., a provider outage) so they don't all hit the provider at once.
+
+use std::time::Duration;
+
+use rand::Rng;
+
+/// Backoff and attempt limits for notification delivery.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total attempts (including the first) before a notification is dead-lettered
+    pub max_attempts: u32,
+    /// Delay before the first retry (before jitter)
+    pub base_delay: Duration,
+    /// Upper bound on any single delay
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(30),
+            max_delay: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether another attempt is allowed after `attempts` have been made.
+    pub fn can_retry(&self, attempts: u32) -> bool {
+        attempts < self.max_attempts
+    }
+
+    /// Un-jittered delay after the `attempt`-th failed attempt (1-based).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        self.base_delay
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_delay)
+    }
+
+    /// Jittered delay after the `attempt`-th failed attempt, never shorter than
+    /// `floor` (e.g., a provider's `retry_after`).
+    pub fn next_delay(&self, attempt: u32, floor: Option<Duration>) -> Duration {
+        let jitter = rand::rng().random_range(0.0..=1.0);
+        self.jittered(attempt, jitter)
+            .max(floor.unwrap_or_default())
+    }
+
+    /// Delay for a given jitter factor in `[0, 1]`.
+    fn jittered(&self, attempt: u32, jitter: f64) -> Duration {
+        let delay = self.backoff(attempt);
+        delay / 2 + delay.div_f64(2.0).mul_f64(jitter.clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(60),
+        };
+        assert_eq!(policy.backoff(1), Duration::from_secs(10));
+        assert_eq!(policy.backoff(2), Duration::from_secs(20));
+        assert_eq!(policy.backoff(3), Duration::from_secs(40));
+        assert_eq!(policy.backoff(4), Duration::from_secs(60));
+        assert_eq!(policy.backoff(40), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_half_to_full_delay() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.jittered(1, 0.0), Duration::from_secs(15));
+        assert_eq!(policy.jittered(1, 1.0), Duration::from_secs(30));
+
+        for _ in 0..100 {
+            let delay = policy.next_delay(2, None);
+            assert!(delay >= Duration::from_secs(30) && delay <= Duration::from_secs(60));
+        }
+    }
+
+    #[test]
+    fn test_retry_after_is_a_floor() {
+        let policy = RetryPolicy::default();
+        let delay = policy.next_delay(1, Some(Duration::from_secs(300)));
+        assert_eq!(delay, Duration::from_secs(300));
+    }
+
+    #[test]
+    fn test_can_retry() {
+        let policy = RetryPolicy::default();
+        assert!(policy.can_retry(4));
+        assert!(!policy.can_retry(5));
+    }
+}
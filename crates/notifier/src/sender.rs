@@ -0,0 +1,207 @@
+//! Channel-agnostic delivery abstraction.
+//!
+//! Each channel (Telegram, Discord, Email, ...) implements `NotificationSender`.
+//! Senders receive a fully loaded `Delivery` and perform exactly one delivery
+//! attempt; retry and status bookkeeping live in the worker.
+//!
+//! Senders that talk to an HTTP API take their base URL as a constructor argument
+//! so tests can point them at a local stand-in server instead of the real provider.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use flare_common::types::{
+    Alert, ChannelType, DecodedEvent, Notification, NotificationChannel, NotificationPayload,
+};
+
+/// Everything a sender needs to deliver one notification.
+#[derive(Debug, Clone)]
+pub struct Delivery {
+    /// The notification row being delivered
+    pub notification: Notification,
+    /// Destination channel (type + channel-specific config JSON)
+    pub channel: NotificationChannel,
+    /// The alert that produced this notification
+    pub alert: Alert,
+    /// The on-chain event that triggered the alert
+    pub event: DecodedEvent,
+    /// Human-readable rendering of the event
+    pub payload: NotificationPayload,
+}
+
+/// Errors a sender can report for a single delivery attempt.
+#[derive(Debug, Error)]
+pub enum DeliveryError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Provider rejected request ({status}): {body}")]
+    Rejected { status: u16, body: String },
+
+    #[error("Rate limited by provider, retry after {retry_after:?}")]
+    RateLimited { retry_after: Duration },
+
+    #[error("Target unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Invalid channel config: {0}")]
+    InvalidConfig(String),
+
+    #[error("No sender configured for channel type {0:?}")]
+    NoSender(ChannelType),
+
+    #[error("Channel {0} is not verified")]
+    Unverified(Uuid),
+}
+
+impl DeliveryError {
+    /// Whether retrying cannot help: the provider rejected the request (4xx other
+    /// than timeout/rate limit) or the channel itself is unusable.
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            DeliveryError::Rejected { status, .. } => {
+                (400..500).contains(status) && *status != 408 && *status != 429
+            }
+            DeliveryError::InvalidConfig(_)
+            | DeliveryError::NoSender(_)
+            | DeliveryError::Unverified(_) => true,
+            DeliveryError::Http(_)
+            | DeliveryError::RateLimited { .. }
+            | DeliveryError::Unavailable(_) => false,
+        }
+    }
+
+    /// Minimum wait the provider asked for before retrying, if any.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            DeliveryError::RateLimited { retry_after } => Some(*retry_after),
+            _ => None,
+        }
+    }
+}
+
+/// Boxed future returned by `NotificationSender::send`.
+pub type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<(), DeliveryError>> + Send + 'a>>;
+
+/// A delivery backend for one `ChannelType`.
+pub trait NotificationSender: Send + Sync {
+    /// The channel type this sender delivers to.
+    fn channel_type(&self) -> ChannelType;
+
+    /// Perform a single delivery attempt.
+    fn send<'a>(&'a self, delivery: &'a Delivery) -> SendFuture<'a>;
+}
+
+/// Turn a non-2xx provider response into `DeliveryError::Rejected`.
+pub(crate) async fn error_for_status(
+    response: reqwest::Response,
+) -> Result<reqwest::Response, DeliveryError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    Err(DeliveryError::Rejected {
+        status: status.as_u16(),
+        body,
+    })
+}
+
+/// Lookup table of senders keyed by channel type.
+#[derive(Default)]
+pub struct SenderRegistry {
+    senders: HashMap<ChannelType, Box<dyn NotificationSender>>,
+}
+
+impl SenderRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a sender, replacing any existing sender for the same channel type.
+    pub fn register(&mut self, sender: Box<dyn NotificationSender>) {
+        self.senders.insert(sender.channel_type(), sender);
+    }
+
+    /// Get the sender for a channel type, if one is registered.
+    pub fn get(&self, channel_type: ChannelType) -> Option<&dyn NotificationSender> {
+        self.senders.get(&channel_type).map(|s| s.as_ref())
+    }
+
+    /// Number of registered senders.
+    pub fn len(&self) -> usize {
+        self.senders.len()
+    }
+
+    /// Check if no senders are registered.
+    pub fn is_empty(&self) -> bool {
+        self.senders.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct NoopSender(ChannelType);
+
+    impl NotificationSender for NoopSender {
+        fn channel_type(&self) -> ChannelType {
+            self.0
+        }
+
+        fn send<'a>(&'a self, _delivery: &'a Delivery) -> SendFuture<'a> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[test]
+    fn test_registry_lookup_by_channel_type() {
+        let mut registry = SenderRegistry::new();
+        assert!(registry.is_empty());
+
+        registry.register(Box::new(NoopSender(ChannelType::Telegram)));
+        registry.register(Box::new(NoopSender(ChannelType::Email)));
+
+        assert_eq!(registry.len(), 2);
+        assert!(registry.get(ChannelType::Telegram).is_some());
+        assert!(registry.get(ChannelType::Email).is_some());
+        assert!(registry.get(ChannelType::Discord).is_none());
+    }
+
+    #[test]
+    fn test_permanent_errors() {
+        let rejected = |status| DeliveryError::Rejected {
+            status,
+            body: String::new(),
+        };
+        assert!(rejected(400).is_permanent());
+        assert!(rejected(404).is_permanent());
+        assert!(!rejected(408).is_permanent());
+        assert!(!rejected(429).is_permanent());
+        assert!(!rejected(500).is_permanent());
+        assert!(!rejected(503).is_permanent());
+        assert!(DeliveryError::InvalidConfig("x".to_string()).is_permanent());
+        assert!(!DeliveryError::Unavailable("x".to_string()).is_permanent());
+        assert!(
+            !DeliveryError::RateLimited {
+                retry_after: Duration::from_secs(1)
+            }
+            .is_permanent()
+        );
+    }
+
+    #[test]
+    fn test_registry_replaces_same_channel_type() {
+        let mut registry = SenderRegistry::new();
+        registry.register(Box::new(NoopSender(ChannelType::Discord)));
+        registry.register(Box::new(NoopSender(ChannelType::Discord)));
+        assert_eq!(registry.len(), 1);
+    }
+}
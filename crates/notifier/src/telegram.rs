@@ -0,0 +1,352 @@
+//! Telegram delivery via the Bot API.
+//!
+//! Channel config: `{ "chat_id": <int | string> }`. The `chat_id` is normally filled
+//! in by the verification handshake rather than typed by the user:
+//!
+//! 1. The API issues a one-time code for an unverified Telegram channel.
+//! 2. The user sends `/start <code>` (or just the code) to the bot.
+//! 3. `TelegramBotListener` sees the message via `getUpdates`, redeems the code,
+//!    stores the originating `chat_id` on the channel and marks it verified.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::json;
+use sqlx::PgPool;
+
+use flare_common::types::ChannelType;
+use flare_engine::channel::ChannelService;
+
+use crate::sender::{Delivery, DeliveryError, NotificationSender, SendFuture, error_for_status};
+
+/// Timeout for a single `sendMessage` call.
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Long-poll duration for `getUpdates`.
+const LONG_POLL_SECS: u64 = 30;
+
+/// Sends alerts to Telegram chats through the Bot API.
+pub struct TelegramSender {
+    client: reqwest::Client,
+    api_base_url: String,
+    bot_token: String,
+}
+
+impl TelegramSender {
+    /// Create a sender. `api_base_url` is normally `https://api.telegram.org`.
+    pub fn new(api_base_url: impl Into<String>, bot_token: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(SEND_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            api_base_url: api_base_url.into().trim_end_matches('/').to_string(),
+            bot_token: bot_token.into(),
+        }
+    }
+
+    /// Render a delivery as Telegram HTML.
+    pub fn format_message(delivery: &Delivery) -> String {
+        let event = &delivery.event;
+        format!(
+            "<b>{}</b>\n\n{}\n\nChain: {} · Block: {}\nTx: <code>{}</code>",
+            escape_html(&delivery.payload.title),
+            escape_html(&delivery.payload.body),
+            event.chain,
+            event.block_number,
+            escape_html(&event.tx_hash),
+        )
+    }
+}
+
+impl NotificationSender for TelegramSender {
+    fn channel_type(&self) -> ChannelType {
+        ChannelType::Telegram
+    }
+
+    fn send<'a>(&'a self, delivery: &'a Delivery) -> SendFuture<'a> {
+        Box::pin(async move {
+            let chat_id = delivery
+                .channel
+                .config
+                .get("chat_id")
+                .filter(|v| v.is_string() || v.is_number())
+                .cloned()
+                .ok_or_else(|| DeliveryError::InvalidConfig("missing chat_id".to_string()))?;
+
+            send_message(
+                &self.client,
+                &self.api_base_url,
+                &self.bot_token,
+                chat_id,
+                &Self::format_message(delivery),
+            )
+            .await
+        })
+    }
+}
+
+/// Call `sendMessage` with HTML parse mode.
+async fn send_message(
+    client: &reqwest::Client,
+    api_base_url: &str,
+    bot_token: &str,
+    chat_id: serde_json::Value,
+    text: &str,
+) -> Result<(), DeliveryError> {
+    let response = client
+        .post(format!("{}/bot{}/sendMessage", api_base_url, bot_token))
+        .json(&json!({
+            "chat_id": chat_id,
+            "text": text,
+            "parse_mode": "HTML",
+            "disable_web_page_preview": true,
+        }))
+        .send()
+        .await?;
+
+    error_for_status(response).await?;
+    Ok(())
+}
+
+/// Minimal subset of the Bot API response envelope.
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    ok: bool,
+    result: Option<T>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    chat: Chat,
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+/// Long-polls the bot's updates and completes channel verification handshakes.
+///
+/// Only one listener may poll a given bot token at a time (a Bot API restriction),
+/// so run it in a single notifier process.
+pub struct TelegramBotListener {
+    client: reqwest::Client,
+    api_base_url: String,
+    bot_token: String,
+    offset: i64,
+}
+
+impl TelegramBotListener {
+    pub fn new(api_base_url: impl Into<String>, bot_token: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_base_url: api_base_url.into().trim_end_matches('/').to_string(),
+            bot_token: bot_token.into(),
+            offset: 0,
+        }
+    }
+
+    /// Poll for updates indefinitely until the task is cancelled.
+    pub async fn run(&mut self, pool: &PgPool) -> anyhow::Result<()> {
+        tracing::info!("Telegram verification listener started");
+
+        loop {
+            if let Err(e) = self.poll_once(pool, LONG_POLL_SECS).await {
+                tracing::warn!(error = %e, "Telegram getUpdates failed, retrying");
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        }
+    }
+
+    /// Fetch one batch of updates and handle them. Returns the number of updates seen.
+    pub async fn poll_once(&mut self, pool: &PgPool, timeout_secs: u64) -> anyhow::Result<usize> {
+        let response: ApiResponse<Vec<Update>> = self
+            .client
+            .get(format!(
+                "{}/bot{}/getUpdates",
+                self.api_base_url, self.bot_token
+            ))
+            .query(&[
+                ("offset", self.offset.to_string()),
+                ("timeout", timeout_secs.to_string()),
+                ("allowed_updates", r#"["message"]"#.to_string()),
+            ])
+            .timeout(Duration::from_secs(timeout_secs + 10))
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        if !response.ok {
+            anyhow::bail!(
+                "getUpdates rejected: {}",
+                response.description.unwrap_or_default()
+            );
+        }
+
+        let updates = response.result.unwrap_or_default();
+        for update in &updates {
+            self.offset = self.offset.max(update.update_id + 1);
+            if let Some(message) = &update.message
+                && let Err(e) = self.handle_message(pool, message).await
+            {
+                tracing::warn!(
+                    update_id = update.update_id,
+                    error = %e,
+                    "Failed to handle Telegram message"
+                );
+            }
+        }
+
+        Ok(updates.len())
+    }
+
+    /// Try to redeem a verification code from an incoming message and reply.
+    async fn handle_message(&self, pool: &PgPool, message: &Message) -> anyhow::Result<()> {
+        let Some(text) = message.text.as_deref() else {
+            return Ok(());
+        };
+
+        let reply = match extract_code(text) {
+            Some(code) => {
+                let verified = ChannelService::confirm_verification(
+                    pool,
+                    ChannelType::Telegram,
+                    code,
+                    json!({ "chat_id": message.chat.id }),
+                )
+                .await?;
+
+                match verified {
+                    Some(_) => "✅ Verified! FlareEmissary alerts will be delivered to this chat.",
+                    None => "❌ Invalid or expired verification code.",
+                }
+            }
+            None if text.trim_start().starts_with("/start") => {
+                "Send the verification code from FlareEmissary to link this chat."
+            }
+            None => return Ok(()),
+        };
+
+        send_message(
+            &self.client,
+            &self.api_base_url,
+            &self.bot_token,
+            json!(message.chat.id),
+            reply,
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// Extract a verification code from `/start <code>` or a bare code message.
+fn extract_code(text: &str) -> Option<&str> {
+    let text = text.trim();
+    let candidate = match text.strip_prefix("/start") {
+        Some(rest) => rest.trim(),
+        None => text,
+    };
+
+    let looks_like_code =
+        candidate.len() == 8 && candidate.chars().all(|c| c.is_ascii_alphanumeric());
+    looks_like_code.then_some(candidate)
+}
+
+/// Escape the characters Telegram's HTML parse mode treats specially.
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{Captured, delivery, spawn_stub};
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    async fn stub_telegram(status: StatusCode) -> (String, Captured) {
+        let captured = Captured::default();
+        let router = Router::new()
+            .route(
+                "/{bot}/sendMessage",
+                post(
+                    move |State(captured): State<Captured>,
+                          Path(bot): Path<String>,
+                          Json(body): Json<serde_json::Value>| async move {
+                        captured.lock().unwrap().push((bot, body));
+                        (status, Json(json!({ "ok": status.is_success() })))
+                    },
+                ),
+            )
+            .with_state(captured.clone());
+        (spawn_stub(router).await, captured)
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_html_message_to_chat() {
+        let (base_url, captured) = stub_telegram(StatusCode::OK).await;
+        let sender = TelegramSender::new(base_url, "123:ABC");
+
+        let delivery = delivery(ChannelType::Telegram, json!({ "chat_id": 987654 }));
+        sender.send(&delivery).await.unwrap();
+
+        let captured = captured.lock().unwrap();
+        assert_eq!(captured.len(), 1);
+        let (bot, body) = &captured[0];
+        assert_eq!(bot, "bot123:ABC");
+        assert_eq!(body["chat_id"], 987654);
+        assert_eq!(body["parse_mode"], "HTML");
+        assert!(
+            body["text"]
+                .as_str()
+                .unwrap()
+                .contains("Liquidation Started")
+        );
+        assert!(body["text"].as_str().unwrap().contains("0xfeedbeef"));
+    }
+
+    #[tokio::test]
+    async fn test_send_rejected_surfaces_status() {
+        let (base_url, _) = stub_telegram(StatusCode::BAD_REQUEST).await;
+        let sender = TelegramSender::new(base_url, "123:ABC");
+
+        let delivery = delivery(ChannelType::Telegram, json!({ "chat_id": "42" }));
+        let err = sender.send(&delivery).await.unwrap_err();
+        assert!(matches!(err, DeliveryError::Rejected { status: 400, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_send_without_chat_id_is_invalid_config() {
+        let sender = TelegramSender::new("http://127.0.0.1:9", "123:ABC");
+        let delivery = delivery(ChannelType::Telegram, json!({}));
+        let err = sender.send(&delivery).await.unwrap_err();
+        assert!(matches!(err, DeliveryError::InvalidConfig(_)));
+    }
+
+    #[test]
+    fn test_extract_code() {
+        assert_eq!(extract_code("/start AB12CD34"), Some("AB12CD34"));
+        assert_eq!(extract_code("  AB12CD34 "), Some("AB12CD34"));
+        assert_eq!(extract_code("/start"), None);
+        assert_eq!(extract_code("hello there"), None);
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(escape_html("a < b & c > d"), "a &lt; b &amp; c &gt; d");
+    }
+}
@@ -0,0 +1,84 @@
+//! Shared fixtures for sender unit tests.
+//!
+//! Senders are exercised against a local axum server standing in for the
+//! provider's HTTP API, so no real Telegram/Discord/Resend calls are made.
+
+use std::sync::{Arc, Mutex};
+
+use axum::Router;
+use chrono::Utc;
+use uuid::Uuid;
+
+use flare_common::types::{
+    Alert, Chain, ChannelType, DecodedEvent, DeliveryStatus, EventType, Notification,
+    NotificationChannel, NotificationKind, Severity,
+};
+use flare_engine::processor::EventProcessor;
+
+use crate::sender::Delivery;
+
+/// Requests captured by a stub server: (path, JSON body).
+pub(crate) type Captured = Arc<Mutex<Vec<(String, serde_json::Value)>>>;
+
+/// Serve `router` on an ephemeral local port and return its base URL.
+pub(crate) async fn spawn_stub(router: Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    format!("http://{}", addr)
+}
+
+/// Build a delivery for a liquidation event on a verified channel.
+pub(crate) fn delivery(channel_type: ChannelType, config: serde_json::Value) -> Delivery {
+    let event = DecodedEvent {
+        tx_hash: "0xfeedbeef".to_string(),
+        log_index: Some(3),
+        block_number: 1_234_567,
+        block_timestamp: Utc::now(),
+        chain: Chain::Flare,
+        address: "0x00000000000000000000000000000000000a6e17".to_string(),
+        event_type: EventType::LiquidationStarted,
+        decoded_data: serde_json::json!({ "agent": "0xagent" }),
+    };
+    let payload = EventProcessor::translate_event(&event);
+
+    let user_id = Uuid::new_v4();
+    let channel_id = Uuid::new_v4();
+    let alert_id = Uuid::new_v4();
+
+    Delivery {
+        notification: Notification {
+            id: Uuid::new_v4(),
+            alert_id,
+            channel_id,
+            status: DeliveryStatus::Pending,
+            sent_at: None,
+            error_detail: None,
+            created_at: Utc::now(),
+            attempts: 0,
+            next_attempt_at: None,
+            kind: NotificationKind::Alert,
+        },
+        channel: NotificationChannel {
+            id: channel_id,
+            user_id,
+            channel_type,
+            config,
+            verified: true,
+            created_at: Utc::now(),
+        },
+        alert: Alert {
+            id: alert_id,
+            subscription_id: Uuid::new_v4(),
+            event_id: 42,
+            severity: Severity::Critical,
+            message: payload.body.clone(),
+            triggered_at: Utc::now(),
+            retracted_at: None,
+        },
+        event,
+        payload,
+    }
+}
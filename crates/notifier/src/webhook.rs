@@ -0,0 +1,525 @@
+//! Generic outbound webhooks with HMAC-signed payloads.
+//!
+//! Channel config: `{ "url": "https://...", "secret": "whsec_..." }` (the secret is
+//! generated at channel creation if not supplied).
+//!
+//! Every request is a JSON POST with these headers:
+//!
+//! - `X-Emissary-Event`: `alert`, `alert_retracted` or `verification`
+//! - `X-Emissary-Delivery`: unique delivery ID (stable across retries of one notification)
+//! - `X-Emissary-Timestamp`: UNIX seconds when the request was signed
+//! - `X-Emissary-Signature`: `v1=<hex HMAC-SHA256(secret, "<timestamp>.<raw body>")>`
+//!
+//! Receivers should recompute the signature over the raw body and reject requests
+//! whose timestamp is outside a small tolerance (see `verify_signature`), which
+//! blocks replay of captured requests.
+//!
+//! An `alert_retracted` body repeats the alert (with `retracted_at` set) after its
+//! triggering event was reorged out of the chain; receivers should undo anything
+//! they did for the original `alert.id`.
+//!
+//! Channels are verified with a challenge: the endpoint must answer a `verification`
+//! event by echoing its `challenge` value in the response body.
+//!
+//! Unless private targets are allowed, the host is resolved once, every address is
+//! checked, and the request is pinned to a checked address, so a DNS answer that
+//! changes between the check and the connection cannot reach an internal service.
+//! Redirects are never followed.
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use flare_common::net::is_internal_ip;
+use flare_common::signing;
+use flare_common::types::{Alert, ChannelType, DecodedEvent, NotificationKind};
+
+use crate::sender::{Delivery, DeliveryError, NotificationSender, SendFuture, error_for_status};
+
+/// Version of the JSON body schema; bumped on breaking changes.
+pub const WEBHOOK_BODY_VERSION: u32 = 1;
+
+pub const EVENT_HEADER: &str = "X-Emissary-Event";
+pub const DELIVERY_HEADER: &str = "X-Emissary-Delivery";
+pub const TIMESTAMP_HEADER: &str = "X-Emissary-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-Emissary-Signature";
+
+/// Timeout for a single webhook request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Versioned body POSTed for each alert.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookBody {
+    pub version: u32,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub delivery_id: Uuid,
+    pub subscription_id: Uuid,
+    /// True when sent by the replay tool rather than the delivery pipeline
+    pub replay: bool,
+    pub alert: serde_json::Value,
+    pub event: serde_json::Value,
+}
+
+impl WebhookBody {
+    /// Build an alert body. Enum fields use the same snake_case strings as the API.
+    pub fn alert(delivery_id: Uuid, alert: &Alert, event: &DecodedEvent, replay: bool) -> Self {
+        Self {
+            version: WEBHOOK_BODY_VERSION,
+            kind: "alert",
+            delivery_id,
+            subscription_id: alert.subscription_id,
+            replay,
+            alert: json!({
+                "id": alert.id,
+                "severity": alert.severity.to_string(),
+                "message": alert.message,
+                "triggered_at": alert.triggered_at,
+                "retracted_at": alert.retracted_at,
+            }),
+            event: json!({
+                "tx_hash": event.tx_hash,
+                "log_index": event.log_index,
+                "block_number": event.block_number,
+                "block_timestamp": event.block_timestamp,
+                "chain": event.chain.to_string(),
+                "address": event.address,
+                "event_type": event.event_type.to_string(),
+                "decoded_data": event.decoded_data,
+            }),
+        }
+    }
+
+    /// Build a body announcing that a previously sent alert was retracted.
+    pub fn retraction(delivery_id: Uuid, alert: &Alert, event: &DecodedEvent) -> Self {
+        Self {
+            kind: "alert_retracted",
+            ..Self::alert(delivery_id, alert, event, false)
+        }
+    }
+}
+
+/// Compute the `X-Emissary-Signature` header value for a body signed at `timestamp`.
+pub fn signature_header(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    format!(
+        "v1={}",
+        signing::sign(secret.as_bytes(), &signed_message(timestamp, body))
+    )
+}
+
+/// Verify a received webhook: the signature must match and the timestamp must be
+/// within `tolerance` of `now`.
+pub fn verify_signature(
+    secret: &str,
+    timestamp: i64,
+    body: &[u8],
+    signature_header: &str,
+    tolerance: Duration,
+    now: DateTime<Utc>,
+) -> bool {
+    let fresh = (now.timestamp() - timestamp).unsigned_abs() <= tolerance.as_secs();
+    let Some(signature) = signature_header.strip_prefix("v1=") else {
+        return false;
+    };
+    fresh
+        && signing::verify(
+            secret.as_bytes(),
+            &signed_message(timestamp, body),
+            signature,
+        )
+}
+
+fn signed_message(timestamp: i64, body: &[u8]) -> Vec<u8> {
+    let mut message = format!("{}.", timestamp).into_bytes();
+    message.extend_from_slice(body);
+    message
+}
+
+/// Posts signed alert payloads to user-configured URLs.
+pub struct WebhookSender {
+    client: reqwest::Client,
+    allow_private_targets: bool,
+}
+
+impl WebhookSender {
+    /// Create a sender. Unless `allow_private_targets` is set, URLs resolving to
+    /// loopback, private or link-local addresses are refused.
+    pub fn new(allow_private_targets: bool) -> Self {
+        Self {
+            client: Self::client_builder().build().unwrap_or_default(),
+            allow_private_targets,
+        }
+    }
+
+    fn client_builder() -> reqwest::ClientBuilder {
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(reqwest::redirect::Policy::none())
+    }
+
+    /// Sign and POST a body, returning the response on 2xx.
+    pub async fn post(
+        &self,
+        url: &str,
+        secret: &str,
+        kind: &str,
+        delivery_id: Uuid,
+        body: &impl Serialize,
+    ) -> Result<reqwest::Response, DeliveryError> {
+        let (url, pinned) = self.check_target(url).await?;
+        let body = serde_json::to_vec(body)
+            .map_err(|e| DeliveryError::InvalidConfig(format!("unserializable body: {}", e)))?;
+        let timestamp = Utc::now().timestamp();
+
+        // Connect to the address that was checked, not whatever the host resolves to next
+        let pinned_client;
+        let client = match (pinned, url.host_str()) {
+            (Some(addr), Some(host)) => {
+                pinned_client = Self::client_builder().resolve(host, addr).build()?;
+                &pinned_client
+            }
+            _ => &self.client,
+        };
+
+        let response = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, kind)
+            .header(DELIVERY_HEADER, delivery_id.to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature_header(secret, timestamp, &body))
+            .body(body)
+            .send()
+            .await?;
+
+        error_for_status(response).await
+    }
+
+    /// Send a verification challenge; succeeds only if the endpoint echoes it back.
+    pub async fn challenge(
+        &self,
+        url: &str,
+        secret: &str,
+        challenge: &str,
+    ) -> Result<(), DeliveryError> {
+        let delivery_id = Uuid::new_v4();
+        let body = json!({
+            "version": WEBHOOK_BODY_VERSION,
+            "type": "verification",
+            "delivery_id": delivery_id,
+            "challenge": challenge,
+        });
+
+        let response = self
+            .post(url, secret, "verification", delivery_id, &body)
+            .await?;
+        let status = response.status().as_u16();
+        let text = response.text().await.unwrap_or_default();
+
+        if text.contains(challenge) {
+            Ok(())
+        } else {
+            Err(DeliveryError::Rejected {
+                status,
+                body: "response did not echo the verification challenge".to_string(),
+            })
+        }
+    }
+
+    /// Parse the URL and refuse internal targets unless explicitly allowed.
+    ///
+    /// Returns the checked address to pin the connection to, or `None` when private
+    /// targets are allowed and no check is made. A host that doesn't resolve may be
+    /// a passing DNS failure, so it is reported as retryable.
+    async fn check_target(
+        &self,
+        url: &str,
+    ) -> Result<(reqwest::Url, Option<SocketAddr>), DeliveryError> {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| DeliveryError::InvalidConfig(format!("invalid webhook url: {}", e)))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(DeliveryError::InvalidConfig(
+                "webhook url must be http(s)".to_string(),
+            ));
+        }
+        if self.allow_private_targets {
+            return Ok((parsed, None));
+        }
+
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| DeliveryError::InvalidConfig("webhook url has no host".to_string()))?;
+        let port = parsed.port_or_known_default().unwrap_or(443);
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.trim_matches(['[', ']']), port))
+            .await
+            .map_err(|e| DeliveryError::Unavailable(format!("cannot resolve {}: {}", host, e)))?
+            .collect();
+
+        for addr in &addrs {
+            if is_internal_ip(addr.ip()) {
+                return Err(DeliveryError::InvalidConfig(format!(
+                    "webhook host {} resolves to internal address {}",
+                    host,
+                    addr.ip()
+                )));
+            }
+        }
+        let pinned = addrs.first().copied().ok_or_else(|| {
+            DeliveryError::Unavailable(format!("{} resolves to no addresses", host))
+        })?;
+        Ok((parsed, Some(pinned)))
+    }
+}
+
+impl NotificationSender for WebhookSender {
+    fn channel_type(&self) -> ChannelType {
+        ChannelType::Webhook
+    }
+
+    fn send<'a>(&'a self, delivery: &'a Delivery) -> SendFuture<'a> {
+        Box::pin(async move {
+            let (url, secret) = webhook_config(&delivery.channel.config)?;
+            let body = match delivery.notification.kind {
+                NotificationKind::Alert => WebhookBody::alert(
+                    delivery.notification.id,
+                    &delivery.alert,
+                    &delivery.event,
+                    false,
+                ),
+                NotificationKind::Retraction => WebhookBody::retraction(
+                    delivery.notification.id,
+                    &delivery.alert,
+                    &delivery.event,
+                ),
+            };
+            self.post(url, secret, body.kind, body.delivery_id, &body)
+                .await?;
+            Ok(())
+        })
+    }
+}
+
+/// Extract `(url, secret)` from a webhook channel's config.
+pub fn webhook_config(config: &serde_json::Value) -> Result<(&str, &str), DeliveryError> {
+    let url = config
+        .get("url")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| DeliveryError::InvalidConfig("missing url".to_string()))?;
+    let secret = config
+        .get("secret")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| DeliveryError::InvalidConfig("missing secret".to_string()))?;
+    Ok((url, secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{Captured, delivery, spawn_stub};
+    use axum::Router;
+    use axum::body::Bytes;
+    use axum::extract::State;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+
+    const SECRET: &str = "whsec_test_secret_value";
+
+    /// Stub receiver that verifies signatures like a real consumer would and
+    /// echoes verification challenges.
+    async fn stub_receiver() -> (String, Captured) {
+        async fn receive(
+            State(captured): State<Captured>,
+            headers: HeaderMap,
+            body: Bytes,
+        ) -> (axum::http::StatusCode, String) {
+            let timestamp: i64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+            let signature = headers[SIGNATURE_HEADER].to_str().unwrap();
+            if !verify_signature(
+                SECRET,
+                timestamp,
+                &body,
+                signature,
+                Duration::from_secs(300),
+                Utc::now(),
+            ) {
+                return (axum::http::StatusCode::UNAUTHORIZED, String::new());
+            }
+
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            let event = headers[EVENT_HEADER].to_str().unwrap().to_string();
+            captured.lock().unwrap().push((event, json.clone()));
+            let reply = json["challenge"].as_str().unwrap_or("ok").to_string();
+            (axum::http::StatusCode::OK, reply)
+        }
+
+        let captured = Captured::default();
+        let router = Router::new()
+            .route("/hook", post(receive))
+            .with_state(captured.clone());
+        (format!("{}/hook", spawn_stub(router).await), captured)
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_signed_versioned_body() {
+        let (url, captured) = stub_receiver().await;
+        let sender = WebhookSender::new(true);
+
+        let delivery = delivery(
+            ChannelType::Webhook,
+            json!({ "url": url, "secret": SECRET }),
+        );
+        sender.send(&delivery).await.unwrap();
+
+        let captured = captured.lock().unwrap();
+        assert_eq!(captured.len(), 1);
+        let (event, body) = &captured[0];
+        assert_eq!(event, "alert");
+        assert_eq!(body["version"], WEBHOOK_BODY_VERSION);
+        assert_eq!(body["type"], "alert");
+        assert_eq!(body["delivery_id"], delivery.notification.id.to_string());
+        assert_eq!(
+            body["subscription_id"],
+            delivery.alert.subscription_id.to_string()
+        );
+        assert_eq!(body["replay"], false);
+        assert_eq!(body["alert"]["severity"], "critical");
+        assert_eq!(body["event"]["event_type"], "liquidation_started");
+        assert_eq!(body["event"]["chain"], "flare");
+    }
+
+    #[tokio::test]
+    async fn test_retraction_is_sent_as_alert_retracted() {
+        let (url, captured) = stub_receiver().await;
+        let sender = WebhookSender::new(true);
+
+        let mut delivery = delivery(
+            ChannelType::Webhook,
+            json!({ "url": url, "secret": SECRET }),
+        );
+        delivery.notification.kind = NotificationKind::Retraction;
+        delivery.alert.retracted_at = Some(Utc::now());
+        sender.send(&delivery).await.unwrap();
+
+        let captured = captured.lock().unwrap();
+        let (event, body) = &captured[0];
+        assert_eq!(event, "alert_retracted");
+        assert_eq!(body["type"], "alert_retracted");
+        assert_eq!(body["alert"]["id"], delivery.alert.id.to_string());
+        assert!(body["alert"]["retracted_at"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_wrong_secret_is_rejected_by_receiver() {
+        let (url, _) = stub_receiver().await;
+        let sender = WebhookSender::new(true);
+
+        let delivery = delivery(
+            ChannelType::Webhook,
+            json!({ "url": url, "secret": "some-other-secret" }),
+        );
+        let err = sender.send(&delivery).await.unwrap_err();
+        assert!(matches!(err, DeliveryError::Rejected { status: 401, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_challenge_requires_echo() {
+        let (url, _) = stub_receiver().await;
+        let sender = WebhookSender::new(true);
+        sender.challenge(&url, SECRET, "ABCD1234").await.unwrap();
+
+        // An endpoint that answers 2xx without echoing does not pass
+        let router = Router::new().route("/hook", post(|| async { "ok" }));
+        let other = format!("{}/hook", spawn_stub(router).await);
+        assert!(sender.challenge(&other, SECRET, "ABCD1234").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_private_targets_refused_by_default() {
+        let sender = WebhookSender::new(false);
+        let by_ip = delivery(
+            ChannelType::Webhook,
+            json!({ "url": "http://127.0.0.1:9/hook", "secret": SECRET }),
+        );
+        let err = sender.send(&by_ip).await.unwrap_err();
+        assert!(matches!(err, DeliveryError::InvalidConfig(_)));
+
+        // Names are checked by what they resolve to
+        let by_name = delivery(
+            ChannelType::Webhook,
+            json!({ "url": "http://localhost:9/hook", "secret": SECRET }),
+        );
+        let err = sender.send(&by_name).await.unwrap_err();
+        assert!(matches!(err, DeliveryError::InvalidConfig(_)));
+    }
+
+    #[tokio::test]
+    async fn test_redirects_are_not_followed() {
+        let target = Captured::default();
+        let recorded = target.clone();
+        let router = Router::new().route(
+            "/internal",
+            post(move || async move {
+                recorded.lock().unwrap().push(Default::default());
+                "ok"
+            }),
+        );
+        let internal = format!("{}/internal", spawn_stub(router).await);
+        let router = Router::new().route(
+            "/hook",
+            post(move || async move {
+                (
+                    axum::http::StatusCode::TEMPORARY_REDIRECT,
+                    [(axum::http::header::LOCATION, internal)],
+                )
+            }),
+        );
+        let url = format!("{}/hook", spawn_stub(router).await);
+
+        let sender = WebhookSender::new(true);
+        let delivery = delivery(
+            ChannelType::Webhook,
+            json!({ "url": url, "secret": SECRET }),
+        );
+        assert!(sender.send(&delivery).await.is_err());
+        assert!(target.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_verify_signature_rejects_stale_and_tampered() {
+        let now = Utc::now();
+        let ts = now.timestamp();
+        let body = br#"{"a":1}"#;
+        let header = signature_header(SECRET, ts, body);
+        let tolerance = Duration::from_secs(300);
+
+        assert!(verify_signature(SECRET, ts, body, &header, tolerance, now));
+        assert!(!verify_signature(
+            SECRET,
+            ts,
+            br#"{"a":2}"#,
+            &header,
+            tolerance,
+            now
+        ));
+        assert!(!verify_signature(
+            SECRET,
+            ts + 1,
+            body,
+            &header,
+            tolerance,
+            now
+        ));
+        assert!(!verify_signature(
+            SECRET,
+            ts,
+            body,
+            &header,
+            tolerance,
+            now + chrono::Duration::seconds(301)
+        ));
+    }
+}
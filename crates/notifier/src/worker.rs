@@ -0,0 +1,381 @@
+//! Notification delivery worker.
+//!
+//! Consumes notification IDs from the Redis queue, loads the notification with its
+//! channel, alert and triggering event, hands it to the matching `NotificationSender`,
+//! and records the outcome on the `notifications` row.
+//!
+//! Notifications for unverified channels are never sent; they are dead-lettered.
+//! A `retraction` notification is rendered as a follow-up to its (retracted) alert.
+//!
+//! Failed attempts are retried according to the `RetryPolicy`: a transient failure
+//! moves the row to `failed` with `next_attempt_at` set, and `requeue_due` puts it
+//! back on the queue once due. Permanent failures (provider 4xx, unusable channel)
+//! and notifications that run out of attempts move to `dead_letter`.
+//!
+//! Each delivery claims its `pending`/`failed` row by taking a lease on it in a short
+//! transaction, sends outside any transaction, and records the outcome in a second
+//! one guarded by the lease. Duplicate queue entries (e.g., from the
+//! stale-notification sweep) and concurrent workers never deliver the same
+//! notification twice while the lease holds, and slow providers (rate-limit waits)
+//! never hold row locks or pooled connections. If a worker dies mid-send, the lease
+//! expires and the notification can be claimed again.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use redis::aio::ConnectionManager;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use flare_common::queue;
+use flare_common::types::{
+    Alert, DecodedEvent, DeliveryStatus, IndexedEvent, Notification, NotificationChannel,
+    NotificationKind,
+};
+use flare_engine::processor::EventProcessor;
+use flare_engine::retraction::retraction_payload;
+
+use crate::retry::RetryPolicy;
+use crate::sender::{Delivery, DeliveryError, SenderRegistry};
+
+/// How long a single `BRPOP` blocks before the worker loops again.
+const QUEUE_POLL_TIMEOUT_SECS: f64 = 5.0;
+
+/// How long a claimed notification stays reserved for its worker. Must outlast a
+/// send, including provider rate-limit retries.
+const DELIVERY_LEASE: Duration = Duration::from_secs(300);
+
+/// Maximum number of due retries re-enqueued per sweep.
+const RETRY_SWEEP_LIMIT: i64 = 1000;
+
+/// A single queue-consuming delivery worker.
+pub struct NotificationWorker {
+    id: usize,
+    pool: PgPool,
+    senders: Arc<SenderRegistry>,
+    retry_policy: RetryPolicy,
+}
+
+impl NotificationWorker {
+    pub fn new(id: usize, pool: PgPool, senders: Arc<SenderRegistry>) -> Self {
+        Self {
+            id,
+            pool,
+            senders,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Override the retry policy (default: `RetryPolicy::default()`).
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Start consuming the queue. Runs indefinitely until the task is cancelled.
+    ///
+    /// Each worker needs its own Redis connection because `BRPOP` blocks the
+    /// connection while waiting.
+    pub async fn run(&self, mut redis: ConnectionManager) -> anyhow::Result<()> {
+        tracing::info!(worker = self.id, "Notification worker started");
+
+        loop {
+            let next = queue::dequeue_notification(&mut redis, QUEUE_POLL_TIMEOUT_SECS).await;
+
+            match next {
+                Ok(Some(notification_id)) => {
+                    if let Err(e) = self.deliver(notification_id).await {
+                        tracing::error!(
+                            worker = self.id,
+                            notification_id = %notification_id,
+                            error = %e,
+                            "Failed to process notification"
+                        );
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!(worker = self.id, error = %e, "Queue read failed, backing off");
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+
+    /// Deliver a single notification by ID.
+    ///
+    /// Returns the resulting status, or `None` if the notification was not awaiting
+    /// delivery (already sent, dead-lettered, or claimed by another worker).
+    pub async fn deliver(&self, notification_id: Uuid) -> anyhow::Result<Option<DeliveryStatus>> {
+        let lease_id = Uuid::new_v4();
+        let mut tx = self.pool.begin().await?;
+        let Some(delivery) = Self::load_delivery(&mut tx, notification_id, lease_id).await? else {
+            return Ok(None);
+        };
+        tx.commit().await?;
+
+        let result = if !delivery.channel.verified {
+            Err(DeliveryError::Unverified(delivery.channel.id))
+        } else {
+            match self.senders.get(delivery.channel.channel_type) {
+                Some(sender) => sender.send(&delivery).await,
+                None => Err(DeliveryError::NoSender(delivery.channel.channel_type)),
+            }
+        };
+
+        let attempts = delivery.notification.attempts + 1;
+
+        let (status, recorded) = match result {
+            Ok(()) => {
+                // Recorded even if the alert was retracted mid-send: it did go out, and
+                // the retraction has already queued a notice for it
+                let recorded = sqlx::query(
+                    r#"
+                    UPDATE notifications
+                    SET status = $1, attempts = $2, sent_at = NOW(),
+                        next_attempt_at = NULL, error_detail = NULL,
+                        lease_id = NULL, lease_expires_at = NULL
+                    WHERE id = $3 AND lease_id = $4
+                    "#,
+                )
+                .bind(DeliveryStatus::Sent.to_string())
+                .bind(attempts)
+                .bind(notification_id)
+                .bind(lease_id)
+                .execute(&self.pool)
+                .await?;
+
+                tracing::info!(
+                    notification_id = %notification_id,
+                    channel_type = ?delivery.channel.channel_type,
+                    attempts,
+                    "Notification delivered"
+                );
+                (DeliveryStatus::Sent, recorded)
+            }
+            Err(e) if e.is_permanent() || !self.retry_policy.can_retry(attempts as u32) => {
+                let recorded = sqlx::query(
+                    r#"
+                    UPDATE notifications
+                    SET status = $1, attempts = $2, next_attempt_at = NULL, error_detail = $3,
+                        lease_id = NULL, lease_expires_at = NULL
+                    WHERE id = $4 AND lease_id = $5
+                      AND status IN ('pending', 'failed')
+                    "#,
+                )
+                .bind(DeliveryStatus::DeadLetter.to_string())
+                .bind(attempts)
+                .bind(e.to_string())
+                .bind(notification_id)
+                .bind(lease_id)
+                .execute(&self.pool)
+                .await?;
+
+                tracing::warn!(
+                    notification_id = %notification_id,
+                    channel_type = ?delivery.channel.channel_type,
+                    attempts,
+                    permanent = e.is_permanent(),
+                    error = %e,
+                    "Notification dead-lettered"
+                );
+                (DeliveryStatus::DeadLetter, recorded)
+            }
+            Err(e) => {
+                let delay = self
+                    .retry_policy
+                    .next_delay(attempts as u32, e.retry_after());
+
+                let recorded = sqlx::query(
+                    r#"
+                    UPDATE notifications
+                    SET status = $1, attempts = $2, error_detail = $3,
+                        next_attempt_at = NOW() + make_interval(secs => $4),
+                        lease_id = NULL, lease_expires_at = NULL
+                    WHERE id = $5 AND lease_id = $6
+                      AND status IN ('pending', 'failed')
+                    "#,
+                )
+                .bind(DeliveryStatus::Failed.to_string())
+                .bind(attempts)
+                .bind(e.to_string())
+                .bind(delay.as_secs_f64())
+                .bind(notification_id)
+                .bind(lease_id)
+                .execute(&self.pool)
+                .await?;
+
+                tracing::warn!(
+                    notification_id = %notification_id,
+                    channel_type = ?delivery.channel.channel_type,
+                    attempts,
+                    retry_in_secs = delay.as_secs(),
+                    error = %e,
+                    "Notification delivery failed, retry scheduled"
+                );
+                (DeliveryStatus::Failed, recorded)
+            }
+        };
+
+        if recorded.rows_affected() == 0 {
+            // The lease expired mid-send and another worker claimed the row, or the
+            // alert was retracted and a failed attempt must not revive the row
+            tracing::warn!(
+                notification_id = %notification_id,
+                ?status,
+                "Delivery outcome not recorded: lease lost or notification cancelled"
+            );
+        }
+
+        Ok(Some(status))
+    }
+
+    /// Lease a pending or retrying notification to `lease_id` and load everything
+    /// needed to deliver it. Rows leased by another worker are skipped.
+    async fn load_delivery(
+        tx: &mut Transaction<'_, Postgres>,
+        notification_id: Uuid,
+        lease_id: Uuid,
+    ) -> anyhow::Result<Option<Delivery>> {
+        let notification: Option<Notification> = sqlx::query_as(
+            r#"
+            UPDATE notifications
+            SET lease_id = $2, lease_expires_at = NOW() + make_interval(secs => $3)
+            WHERE id = $1 AND status IN ('pending', 'failed')
+              AND (lease_expires_at IS NULL OR lease_expires_at < NOW())
+            RETURNING *
+            "#,
+        )
+        .bind(notification_id)
+        .bind(lease_id)
+        .bind(DELIVERY_LEASE.as_secs_f64())
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        let Some(notification) = notification else {
+            return Ok(None);
+        };
+
+        let channel: NotificationChannel =
+            sqlx::query_as("SELECT * FROM notification_channels WHERE id = $1")
+                .bind(notification.channel_id)
+                .fetch_one(&mut **tx)
+                .await?;
+
+        let (alert, event) = load_alert(tx, notification.alert_id).await?;
+        let payload = match notification.kind {
+            NotificationKind::Alert => EventProcessor::translate_event(&event),
+            NotificationKind::Retraction => retraction_payload(&alert, &event),
+        };
+
+        Ok(Some(Delivery {
+            notification,
+            channel,
+            alert,
+            event,
+            payload,
+        }))
+    }
+}
+
+/// Load an alert and the decoded event that triggered it.
+pub(crate) async fn load_alert(
+    conn: &mut PgConnection,
+    alert_id: Uuid,
+) -> anyhow::Result<(Alert, DecodedEvent)> {
+    let alert: Alert = sqlx::query_as(
+        "SELECT id, subscription_id, event_id, severity, message, triggered_at, retracted_at FROM alerts WHERE id = $1",
+    )
+    .bind(alert_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let event: IndexedEvent = sqlx::query_as(
+        r#"
+        SELECT id, tx_hash, log_index, block_number, block_timestamp, chain, address,
+               event_type, decoded_data, is_reorged
+        FROM indexed_events
+        WHERE id = $1
+        "#,
+    )
+    .bind(alert.event_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok((alert, DecodedEvent::from(event)))
+}
+
+/// Re-enqueue notifications that have been pending for longer than `older_than`.
+///
+/// Covers notifications whose queue push failed or whose queue entry was lost
+/// (e.g., Redis restart). Returns the number of notifications re-enqueued.
+pub async fn requeue_stale(
+    pool: &PgPool,
+    redis: &mut ConnectionManager,
+    older_than: Duration,
+) -> anyhow::Result<usize> {
+    let stale: Vec<(Uuid,)> = sqlx::query_as(
+        r#"
+        SELECT id FROM notifications
+        WHERE status = 'pending'
+          AND created_at < NOW() - make_interval(secs => $1)
+        ORDER BY created_at
+        "#,
+    )
+    .bind(older_than.as_secs_f64())
+    .fetch_all(pool)
+    .await?;
+
+    for (id,) in &stale {
+        queue::enqueue_notification(redis, *id).await?;
+    }
+
+    if !stale.is_empty() {
+        tracing::info!(
+            count = stale.len(),
+            "Re-enqueued stale pending notifications"
+        );
+    }
+
+    Ok(stale.len())
+}
+
+/// Re-enqueue failed notifications whose retry is due.
+///
+/// Each due row's `next_attempt_at` is pushed out by `lease` as it is enqueued, so
+/// later sweeps don't enqueue it again while it waits in the queue; if the queue
+/// entry is lost, the row becomes due again once the lease expires.
+pub async fn requeue_due(
+    pool: &PgPool,
+    redis: &mut ConnectionManager,
+    lease: Duration,
+) -> anyhow::Result<usize> {
+    let due: Vec<(Uuid,)> = sqlx::query_as(
+        r#"
+        UPDATE notifications
+        SET next_attempt_at = NOW() + make_interval(secs => $1)
+        WHERE id IN (
+            SELECT id FROM notifications
+            WHERE status = 'failed' AND next_attempt_at <= NOW()
+            ORDER BY next_attempt_at
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id
+        "#,
+    )
+    .bind(lease.as_secs_f64())
+    .bind(RETRY_SWEEP_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    for (id,) in &due {
+        queue::enqueue_notification(redis, *id).await?;
+    }
+
+    if !due.is_empty() {
+        tracing::info!(count = due.len(), "Re-enqueued notifications due for retry");
+    }
+
+    Ok(due.len())
+}
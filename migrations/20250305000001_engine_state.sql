@@ -0,0 +1,12 @@
+-- FlareEmissary Migration 003: Engine processing cursor
+-- Tracks how far each engine worker has processed `indexed_events` so a
+-- restart neither re-alerts on nor skips already persisted events.
+
+CREATE TABLE IF NOT EXISTS engine_state (
+    worker TEXT PRIMARY KEY,
+    last_event_id BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One alert per (subscription, event) so replaying an event after a crash is idempotent
+CREATE UNIQUE INDEX IF NOT EXISTS idx_alerts_subscription_event ON alerts(subscription_id, event_id);
@@ -0,0 +1,12 @@
+-- FlareEmissary Migration 004: Notification channel verification codes
+-- One-time codes proving the user controls a channel's destination. A channel only
+-- flips to `verified = true` once its code is presented back (e.g., sent to the bot).
+
+CREATE TABLE IF NOT EXISTS channel_verifications (
+    code TEXT PRIMARY KEY,
+    channel_id UUID NOT NULL REFERENCES notification_channels(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_channel_verifications_channel ON channel_verifications(channel_id);
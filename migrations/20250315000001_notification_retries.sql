@@ -0,0 +1,15 @@
+-- FlareEmissary Migration 005: Notification retries and dead-letter status
+-- status is now 'pending' | 'sent' | 'failed' (retry scheduled at next_attempt_at)
+-- | 'dead_letter' (permanent failure or attempts exhausted; re-driven via the API).
+
+ALTER TABLE notifications
+    ADD COLUMN IF NOT EXISTS attempts INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS next_attempt_at TIMESTAMPTZ;
+
+-- Retry scheduler scans failed notifications that are due
+CREATE INDEX IF NOT EXISTS idx_notifications_retry_due
+    ON notifications(next_attempt_at) WHERE status = 'failed';
+
+-- Dead-letter listing
+CREATE INDEX IF NOT EXISTS idx_notifications_dead_letter
+    ON notifications(created_at) WHERE status = 'dead_letter';
@@ -0,0 +1,18 @@
+-- FlareEmissary Migration 006: Historical backfill progress
+-- `flare-indexer backfill --from N --to M` tracks its position per requested range,
+-- independently of the live cursor in `indexer_state`, so a backfill can run
+-- alongside live indexing and resume where it stopped.
+
+CREATE TABLE IF NOT EXISTS backfill_progress (
+    chain TEXT NOT NULL,
+    from_block BIGINT NOT NULL,
+    to_block BIGINT NOT NULL,
+    next_block BIGINT NOT NULL,
+    completed_at TIMESTAMPTZ,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (chain, from_block, to_block)
+);
+
+-- Backfilled history is stored for queries but must not raise alerts
+ALTER TABLE indexed_events ADD COLUMN IF NOT EXISTS backfilled BOOLEAN NOT NULL DEFAULT false;
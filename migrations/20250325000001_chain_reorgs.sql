@@ -0,0 +1,16 @@
+-- FlareEmissary Migration 007: Reorg log
+-- Every reorg the live poller handles is recorded here. Reorgs deeper than the
+-- detector window (`beyond_window`) could only be rolled back partially and
+-- need an operator to re-index from an older block.
+
+CREATE TABLE IF NOT EXISTS chain_reorgs (
+    id BIGSERIAL PRIMARY KEY,
+    chain TEXT NOT NULL,
+    fork_block BIGINT NOT NULL,
+    detected_at_block BIGINT NOT NULL,
+    beyond_window BOOLEAN NOT NULL DEFAULT false,
+    events_reorged BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_chain_reorgs_chain ON chain_reorgs (chain, created_at DESC);
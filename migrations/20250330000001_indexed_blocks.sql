@@ -0,0 +1,17 @@
+-- FlareEmissary Migration 008: Indexed block hashes
+-- The live poller records every block it indexes, so the reorg detector window can
+-- be restored after a restart. Blocks replaced by a reorg are kept with
+-- `is_canonical = false`, which leaves an audit trail of the chain as indexed.
+
+CREATE TABLE IF NOT EXISTS indexed_blocks (
+    chain TEXT NOT NULL,
+    block_number BIGINT NOT NULL,
+    block_hash TEXT NOT NULL,
+    parent_hash TEXT NOT NULL,
+    is_canonical BOOLEAN NOT NULL DEFAULT true,
+    indexed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (chain, block_number, block_hash)
+);
+
+CREATE INDEX IF NOT EXISTS idx_indexed_blocks_canonical
+    ON indexed_blocks (chain, block_number DESC) WHERE is_canonical;
@@ -0,0 +1,15 @@
+-- FlareEmissary Migration 009: Alert retraction on reorg
+-- Alerts whose triggering event was reorged out are marked retracted. Their
+-- undelivered notifications move to `cancelled`, and channels that already got the
+-- alert can receive a follow-up notification of kind `retraction`.
+
+ALTER TABLE alerts ADD COLUMN IF NOT EXISTS retracted_at TIMESTAMPTZ;
+
+-- 'alert' | 'retraction'
+ALTER TABLE notifications ADD COLUMN IF NOT EXISTS kind TEXT NOT NULL DEFAULT 'alert';
+
+-- Set by the engine once alerts affected by the reorg have been retracted
+ALTER TABLE chain_reorgs ADD COLUMN IF NOT EXISTS alerts_checked_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_alerts_event_active ON alerts(event_id) WHERE retracted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_chain_reorgs_unchecked ON chain_reorgs(id) WHERE alerts_checked_at IS NULL;
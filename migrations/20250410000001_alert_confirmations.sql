@@ -0,0 +1,8 @@
+-- FlareEmissary Migration 010: Confirmation depth for alerts
+-- Subscriptions with `threshold_config.confirmations = N` hold their notifications
+-- in `held` until the indexed chain head is N blocks past the triggering event.
+-- The depth is copied onto the alert so later config edits don't move the goalposts.
+
+ALTER TABLE alerts ADD COLUMN IF NOT EXISTS confirmations INT NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_notifications_held ON notifications(alert_id) WHERE status = 'held';
@@ -0,0 +1,18 @@
+-- FlareEmissary Migration 011: Contract address history
+-- The poller re-resolves FlareContractRegistry periodically and whenever the
+-- registry changes. Each resolved address is recorded with the block range it was
+-- used for, so history can be re-indexed against the contracts active at the time.
+-- `to_block` is NULL for the address currently in use.
+
+CREATE TABLE IF NOT EXISTS contract_address_history (
+    chain TEXT NOT NULL,
+    contract_name TEXT NOT NULL,
+    address TEXT NOT NULL,
+    from_block BIGINT NOT NULL,
+    to_block BIGINT,
+    resolved_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (chain, contract_name, from_block)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_contract_address_history_current
+    ON contract_address_history (chain, contract_name) WHERE to_block IS NULL;
@@ -0,0 +1,14 @@
+-- FlareEmissary Migration 012: FTSO price ticks written by the indexer
+-- Ticks are derived from FtsoFeedPublished and FastUpdateFeeds events and written
+-- in the same transaction. Re-indexing a block must not duplicate them, and one
+-- FastUpdateFeeds log carries many feeds, so a tick is keyed by its log and feed.
+-- `price` is the feed value scaled by `decimals`.
+
+ALTER TABLE ftso_price_ticks ADD COLUMN IF NOT EXISTS log_index BIGINT NOT NULL DEFAULT 0;
+
+-- Includes block_timestamp so it stays valid once the table is a hypertable
+CREATE UNIQUE INDEX IF NOT EXISTS idx_price_ticks_log_feed
+    ON ftso_price_ticks(chain, tx_hash, log_index, feed_id, block_timestamp);
+
+-- Reorg rollback deletes ticks by chain and block
+CREATE INDEX IF NOT EXISTS idx_price_ticks_chain_block ON ftso_price_ticks(chain, block_number);
@@ -0,0 +1,7 @@
+-- FlareEmissary Migration 013: FAsset events by agent
+-- The engine's CR calculator rebuilds an agent vault from its FAsset events when a
+-- CR subscription for the agent appears. Decoded agent addresses are lowercase hex.
+
+CREATE INDEX IF NOT EXISTS idx_events_agent
+    ON indexed_events(chain, (decoded_data->>'agent'))
+    WHERE decoded_data ? 'agent';
@@ -0,0 +1,31 @@
+-- FlareEmissary Migration 014: FAsset agent vault health snapshots
+-- Written by the engine's CR calculator at most once per ENGINE_SNAPSHOT_INTERVAL_SECS
+-- of block time per agent, for the vault health API and dashboard. Collateral is in
+-- whole tokens; USD values and ratios are NULL while a price is unknown.
+-- Snapshots are derived data: after a reorg they are not rewritten, the next ones
+-- reflect the canonical chain.
+
+CREATE TABLE IF NOT EXISTS agent_vault_snapshots (
+    id BIGSERIAL PRIMARY KEY,
+    chain TEXT NOT NULL,
+    agent TEXT NOT NULL,
+    fasset TEXT,
+    cr DOUBLE PRECISION,
+    vault_cr DOUBLE PRECISION,
+    pool_cr DOUBLE PRECISION,
+    vault_collateral DOUBLE PRECISION NOT NULL,
+    pool_collateral DOUBLE PRECISION NOT NULL,
+    vault_collateral_usd DOUBLE PRECISION,
+    pool_collateral_usd DOUBLE PRECISION,
+    minted_value_usd DOUBLE PRECISION,
+    minted_lots BIGINT NOT NULL,
+    reserved_lots BIGINT NOT NULL,
+    redeeming_lots BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One snapshot per agent and block time: a replayed trigger doesn't add another
+CREATE UNIQUE INDEX IF NOT EXISTS idx_vault_snapshots_agent_time
+    ON agent_vault_snapshots(chain, agent, recorded_at DESC);
@@ -0,0 +1,9 @@
+-- FlareEmissary Migration 015: Notification delivery leases
+-- A delivery worker claims a notification by taking a lease on it and commits before
+-- calling the provider, so no transaction or pooled connection is held across the
+-- HTTP call. Only the lease holder records the outcome. If the worker dies mid-send,
+-- the lease expires and the notification can be claimed again.
+
+ALTER TABLE notifications
+    ADD COLUMN IF NOT EXISTS lease_id UUID,
+    ADD COLUMN IF NOT EXISTS lease_expires_at TIMESTAMPTZ;
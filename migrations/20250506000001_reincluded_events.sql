@@ -0,0 +1,10 @@
+-- FlareEmissary Migration 016: Re-included events get a new row
+-- An event flagged as reorged that is re-mined on the canonical chain used to be
+-- un-flagged in place, keeping its old id. The engine's cursor had already passed
+-- that id, so the event never alerted. Uniqueness now only applies to canonical
+-- rows: the re-mined event is inserted as a new row (with a new id the engine will
+-- read), and the flagged row stays behind with any alerts retracted for it.
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_events_unique_canonical
+    ON indexed_events(tx_hash, log_index) WHERE NOT is_reorged;
+DROP INDEX IF EXISTS idx_events_unique;
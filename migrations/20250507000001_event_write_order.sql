@@ -0,0 +1,21 @@
+-- FlareEmissary Migration 017: Commit-ordered event ids
+-- The engine tails `indexed_events` by id, but ids are assigned at insert, not
+-- at commit: with concurrent writers (one poller per chain, the engine's own
+-- synthetic events) a lower id could commit after the engine's cursor passed it,
+-- and the event was never processed.
+-- Writers now take a transaction-level lock before inserting, so the next
+-- writer only draws ids once the previous one committed or rolled back. Ids
+-- become visible in order and the cursor never skips a row.
+
+CREATE OR REPLACE FUNCTION lock_indexed_events_writes() RETURNS trigger AS $$
+BEGIN
+    -- Released at commit or rollback; any key unique to this table works
+    PERFORM pg_advisory_xact_lock('indexed_events'::regclass::oid::bigint);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS indexed_events_write_order ON indexed_events;
+CREATE TRIGGER indexed_events_write_order
+    BEFORE INSERT ON indexed_events
+    FOR EACH STATEMENT EXECUTE FUNCTION lock_indexed_events_writes();
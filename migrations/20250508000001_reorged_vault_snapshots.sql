@@ -0,0 +1,8 @@
+-- FlareEmissary Migration 018: Drop vault snapshots of reorged blocks
+-- Snapshots used to outlive a reorg: the health API kept serving values computed
+-- from orphaned blocks, and their (chain, agent, recorded_at) key blocked the
+-- canonical re-snapshot at the same block time. The indexer now deletes them from
+-- the fork block on when it rewinds, like price ticks; this index serves that.
+
+CREATE INDEX IF NOT EXISTS idx_vault_snapshots_chain_block
+    ON agent_vault_snapshots(chain, block_number);